@@ -1 +1,11 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod node;
+pub mod owned_node;
+pub mod size_error;
+pub mod typed_node;
+pub mod validation_error;
+#[cfg(feature = "serde")]
+pub mod value;
@@ -1,3 +1,21 @@
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    cmp::Ordering,
+    fmt::{Display, Write},
+};
+
+use token::escape::unescape_json_string;
+
+use crate::{size_error::SizeError, validation_error::ValidationError};
+
 /// Abstract Syntax Tree (AST) node
 ///
 /// ## Description
@@ -22,8 +40,44 @@
 ///     Box::new(Node::Literal("\"type\"")),
 ///     Box::new(Node::Literal("\"lion\""))
 /// )]);
+///
+/// assert_eq!("{\"type\":\"lion\"}", object.to_string());
 ///```
-#[derive(Debug, PartialEq)]
+///
+/// ## Ordering
+///
+/// Nodes have a total order based on JSON value semantics, `null < boolean < number < string <
+/// array < object`, so that sorting features share a single definition. Values of the same type
+/// are compared by value: `false < true`, numbers numerically, strings lexically by their
+/// unquoted content, and arrays and objects element by element. Literals with equal values but a
+/// different spelling, such as `1` and `1.0`, are then ordered by their source so the ordering
+/// agrees with equality.
+///
+/// ```
+/// use ast::node::Node;
+///
+/// let mut values = vec![
+///     Node::Literal("\"a\""),
+///     Node::Literal("10"),
+///     Node::Array(vec![]),
+///     Node::Literal("2"),
+///     Node::Literal("null"),
+/// ];
+///
+/// values.sort();
+///
+/// assert_eq!(
+///     vec![
+///         Node::Literal("null"),
+///         Node::Literal("2"),
+///         Node::Literal("10"),
+///         Node::Literal("\"a\""),
+///         Node::Array(vec![]),
+///     ],
+///     values
+/// );
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub enum Node<'source> {
     Object(Vec<Node<'source>>),
     Property(Box<Node<'source>>, Box<Node<'source>>),
@@ -31,12 +85,1122 @@ pub enum Node<'source> {
     Literal(&'source str),
 }
 
+impl<'source> Node<'source> {
+    /// Find the node at a JSON path such as `$.servers[0].port`
+    ///
+    /// Object keys are matched against their unquoted value and array steps use numeric indices.
+    /// `None` is returned if any step does not match.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let ast = Node::Object(vec![Node::Property(
+    ///     Box::new(Node::Literal("\"ports\"")),
+    ///     Box::new(Node::Array(vec![Node::Literal("80"), Node::Literal("443")])),
+    /// )]);
+    ///
+    /// assert_eq!(Some(&Node::Literal("443")), ast.query("$.ports[1]"));
+    /// assert_eq!(None, ast.query("$.ports[2]"));
+    /// ```
+    pub fn query(&self, path: &str) -> Option<&Node<'source>> {
+        let mut node = self;
+        let mut path = path.strip_prefix('$').unwrap_or(path);
+
+        while !path.is_empty() {
+            if let Some(rest) = path.strip_prefix('[') {
+                let (index, rest) = rest.split_once(']')?;
+                let index = index.parse::<usize>().ok()?;
+
+                node = node.index(index)?;
+
+                path = rest;
+            } else {
+                let rest = path.strip_prefix('.').unwrap_or(path);
+                let (key, rest) = rest.split_at(rest.find(['.', '[']).unwrap_or(rest.len()));
+
+                if key.is_empty() {
+                    return None;
+                }
+
+                node = node.get(key)?;
+
+                path = rest;
+            }
+        }
+
+        Some(node)
+    }
+
+    /// JSON path of `target`, the reverse of `query`
+    ///
+    /// `target` is found by reference, not by value, so it must be a node borrowed from this
+    /// tree. A property and its value share the path of the value. The tree is searched depth
+    /// first with an explicit stack and `None` is returned if `target` is not part of it.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let ast = Node::Object(vec![Node::Property(
+    ///     Box::new(Node::Literal("\"servers\"")),
+    ///     Box::new(Node::Array(vec![Node::Literal("80"), Node::Literal("80")])),
+    /// )]);
+    ///
+    /// let port = ast.query("$.servers[1]").unwrap();
+    ///
+    /// assert_eq!(Some("$.servers[1]".to_string()), ast.path_of(port));
+    /// assert_eq!(None, ast.path_of(&Node::Literal("80")));
+    /// ```
+    pub fn path_of(&self, target: &Node) -> Option<String> {
+        self.paths()
+            .find_map(|(node, path)| core::ptr::eq(node, target).then_some(path))
+    }
+
+    /// Check that every string literal, including keys, decodes to a valid Rust `String`
+    ///
+    /// Literals are kept as raw slices so escape sequences are only decoded on demand. This
+    /// decodes each one with `unescape_json_string` and returns the path and reason of the first
+    /// that fails, such as a lone surrogate in `"\uD800"` within a hand-built AST. The scanner
+    /// already rejects such literals in parsed input.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let ast = Node::Array(vec![Node::Literal("\"\\u0041\""), Node::Literal("\"\\uDE00\"")]);
+    ///
+    /// assert_eq!("$[1]", ast.validate_strings().unwrap_err().path);
+    /// assert_eq!(Ok(()), Node::Literal("\"\\u0041\"").validate_strings());
+    /// ```
+    pub fn validate_strings(&self) -> Result<(), ValidationError> {
+        for (node, path) in self.paths() {
+            let Node::Literal(literal) = node else {
+                continue;
+            };
+
+            if !literal.starts_with('"') {
+                continue;
+            }
+
+            unescape_json_string(literal).map_err(|error| ValidationError {
+                path,
+                literal: literal.to_string(),
+                error,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that the tree has at most `max_nodes` nodes before formatting or minifying it
+    ///
+    /// The parser limits the depth of parsed input but a hand-built AST can be arbitrarily large.
+    /// Every node is counted, including properties and their keys, and the walk stops as soon as
+    /// the limit is exceeded so checking a huge tree is cheap.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let ast = Node::Array(vec![Node::Literal("1"), Node::Literal("2")]);
+    ///
+    /// assert_eq!(Ok(()), ast.validate_size(3));
+    /// assert_eq!(2, ast.validate_size(2).unwrap_err().max_nodes);
+    /// ```
+    pub fn validate_size(&self, max_nodes: usize) -> Result<(), SizeError> {
+        let mut nodes = 0;
+        let mut stack = vec![self];
+
+        while let Some(node) = stack.pop() {
+            nodes += 1;
+
+            if nodes > max_nodes {
+                return Err(SizeError { max_nodes });
+            }
+
+            match node {
+                Node::Object(children) | Node::Array(children) => stack.extend(children.iter()),
+                Node::Property(key, value) => {
+                    stack.push(value);
+                    stack.push(key);
+                }
+                Node::Literal(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stable 64-bit hash of the document's values, ignoring key order, whitespace and spelling
+    ///
+    /// Object properties are hashed in order of their decoded key, numbers by their `f64` value
+    /// with `-0` equal to `0`, and strings by their decoded content, so `{"b": 1.0, "a": "\u0041"}`
+    /// and `{"a":"A","b":1}` share a fingerprint. Array order is significant. The hash is 64-bit
+    /// FNV-1a over a tagged encoding of the tree and does not depend on the platform or Rust
+    /// version, so it can be stored for caching and deduplication. It is not cryptographic.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let property = |key, value| {
+    ///     Node::Property(Box::new(Node::Literal(key)), Box::new(Node::Literal(value)))
+    /// };
+    ///
+    /// let a = Node::Object(vec![property("\"b\"", "1.0"), property("\"a\"", "\"\\u0041\"")]);
+    /// let b = Node::Object(vec![property("\"a\"", "\"A\""), property("\"b\"", "1")]);
+    ///
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// assert_ne!(a.fingerprint(), Node::Object(vec![property("\"a\"", "\"A\"")]).fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut write = |bytes: &[u8]| {
+            for byte in bytes {
+                hash = (hash ^ *byte as u64).wrapping_mul(FNV_PRIME);
+            }
+        };
+
+        let mut stack = vec![self];
+
+        while let Some(node) = stack.pop() {
+            match node {
+                Node::Object(children) | Node::Array(children) => {
+                    let tag = if matches!(node, Node::Object(_)) {
+                        b'{'
+                    } else {
+                        b'['
+                    };
+
+                    write(&[tag]);
+                    write(&(children.len() as u64).to_le_bytes());
+
+                    let mut children: Vec<&Node> = children.iter().collect();
+
+                    if tag == b'{' {
+                        children.sort_by_cached_key(|child| match child {
+                            Node::Property(key, _) => key.decoded(),
+                            _ => Cow::Borrowed(""),
+                        });
+                    }
+
+                    stack.extend(children.into_iter().rev());
+                }
+                Node::Property(key, value) => {
+                    let key = key.decoded();
+
+                    write(b":");
+                    write(&(key.len() as u64).to_le_bytes());
+                    write(key.as_bytes());
+
+                    stack.push(value);
+                }
+                Node::Literal(literal) => match (node.type_name(), literal.parse::<f64>()) {
+                    ("string", _) => {
+                        let value = node.decoded();
+
+                        write(b"s");
+                        write(&(value.len() as u64).to_le_bytes());
+                        write(value.as_bytes());
+                    }
+                    ("number", Ok(number)) => {
+                        // Adding 0.0 turns -0.0 into 0.0
+                        write(b"n");
+                        write(&(number + 0.0).to_bits().to_le_bytes());
+                    }
+                    _ => {
+                        write(b"l");
+                        write(&(literal.len() as u64).to_le_bytes());
+                        write(literal.as_bytes());
+                    }
+                },
+            }
+        }
+
+        hash
+    }
+
+    /// Content of a string literal with escape sequences decoded, or the unquoted literal if it
+    /// cannot be decoded
+    fn decoded(&self) -> Cow<'_, str> {
+        match self {
+            Node::Literal(literal) => match unescape_json_string(literal) {
+                Ok(value) => Cow::Owned(value),
+                Err(_) => Cow::Borrowed(unquote(literal)),
+            },
+            _ => Cow::Borrowed(""),
+        }
+    }
+
+    /// Every node with its JSON path in depth first order, see `Paths`
+    fn paths(&self) -> Paths<'_, 'source> {
+        Paths {
+            stack: vec![(self, "$".to_string())],
+        }
+    }
+
+    /// Iterate over the unquoted key and value of each property of an object
+    ///
+    /// Nodes other than objects yield nothing.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let ast = Node::Object(vec![Node::Property(
+    ///     Box::new(Node::Literal("\"port\"")),
+    ///     Box::new(Node::Literal("80")),
+    /// )]);
+    ///
+    /// assert_eq!(vec![("port", &Node::Literal("80"))], ast.entries().collect::<Vec<_>>());
+    /// assert_eq!(0, Node::Literal("80").entries().count());
+    /// ```
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Node<'source>)> {
+        let children = match self {
+            Node::Object(children) => children.as_slice(),
+            _ => &[],
+        };
+
+        children.iter().filter_map(|child| match child {
+            Node::Property(key, value) => match key.as_ref() {
+                Node::Literal(key) => Some((unquote(key), value.as_ref())),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    /// Iterate over the elements of an array
+    ///
+    /// Nodes other than arrays yield nothing.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let ast = Node::Array(vec![Node::Literal("true"), Node::Literal("null")]);
+    ///
+    /// assert_eq!(Some(&Node::Literal("null")), ast.elements().last());
+    /// assert_eq!(0, Node::Object(vec![]).elements().count());
+    /// ```
+    pub fn elements(&self) -> impl Iterator<Item = &Node<'source>> {
+        match self {
+            Node::Array(children) => children.iter(),
+            _ => [].iter(),
+        }
+    }
+
+    /// Value of the first object property whose unquoted key matches `key`
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let ast = Node::Object(vec![Node::Property(
+    ///     Box::new(Node::Literal("\"name\"")),
+    ///     Box::new(Node::Literal("\"jtools\"")),
+    /// )]);
+    ///
+    /// assert_eq!(Some(&Node::Literal("\"jtools\"")), ast.get("name"));
+    /// assert_eq!(None, ast.get("version"));
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&Node<'source>> {
+        self.entries()
+            .find_map(|(property_key, value)| (property_key == key).then_some(value))
+    }
+
+    /// Array element at `index`
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let ast = Node::Array(vec![Node::Literal("80"), Node::Literal("443")]);
+    ///
+    /// assert_eq!(Some(&Node::Literal("443")), ast.index(1));
+    /// assert_eq!(None, ast.index(2));
+    /// ```
+    pub fn index(&self, index: usize) -> Option<&Node<'source>> {
+        match self {
+            Node::Array(children) => children.get(index),
+            _ => None,
+        }
+    }
+
+    /// Deepest nesting of objects and arrays
+    ///
+    /// A literal has a depth of 0 and `[[]]` a depth of 2. Properties report the depth of their
+    /// value. The tree is walked with an explicit stack so deep trees cannot overflow.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let ast = Node::Array(vec![Node::Literal("1"), Node::Array(vec![Node::Object(vec![])])]);
+    ///
+    /// assert_eq!(3, ast.depth());
+    /// assert_eq!(0, Node::Literal("1").depth());
+    /// ```
+    pub fn depth(&self) -> usize {
+        let mut max_depth = 0;
+        let mut stack = vec![(self, 0)];
+
+        while let Some((node, depth)) = stack.pop() {
+            match node {
+                Node::Object(children) | Node::Array(children) => {
+                    max_depth = max_depth.max(depth + 1);
+                    stack.extend(children.iter().map(|child| (child, depth + 1)));
+                }
+                Node::Property(_, value) => stack.push((value, depth)),
+                Node::Literal(_) => {}
+            }
+        }
+
+        max_depth
+    }
+
+    /// Render the structure of the tree rather than its JSON, one node per line
+    ///
+    /// Each node is written as its variant name, literals followed by their source text, and
+    /// children are indented two spaces below their parent. Unlike `Debug` the output is the same
+    /// shape at every depth, which makes it easier to see how a document maps onto `Node`.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let ast = Node::Object(vec![Node::Property(
+    ///     Box::new(Node::Literal("\"name\"")),
+    ///     Box::new(Node::Array(vec![Node::Literal("1")])),
+    /// )]);
+    ///
+    /// assert_eq!(
+    ///     "Object\n  Property\n    Literal \"name\"\n    Array\n      Literal 1",
+    ///     ast.to_tree_string()
+    /// );
+    /// ```
+    pub fn to_tree_string(&self) -> String {
+        let mut lines = vec![];
+        let mut stack = vec![(self, 0)];
+
+        while let Some((node, depth)) = stack.pop() {
+            let indent = "  ".repeat(depth);
+
+            match node {
+                Node::Object(children) => {
+                    lines.push(format!("{}Object", indent));
+                    stack.extend(children.iter().rev().map(|child| (child, depth + 1)));
+                }
+                Node::Array(children) => {
+                    lines.push(format!("{}Array", indent));
+                    stack.extend(children.iter().rev().map(|child| (child, depth + 1)));
+                }
+                Node::Property(key, value) => {
+                    lines.push(format!("{}Property", indent));
+                    stack.push((value, depth + 1));
+                    stack.push((key, depth + 1));
+                }
+                Node::Literal(literal) => lines.push(format!("{}Literal {}", indent, literal)),
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    /// Number of immediate children, properties of an object or elements of an array
+    ///
+    /// Literals have no children and properties report the length of their value.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let ast = Node::Array(vec![Node::Literal("1"), Node::Array(vec![Node::Literal("2")])]);
+    ///
+    /// assert_eq!(2, ast.len());
+    /// assert_eq!(0, Node::Literal("1").len());
+    /// ```
+    pub fn len(&self) -> usize {
+        match self {
+            Node::Object(children) | Node::Array(children) => children.len(),
+            Node::Property(_, value) => value.len(),
+            Node::Literal(_) => 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Deep merge `overlay` into `base`, as when layering an override file over a base config
+    ///
+    /// Properties of two objects are matched by unquoted key and merged recursively. Base
+    /// properties keep their position and overlay properties missing from the base are appended
+    /// in order. Arrays are replaced by the overlay, or joined when `concat_arrays` is set. In
+    /// every other case, including values of different types, the overlay wins.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let property = |key, value| Node::Property(Box::new(Node::Literal(key)), Box::new(value));
+    ///
+    /// let base = Node::Object(vec![
+    ///     property("\"port\"", Node::Literal("80")),
+    ///     property("\"tags\"", Node::Array(vec![Node::Literal("1")])),
+    /// ]);
+    ///
+    /// let overlay = Node::Object(vec![
+    ///     property("\"tags\"", Node::Array(vec![Node::Literal("2")])),
+    ///     property("\"tls\"", Node::Literal("true")),
+    /// ]);
+    ///
+    /// assert_eq!(
+    ///     "{\"port\":80,\"tags\":[1,2],\"tls\":true}",
+    ///     Node::merge(base, overlay, true).to_string()
+    /// );
+    /// ```
+    pub fn merge(
+        base: Node<'source>,
+        overlay: Node<'source>,
+        concat_arrays: bool,
+    ) -> Node<'source> {
+        match (base, overlay) {
+            (Node::Object(mut base), Node::Object(overlay)) => {
+                let mut positions: BTreeMap<&'source str, usize> = BTreeMap::new();
+
+                for (position, property) in base.iter().enumerate() {
+                    if let Node::Property(key, _) = property {
+                        if let Node::Literal(key) = key.as_ref() {
+                            positions.entry(unquote(key)).or_insert(position);
+                        }
+                    }
+                }
+
+                for property in overlay {
+                    let position = match &property {
+                        Node::Property(key, _) => match key.as_ref() {
+                            Node::Literal(key) => positions.get(unquote(key)).copied(),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+
+                    match (position, property) {
+                        (Some(position), Node::Property(_, overlay_value)) => {
+                            if let Node::Property(_, base_value) = &mut base[position] {
+                                let value =
+                                    core::mem::replace(base_value.as_mut(), Node::Array(vec![]));
+                                **base_value = Node::merge(value, *overlay_value, concat_arrays);
+                            }
+                        }
+                        (_, property) => base.push(property),
+                    }
+                }
+
+                Node::Object(base)
+            }
+            (Node::Array(mut base), Node::Array(overlay)) if concat_arrays => {
+                base.extend(overlay);
+                Node::Array(base)
+            }
+            (_, overlay) => overlay,
+        }
+    }
+
+    /// Sort the properties of every object by their unquoted key, recursively
+    ///
+    /// The sort is stable so properties with the same key, kept by `DuplicateKeys::KeepAll`,
+    /// stay in their original order. Arrays keep the order of their elements.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let property = |key, value| {
+    ///     Node::Property(Box::new(Node::Literal(key)), Box::new(Node::Literal(value)))
+    /// };
+    ///
+    /// let mut ast = Node::Object(vec![property("\"b\"", "1"), property("\"a\"", "2")]);
+    /// ast.sort_keys();
+    ///
+    /// assert_eq!(Node::Object(vec![property("\"a\"", "2"), property("\"b\"", "1")]), ast);
+    /// ```
+    pub fn sort_keys(&mut self) {
+        let mut stack = vec![self];
+
+        while let Some(node) = stack.pop() {
+            match node {
+                Node::Object(children) => {
+                    children.sort_by(|a, b| a.key().cmp(&b.key()));
+                    stack.extend(children.iter_mut());
+                }
+                Node::Array(children) => stack.extend(children.iter_mut()),
+                Node::Property(_, value) => stack.push(value),
+                Node::Literal(_) => {}
+            }
+        }
+    }
+
+    /// Remove repeated elements of an array, keeping the first occurrence of each
+    ///
+    /// Elements are compared with `Ord`, which agrees with `Eq`, so literals must be spelled the
+    /// same to be duplicates: `1` and `1.0` are both kept. Nested arrays are not deduplicated and
+    /// other nodes are left unchanged.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let mut ast = Node::Array(vec![
+    ///     Node::Literal("1"),
+    ///     Node::Literal("\"a\""),
+    ///     Node::Literal("1"),
+    ///     Node::Literal("1.0"),
+    ///     Node::Literal("\"a\""),
+    /// ]);
+    /// ast.dedup_array();
+    ///
+    /// assert_eq!(
+    ///     Node::Array(vec![Node::Literal("1"), Node::Literal("\"a\""), Node::Literal("1.0")]),
+    ///     ast
+    /// );
+    /// ```
+    pub fn dedup_array(&mut self) {
+        let Node::Array(children) = self else {
+            return;
+        };
+
+        let mut seen = BTreeSet::new();
+        let first: Vec<bool> = children.iter().map(|child| seen.insert(child)).collect();
+        let mut first = first.into_iter();
+
+        children.retain(|_| first.next().unwrap_or(true));
+    }
+
+    /// Unquoted key of a property, `None` for other nodes
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// let property = Node::Property(Box::new(Node::Literal("\"a\"")), Box::new(Node::Literal("1")));
+    ///
+    /// assert_eq!(Some("a"), property.key());
+    /// assert_eq!(None, Node::Literal("1").key());
+    /// ```
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Node::Property(key, _) => match key.as_ref() {
+                Node::Literal(key) => Some(unquote(key)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Write the node as minified JSON, passing each literal to `literal` to be written
+    ///
+    /// This is the traversal shared by `Display` and the minifier, which can rewrite literals. An
+    /// explicit stack is used instead of recursion so arbitrarily deep trees cannot overflow the
+    /// call stack.
+    pub fn write_minified<W, F>(&self, out: &mut W, literal: &F) -> core::fmt::Result
+    where
+        W: Write,
+        F: Fn(&mut W, &str) -> core::fmt::Result,
+    {
+        let mut stack = vec![Minified::Node(self)];
+
+        while let Some(next) = stack.pop() {
+            match next {
+                Minified::Node(Node::Object(children)) => {
+                    out.write_char('{')?;
+                    stack.push(Minified::Delimiter('}'));
+                    Self::push_children(&mut stack, children);
+                }
+                Minified::Node(Node::Property(key, value)) => {
+                    stack.push(Minified::Node(value));
+                    stack.push(Minified::Delimiter(':'));
+                    stack.push(Minified::Node(key));
+                }
+                Minified::Node(Node::Array(children)) => {
+                    out.write_char('[')?;
+                    stack.push(Minified::Delimiter(']'));
+                    Self::push_children(&mut stack, children);
+                }
+                Minified::Node(Node::Literal(value)) => literal(out, value)?,
+                Minified::Delimiter(delimiter) => out.write_char(delimiter)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Push children in reverse, separated by commas, so they are popped in order
+    fn push_children<'a>(stack: &mut Vec<Minified<'a, 'source>>, children: &'a [Node<'source>]) {
+        for (i, child) in children.iter().enumerate().rev() {
+            stack.push(Minified::Node(child));
+
+            if i > 0 {
+                stack.push(Minified::Delimiter(','));
+            }
+        }
+    }
+
+    /// Name of the JSON type represented by the node
+    ///
+    /// Literals are classified from their source slice, so `"\"1\""` is a string and `1` is a
+    /// number. Properties report the type of their value.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    ///
+    /// assert_eq!("number", Node::Literal("-4.2e1").type_name());
+    /// assert_eq!("array", Node::Array(vec![]).type_name());
+    /// ```
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Node::Object(_) => "object",
+            Node::Property(_, value) => value.type_name(),
+            Node::Array(_) => "array",
+            Node::Literal("true" | "false") => "boolean",
+            Node::Literal("null") => "null",
+            Node::Literal(literal) if literal.starts_with('"') => "string",
+            Node::Literal(_) => "number",
+        }
+    }
+}
+
+impl Ord for Node<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank()
+            .cmp(&other.rank())
+            .then_with(|| match (self, other) {
+                (Node::Object(a), Node::Object(b)) | (Node::Array(a), Node::Array(b)) => a.cmp(b),
+                (Node::Property(a_key, a_value), Node::Property(b_key, b_value)) => {
+                    a_key.cmp(b_key).then_with(|| a_value.cmp(b_value))
+                }
+                (Node::Literal(a), Node::Literal(b)) => {
+                    let by_value = match self.type_name() {
+                        "number" => match (a.parse::<f64>(), b.parse::<f64>()) {
+                            (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                            _ => Ordering::Equal,
+                        },
+                        "string" => unquote(a).cmp(unquote(b)),
+                        _ => Ordering::Equal,
+                    };
+
+                    by_value.then_with(|| a.cmp(b))
+                }
+                _ => Ordering::Equal,
+            })
+    }
+}
+
+impl PartialOrd for Node<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Node<'_> {
+    /// Position of the node's type in the ordering, properties sort after every value
+    fn rank(&self) -> u8 {
+        match self {
+            Node::Property(..) => 6,
+            Node::Object(_) => 5,
+            Node::Array(_) => 4,
+            literal => match literal.type_name() {
+                "null" => 0,
+                "boolean" => 1,
+                "number" => 2,
+                _ => 3,
+            },
+        }
+    }
+}
+
+/// Minified JSON
+impl Display for Node<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.write_minified(f, &|f, literal| f.write_str(literal))
+    }
+}
+
+/// Pending work of the `write_minified` traversal
+enum Minified<'a, 'source> {
+    Node(&'a Node<'source>),
+    Delimiter(char),
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Depth first walk of a tree yielding each node with its JSON path
+///
+/// A property, its key and its value share the path of the value. An explicit stack is used so
+/// deep trees cannot overflow.
+struct Paths<'a, 'source> {
+    stack: Vec<(&'a Node<'source>, String)>,
+}
+
+impl<'a, 'source> Iterator for Paths<'a, 'source> {
+    type Item = (&'a Node<'source>, String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, path) = self.stack.pop()?;
+
+        match node {
+            Node::Object(children) => {
+                self.stack.extend(children.iter().rev().map(|child| {
+                    let path = match child.key() {
+                        Some(key) => format!("{}.{}", path, key),
+                        None => path.clone(),
+                    };
+
+                    (child, path)
+                }));
+            }
+            Node::Array(children) => {
+                self.stack.extend(
+                    children
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .map(|(i, child)| (child, format!("{}[{}]", path, i))),
+                );
+            }
+            Node::Property(key, value) => {
+                self.stack.push((value, path.clone()));
+                self.stack.push((key, path.clone()));
+            }
+            Node::Literal(_) => {}
+        }
+
+        Some((node, path))
+    }
+}
+
+fn unquote(literal: &str) -> &str {
+    literal
+        .strip_prefix('"')
+        .and_then(|literal| literal.strip_suffix('"'))
+        .unwrap_or(literal)
+}
+
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod ast_node_tests {
-    use token::{token::Token, token_type::TokenType};
+    use token::{escape::UnescapeError, token::Token, token_type::TokenType};
 
     use super::*;
 
+    #[test]
+    fn query_nested_path() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"servers\"")),
+            Box::new(Node::Array(vec![Node::Object(vec![Node::Property(
+                Box::new(Node::Literal("\"port\"")),
+                Box::new(Node::Literal("8080")),
+            )])])),
+        )]);
+
+        assert_eq!(Some(&Node::Literal("8080")), ast.query("$.servers[0].port"));
+        assert_eq!(Some(&Node::Literal("8080")), ast.query("servers[0].port"));
+        assert_eq!(Some(&ast), ast.query("$"));
+    }
+
+    #[test]
+    fn query_missing_path() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"tags\"")),
+            Box::new(Node::Array(vec![Node::Literal("\"json\"")])),
+        )]);
+
+        assert_eq!(None, ast.query("$.name"));
+        assert_eq!(None, ast.query("$.tags[1]"));
+        assert_eq!(None, ast.query("$.tags.name"));
+        assert_eq!(None, ast.query("$[0]"));
+        assert_eq!(None, ast.query("$.tags[x]"));
+        assert_eq!(None, ast.query("$..tags"));
+    }
+
+    #[test]
+    fn find_path_of_node() {
+        let ast = Node::Object(vec![
+            Node::Property(
+                Box::new(Node::Literal("\"name\"")),
+                Box::new(Node::Literal("\"jtools\"")),
+            ),
+            Node::Property(
+                Box::new(Node::Literal("\"servers\"")),
+                Box::new(Node::Array(vec![Node::Object(vec![Node::Property(
+                    Box::new(Node::Literal("\"port\"")),
+                    Box::new(Node::Literal("80")),
+                )])])),
+            ),
+        ]);
+
+        let Node::Object(children) = &ast else {
+            unreachable!()
+        };
+
+        assert_eq!(Some("$".to_string()), ast.path_of(&ast));
+        assert_eq!(Some("$.servers".to_string()), ast.path_of(&children[1]));
+        assert_eq!(
+            Some("$.servers[0].port".to_string()),
+            ast.path_of(ast.query("$.servers[0].port").unwrap())
+        );
+        assert_eq!(None, ast.path_of(&Node::Literal("80")));
+    }
+
+    #[test]
+    fn validate_string_literals() {
+        let ast = Node::Object(vec![
+            Node::Property(
+                Box::new(Node::Literal("\"ok\"")),
+                Box::new(Node::Literal("\"\\uD83D\\uDE00\"")),
+            ),
+            Node::Property(
+                Box::new(Node::Literal("\"list\"")),
+                Box::new(Node::Array(vec![
+                    Node::Literal("1"),
+                    Node::Literal("\"\\uD83D\""),
+                ])),
+            ),
+        ]);
+
+        assert_eq!(
+            Err(ValidationError {
+                path: "$.list[1]".to_string(),
+                literal: "\"\\uD83D\"".to_string(),
+                error: UnescapeError::InvalidSurrogatePair { index: 1 },
+            }),
+            ast.validate_strings()
+        );
+
+        let key = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"\\x\"")),
+            Box::new(Node::Literal("null")),
+        )]);
+
+        assert_eq!("$.\\x", key.validate_strings().unwrap_err().path);
+        assert_eq!(Ok(()), Node::Literal("'\\uD83D'").validate_strings());
+    }
+
+    #[test]
+    fn validate_node_count() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"list\"")),
+            Box::new(Node::Array(vec![Node::Literal("1"), Node::Literal("2")])),
+        )]);
+
+        assert_eq!(Ok(()), ast.validate_size(6));
+        assert_eq!(Err(SizeError { max_nodes: 5 }), ast.validate_size(5));
+        assert_eq!(
+            Err(SizeError { max_nodes: 0 }),
+            Node::Literal("1").validate_size(0)
+        );
+    }
+
+    #[test]
+    fn render_tree_string() {
+        let ast = Node::Array(vec![
+            Node::Object(vec![
+                Node::Property(
+                    Box::new(Node::Literal("\"a\"")),
+                    Box::new(Node::Literal("true")),
+                ),
+                Node::Property(
+                    Box::new(Node::Literal("\"b\"")),
+                    Box::new(Node::Object(vec![])),
+                ),
+            ]),
+            Node::Array(vec![]),
+            Node::Literal("null"),
+        ]);
+
+        assert_eq!(
+            [
+                "Array",
+                "  Object",
+                "    Property",
+                "      Literal \"a\"",
+                "      Literal true",
+                "    Property",
+                "      Literal \"b\"",
+                "      Object",
+                "  Array",
+                "  Literal null",
+            ]
+            .join("\n"),
+            ast.to_tree_string()
+        );
+        assert_eq!("Literal 1", Node::Literal("1").to_tree_string());
+    }
+
+    #[test]
+    fn fingerprint_semantic_content() {
+        let property = |key, value| Node::Property(Box::new(Node::Literal(key)), Box::new(value));
+
+        let a = Node::Object(vec![
+            property(
+                "\"tags\"",
+                Node::Array(vec![Node::Literal("-0"), Node::Literal("1E2")]),
+            ),
+            property("\"name\"", Node::Literal("\"\\u006Atools\"")),
+        ]);
+        let b = Node::Object(vec![
+            property("\"name\"", Node::Literal("\"jtools\"")),
+            property(
+                "tags",
+                Node::Array(vec![Node::Literal("0.0"), Node::Literal("100")]),
+            ),
+        ]);
+
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_eq!(0x755538bede356462, Node::Literal("null").fingerprint());
+
+        let different = [
+            Node::Array(vec![Node::Literal("100"), Node::Literal("0")]),
+            Node::Array(vec![Node::Literal("\"1\"")]),
+            Node::Array(vec![Node::Literal("1")]),
+            Node::Array(vec![Node::Array(vec![])]),
+            Node::Array(vec![Node::Object(vec![])]),
+            Node::Array(vec![]),
+            Node::Literal("null"),
+            Node::Literal("false"),
+        ];
+
+        for (i, x) in different.iter().enumerate() {
+            for y in &different[i + 1..] {
+                assert_ne!(x.fingerprint(), y.fingerprint(), "{} {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn iterate_entries_and_elements() {
+        let ast = Node::Object(vec![
+            Node::Property(
+                Box::new(Node::Literal("\"name\"")),
+                Box::new(Node::Literal("\"jtools\"")),
+            ),
+            Node::Property(
+                Box::new(Node::Literal("\"tags\"")),
+                Box::new(Node::Array(vec![Node::Literal("1"), Node::Literal("2")])),
+            ),
+        ]);
+
+        assert_eq!(
+            vec!["name", "tags"],
+            ast.entries().map(|(key, _)| key).collect::<Vec<&str>>()
+        );
+
+        let (_, tags) = ast.entries().last().unwrap();
+
+        assert_eq!(
+            vec![&Node::Literal("1"), &Node::Literal("2")],
+            tags.elements().collect::<Vec<&Node>>()
+        );
+        assert_eq!(0, ast.elements().count());
+        assert_eq!(0, tags.entries().count());
+    }
+
+    #[test]
+    fn display_deeply_nested_json() {
+        let depth = 100_000;
+        let mut ast = Node::Literal("1");
+
+        for _ in 0..depth {
+            ast = Node::Array(vec![ast]);
+        }
+
+        let json = ast.to_string();
+
+        assert_eq!(format!("{}1{}", "[".repeat(depth), "]".repeat(depth)), json);
+
+        // Dropping the tree recursively would overflow the stack so unwrap it one level at a time
+        while let Node::Array(mut children) = ast {
+            match children.pop() {
+                Some(child) => ast = child,
+                None => break,
+            }
+        }
+    }
+
+    #[test]
+    fn get_and_index_type_mismatch() {
+        let array = Node::Array(vec![Node::Literal("1")]);
+        let object = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"0\"")),
+            Box::new(Node::Literal("1")),
+        )]);
+
+        assert_eq!(None, array.get("0"));
+        assert_eq!(None, object.index(0));
+        assert_eq!(None, Node::Literal("1").get("0"));
+        assert_eq!(Some(&Node::Literal("1")), array.index(0));
+        assert_eq!(Some(&Node::Literal("1")), object.get("0"));
+    }
+
+    #[test]
+    fn order_across_types() {
+        let ordered = vec![
+            Node::Literal("null"),
+            Node::Literal("false"),
+            Node::Literal("true"),
+            Node::Literal("-1"),
+            Node::Literal("1"),
+            Node::Literal("1.0"),
+            Node::Literal("1e1"),
+            Node::Literal("\"\""),
+            Node::Literal("\"a\""),
+            Node::Literal("\"a!\""),
+            Node::Literal("\"b\""),
+            Node::Array(vec![]),
+            Node::Array(vec![Node::Literal("1")]),
+            Node::Array(vec![Node::Literal("2")]),
+            Node::Object(vec![]),
+            Node::Object(vec![Node::Property(
+                Box::new(Node::Literal("\"a\"")),
+                Box::new(Node::Literal("2")),
+            )]),
+            Node::Object(vec![Node::Property(
+                Box::new(Node::Literal("\"b\"")),
+                Box::new(Node::Literal("1")),
+            )]),
+        ];
+
+        for (i, a) in ordered.iter().enumerate() {
+            for (j, b) in ordered.iter().enumerate() {
+                assert_eq!(i.cmp(&j), a.cmp(b), "{} cmp {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn name_node_types() {
+        assert_eq!("object", Node::Object(vec![]).type_name());
+        assert_eq!("array", Node::Array(vec![]).type_name());
+        assert_eq!("string", Node::Literal("\"true\"").type_name());
+        assert_eq!("number", Node::Literal("0").type_name());
+        assert_eq!("boolean", Node::Literal("false").type_name());
+        assert_eq!("null", Node::Literal("null").type_name());
+        assert_eq!(
+            "boolean",
+            Node::Property(
+                Box::new(Node::Literal("\"active\"")),
+                Box::new(Node::Literal("true"))
+            )
+            .type_name()
+        );
+    }
+
+    #[test]
+    fn display_minified_json() {
+        let ast = Node::Object(vec![
+            Node::Property(
+                Box::new(Node::Literal("\"tags\"")),
+                Box::new(Node::Array(vec![
+                    Node::Literal("1"),
+                    Node::Literal("\"a b\""),
+                ])),
+            ),
+            Node::Property(
+                Box::new(Node::Literal("\"empty\"")),
+                Box::new(Node::Object(vec![])),
+            ),
+        ]);
+
+        assert_eq!("{\"tags\":[1,\"a b\"],\"empty\":{}}", format!("{}", ast));
+    }
+
     #[test]
     fn create_ast_literal() {
         let source = "false";
@@ -97,6 +1261,136 @@ mod ast_node_tests {
         );
     }
 
+    #[test]
+    fn measure_depth_and_len() {
+        let ast = Node::Object(vec![
+            Node::Property(
+                Box::new(Node::Literal("\"a\"")),
+                Box::new(Node::Array(vec![Node::Array(vec![])])),
+            ),
+            Node::Property(
+                Box::new(Node::Literal("\"b\"")),
+                Box::new(Node::Literal("null")),
+            ),
+        ]);
+
+        assert_eq!(3, ast.depth());
+        assert_eq!(2, ast.len());
+        assert!(!ast.is_empty());
+
+        let Node::Object(children) = &ast else {
+            unreachable!()
+        };
+
+        assert_eq!(2, children[0].depth());
+        assert_eq!(1, children[0].len());
+        assert_eq!(0, children[1].depth());
+        assert!(children[1].is_empty());
+        assert_eq!(1, Node::Array(vec![]).depth());
+        assert!(Node::Object(vec![]).is_empty());
+    }
+
+    #[test]
+    fn merge_objects_deeply() {
+        let property = |key, value| Node::Property(Box::new(Node::Literal(key)), Box::new(value));
+
+        let base = || {
+            Node::Object(vec![
+                property(
+                    "\"server\"",
+                    Node::Object(vec![
+                        property("\"host\"", Node::Literal("\"localhost\"")),
+                        property("\"port\"", Node::Literal("80")),
+                    ]),
+                ),
+                property("\"tags\"", Node::Array(vec![Node::Literal("1")])),
+                property("\"debug\"", Node::Object(vec![])),
+            ])
+        };
+
+        let overlay = || {
+            Node::Object(vec![
+                property("\"debug\"", Node::Literal("false")),
+                property(
+                    "\"server\"",
+                    Node::Object(vec![property("\"port\"", Node::Literal("443"))]),
+                ),
+                property("\"tags\"", Node::Array(vec![Node::Literal("2")])),
+                property("\"name\"", Node::Literal("\"prod\"")),
+            ])
+        };
+
+        assert_eq!(
+            "{\"server\":{\"host\":\"localhost\",\"port\":443},\"tags\":[2],\"debug\":false,\"name\":\"prod\"}",
+            Node::merge(base(), overlay(), false).to_string()
+        );
+        assert_eq!(
+            "{\"server\":{\"host\":\"localhost\",\"port\":443},\"tags\":[1,2],\"debug\":false,\"name\":\"prod\"}",
+            Node::merge(base(), overlay(), true).to_string()
+        );
+        assert_eq!(
+            Node::Literal("1"),
+            Node::merge(base(), Node::Literal("1"), false)
+        );
+    }
+
+    #[test]
+    fn sort_keys_recursively() {
+        let property = |key, value| Node::Property(Box::new(Node::Literal(key)), Box::new(value));
+
+        let mut ast = Node::Array(vec![Node::Object(vec![
+            property("\"z\"", Node::Literal("1")),
+            property(
+                "\"a\"",
+                Node::Object(vec![
+                    property("\"y\"", Node::Literal("1")),
+                    property("\"x\"", Node::Literal("2")),
+                    property("\"y\"", Node::Literal("3")),
+                ]),
+            ),
+        ])]);
+
+        ast.sort_keys();
+
+        assert_eq!(
+            "[{\"a\":{\"x\":2,\"y\":1,\"y\":3},\"z\":1}]",
+            ast.to_string()
+        );
+    }
+
+    #[test]
+    fn dedup_array_elements() {
+        let property = |key, value| Node::Property(Box::new(Node::Literal(key)), Box::new(value));
+        let object = || Node::Object(vec![property("\"a\"", Node::Array(vec![]))]);
+
+        let mut ast = Node::Array(vec![
+            object(),
+            Node::Array(vec![Node::Literal("1"), Node::Literal("1")]),
+            object(),
+            Node::Literal("null"),
+            Node::Array(vec![Node::Literal("1"), Node::Literal("1")]),
+            Node::Literal("null"),
+        ]);
+
+        ast.dedup_array();
+
+        assert_eq!("[{\"a\":[]},[1,1],null]", ast.to_string());
+
+        let mut literal = Node::Literal("1");
+        literal.dedup_array();
+
+        assert_eq!(Node::Literal("1"), literal);
+    }
+
+    #[test]
+    fn hash_nodes() {
+        let mut seen = std::collections::HashSet::new();
+
+        assert!(seen.insert(Node::Array(vec![Node::Literal("1")])));
+        assert!(seen.insert(Node::Array(vec![Node::Literal("1.0")])));
+        assert!(!seen.insert(Node::Array(vec![Node::Literal("1")])));
+    }
+
     #[test]
     fn create_ast_object() {
         let source = "{\"animal\":\"dog\"}";
@@ -0,0 +1,112 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::node::Node;
+
+/// Abstract Syntax Tree (AST) node that owns its literals
+///
+/// ## Description
+///
+/// `Node` borrows each literal from the source so the source has to outlive the tree. An
+/// `OwnedNode` is a deep copy holding a `String` for each literal, so it can be cached after the
+/// source is dropped or sent to another thread. `OwnedNode::as_node` borrows it back as a `Node`
+/// for use with the formatter and minifier.
+///
+/// ## Examples
+///
+/// ```
+/// use ast::{node::Node, owned_node::OwnedNode};
+///
+/// let owned = {
+///     let source = String::from("[true]");
+///     let ast = Node::Array(vec![Node::Literal(&source[1..5])]);
+///
+///     OwnedNode::from(&ast)
+/// };
+///
+/// assert_eq!(Node::Array(vec![Node::Literal("true")]), owned.as_node());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnedNode {
+    Object(Vec<OwnedNode>),
+    Property(Box<OwnedNode>, Box<OwnedNode>),
+    Array(Vec<OwnedNode>),
+    Literal(String),
+}
+
+impl OwnedNode {
+    pub fn as_node(&self) -> Node<'_> {
+        match self {
+            OwnedNode::Object(children) => {
+                Node::Object(children.iter().map(OwnedNode::as_node).collect())
+            }
+            OwnedNode::Property(key, value) => {
+                Node::Property(Box::new(key.as_node()), Box::new(value.as_node()))
+            }
+            OwnedNode::Array(children) => {
+                Node::Array(children.iter().map(OwnedNode::as_node).collect())
+            }
+            OwnedNode::Literal(literal) => Node::Literal(literal),
+        }
+    }
+}
+
+impl From<&Node<'_>> for OwnedNode {
+    fn from(ast: &Node<'_>) -> Self {
+        match ast {
+            Node::Object(children) => {
+                OwnedNode::Object(children.iter().map(OwnedNode::from).collect())
+            }
+            Node::Property(key, value) => OwnedNode::Property(
+                Box::new(OwnedNode::from(key.as_ref())),
+                Box::new(OwnedNode::from(value.as_ref())),
+            ),
+            Node::Array(children) => {
+                OwnedNode::Array(children.iter().map(OwnedNode::from).collect())
+            }
+            Node::Literal(literal) => OwnedNode::Literal(literal.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod owned_node_tests {
+    use super::*;
+
+    #[test]
+    fn convert_to_owned_and_back() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"tags\"")),
+            Box::new(Node::Array(vec![Node::Literal("1"), Node::Object(vec![])])),
+        )]);
+
+        let owned = OwnedNode::from(&ast);
+
+        assert_eq!(
+            OwnedNode::Object(vec![OwnedNode::Property(
+                Box::new(OwnedNode::Literal("\"tags\"".to_string())),
+                Box::new(OwnedNode::Array(vec![
+                    OwnedNode::Literal("1".to_string()),
+                    OwnedNode::Object(vec![])
+                ])),
+            )]),
+            owned
+        );
+        assert_eq!(ast, owned.as_node());
+    }
+
+    #[test]
+    fn outlive_source() {
+        let owned = {
+            let source = String::from("null");
+            OwnedNode::from(&Node::Literal(&source))
+        };
+
+        let handle = std::thread::spawn(move || owned.as_node().type_name());
+
+        assert_eq!("null", handle.join().unwrap());
+    }
+}
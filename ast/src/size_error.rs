@@ -0,0 +1,28 @@
+use core::{error::Error, fmt::Display};
+
+/// AST with more nodes than allowed, returned by `Node::validate_size`
+#[derive(Debug, PartialEq)]
+pub struct SizeError {
+    pub max_nodes: usize,
+}
+
+impl Error for SizeError {}
+
+impl Display for SizeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "AST exceeds the maximum of {} nodes", self.max_nodes)
+    }
+}
+
+#[cfg(test)]
+mod size_error_tests {
+    use super::*;
+
+    #[test]
+    fn error_message() {
+        assert_eq!(
+            "AST exceeds the maximum of 10 nodes",
+            SizeError { max_nodes: 10 }.to_string()
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use alloc::{boxed::Box, vec::Vec};
+
+/// Abstract Syntax Tree (AST) node with typed literals
+///
+/// ## Description
+///
+/// A parallel tree to `Node`, produced by `Parser::parse_typed`, where each literal is given its
+/// JSON type from the token it was scanned as. Numbers are parsed into an `f64`, and keywords
+/// become `Bool` and `Null`. `String` keeps the raw source slice, including quotes and escape
+/// sequences, in the same way as `Node::Literal`, and object keys are `String` nodes.
+///
+/// ## Examples
+///```rust
+/// use ast::typed_node::TypedNode;
+///
+/// let object = TypedNode::Object(vec![TypedNode::Property(
+///     Box::new(TypedNode::String("\"count\"")),
+///     Box::new(TypedNode::Number(42.0)),
+/// )]);
+///
+/// let array = TypedNode::Array(vec![TypedNode::Bool(true), TypedNode::Null]);
+///```
+#[derive(Debug, PartialEq)]
+pub enum TypedNode<'source> {
+    Object(Vec<TypedNode<'source>>),
+    Property(Box<TypedNode<'source>>, Box<TypedNode<'source>>),
+    Array(Vec<TypedNode<'source>>),
+    String(&'source str),
+    Number(f64),
+    Bool(bool),
+    Null,
+}
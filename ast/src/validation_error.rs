@@ -0,0 +1,48 @@
+use alloc::string::String;
+use core::{error::Error, fmt::Display};
+
+use token::escape::UnescapeError;
+
+/// String literal that cannot be decoded, returned by `Node::validate_strings`
+#[derive(Debug, PartialEq)]
+pub struct ValidationError {
+    /// JSON path of the literal, such as `$.servers[0].name`
+    pub path: String,
+    /// Literal as written in the source, including its quotes
+    pub literal: String,
+    pub error: UnescapeError,
+}
+
+impl Error for ValidationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Invalid string {} at {}, {}",
+            self.literal, self.path, self.error
+        )
+    }
+}
+
+#[cfg(test)]
+mod validation_error_tests {
+    use super::*;
+
+    #[test]
+    fn error_message() {
+        assert_eq!(
+            "Invalid string \"\\uD800\" at $.name, Invalid surrogate pair at index 0",
+            ValidationError {
+                path: "$.name".to_string(),
+                literal: "\"\\uD800\"".to_string(),
+                error: UnescapeError::InvalidSurrogatePair { index: 0 },
+            }
+            .to_string()
+        );
+    }
+}
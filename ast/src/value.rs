@@ -0,0 +1,106 @@
+use serde_json::{Map, Value};
+
+use crate::node::Node;
+
+/// Convert an AST into a `serde_json::Value`
+///
+/// ## Description
+///
+/// Available with the `serde` feature so that input can be validated with jtools before being
+/// handed to serde based code. Literals are decoded from their source slice so escape sequences
+/// in strings and object keys are resolved. A literal that cannot be decoded, for example a
+/// string containing a lone surrogate, is kept as a string of its raw slice.
+///
+/// ## Examples
+///
+/// ```
+/// use ast::node::Node;
+/// use serde_json::{json, Value};
+///
+/// let ast = Node::Object(vec![Node::Property(
+///     Box::new(Node::Literal("\"tags\"")),
+///     Box::new(Node::Array(vec![Node::Literal("\"a\\nb\""), Node::Literal("1.5")])),
+/// )]);
+///
+/// assert_eq!(json!({ "tags": ["a\nb", 1.5] }), Value::from(&ast));
+/// ```
+impl From<&Node<'_>> for Value {
+    fn from(ast: &Node<'_>) -> Self {
+        match ast {
+            Node::Object(children) => {
+                let mut map = Map::new();
+
+                for child in children {
+                    if let Node::Property(key, value) = child {
+                        map.insert(object_key(key), Value::from(value.as_ref()));
+                    }
+                }
+
+                Value::Object(map)
+            }
+            Node::Property(key, value) => {
+                let mut map = Map::new();
+                map.insert(object_key(key), Value::from(value.as_ref()));
+                Value::Object(map)
+            }
+            Node::Array(children) => Value::Array(children.iter().map(Value::from).collect()),
+            Node::Literal(literal) => {
+                serde_json::from_str(literal).unwrap_or_else(|_| Value::String(literal.to_string()))
+            }
+        }
+    }
+}
+
+fn object_key(key: &Node) -> String {
+    match Value::from(key) {
+        Value::String(key) => key,
+        key => key.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod value_tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn convert_literals() {
+        assert_eq!(Value::Null, Value::from(&Node::Literal("null")));
+        assert_eq!(Value::Bool(true), Value::from(&Node::Literal("true")));
+        assert_eq!(Value::Bool(false), Value::from(&Node::Literal("false")));
+        assert_eq!(json!(-1066), Value::from(&Node::Literal("-1066")));
+        assert_eq!(json!(360.0), Value::from(&Node::Literal("3.6e2")));
+        assert_eq!(
+            json!("😀 \"quoted\""),
+            Value::from(&Node::Literal(r#""😀 \"quoted\"""#))
+        );
+    }
+
+    #[test]
+    fn keep_undecodable_literal_as_raw_string() {
+        assert_eq!(
+            json!(r#""\uD83D""#),
+            Value::from(&Node::Literal(r#""\uD83D""#))
+        );
+    }
+
+    #[test]
+    fn convert_containers() {
+        let ast = Node::Object(vec![
+            Node::Property(
+                Box::new(Node::Literal(r#""na\u006De""#)),
+                Box::new(Node::Literal("\"jtools\"")),
+            ),
+            Node::Property(
+                Box::new(Node::Literal("\"tags\"")),
+                Box::new(Node::Array(vec![Node::Object(vec![]), Node::Array(vec![])])),
+            ),
+        ]);
+
+        assert_eq!(
+            json!({ "name": "jtools", "tags": [{}, []] }),
+            Value::from(&ast)
+        );
+    }
+}
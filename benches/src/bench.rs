@@ -22,19 +22,19 @@ fn parse_minified_json(c: &mut Criterion) {
 
     for (name, input) in inputs.into_iter() {
         group.bench_with_input(BenchmarkId::new("scan", name), &input, |b, s| {
-            b.iter(|| black_box(runner.run(Action::Scan, &s)));
+            b.iter(|| black_box(runner.run(Action::Scan, s)));
         });
 
         group.bench_with_input(BenchmarkId::new("parse", name), &input, |b, s| {
-            b.iter(|| black_box(runner.run(Action::Parse, &s)));
+            b.iter(|| black_box(runner.run(Action::Parse, s)));
         });
 
         group.bench_with_input(BenchmarkId::new("format", name), &input, |b, s| {
-            b.iter(|| black_box(runner.run(Action::Format, &s)));
+            b.iter(|| black_box(runner.run(Action::Format, s)));
         });
 
         group.bench_with_input(BenchmarkId::new("minify", name), &input, |b, s| {
-            b.iter(|| black_box(runner.run(Action::Minify, &s)));
+            b.iter(|| black_box(runner.run(Action::Minify, s)));
         });
     }
 
@@ -60,19 +60,19 @@ fn parse_formatted_json(c: &mut Criterion) {
 
     for (name, input) in inputs.into_iter() {
         group.bench_with_input(BenchmarkId::new("scan", name), &input, |b, s| {
-            b.iter(|| black_box(runner.run(Action::Scan, &s)));
+            b.iter(|| black_box(runner.run(Action::Scan, s)));
         });
 
         group.bench_with_input(BenchmarkId::new("parse", name), &input, |b, s| {
-            b.iter(|| black_box(runner.run(Action::Parse, &s)));
+            b.iter(|| black_box(runner.run(Action::Parse, s)));
         });
 
         group.bench_with_input(BenchmarkId::new("format", name), &input, |b, s| {
-            b.iter(|| black_box(runner.run(Action::Format, &s)));
+            b.iter(|| black_box(runner.run(Action::Format, s)));
         });
 
         group.bench_with_input(BenchmarkId::new("minify", name), &input, |b, s| {
-            b.iter(|| black_box(runner.run(Action::Minify, &s)));
+            b.iter(|| black_box(runner.run(Action::Minify, s)));
         });
     }
 
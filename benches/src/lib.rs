@@ -37,7 +37,7 @@ impl Runner {
             return Ok(());
         };
 
-        let minifier = Minifier;
+        let minifier = Minifier::default();
         minifier.minify(&ast);
 
         Ok(())
@@ -0,0 +1,206 @@
+use ast::node::Node;
+use error_preview::error_preview::ErrorPreview;
+
+use crate::check_error::CheckError;
+
+const TYPES: [&str; 6] = ["string", "number", "boolean", "null", "object", "array"];
+
+/// Check the top-level properties of an object against expected types
+///
+/// ## Description
+///
+/// A lightweight alternative to a schema. Requirements are written as a comma separated list of
+/// `property:type` pairs where type is one of `string`, `number`, `boolean`, `null`, `object` or
+/// `array`. Each property must exist on the top-level object and its value must be of the given
+/// type. The first requirement that fails is returned as an error, type mismatches include an
+/// error preview pointing at the property.
+///
+/// ## Examples
+///
+/// ```
+/// use ast::node::Node;
+/// use cli::check::Check;
+///
+/// let source = "{\"name\":\"jtools\",\"age\":1}";
+///
+/// let ast = Node::Object(vec![
+///     Node::Property(
+///         Box::new(Node::Literal(&source[1..7])),
+///         Box::new(Node::Literal(&source[8..16])),
+///     ),
+///     Node::Property(
+///         Box::new(Node::Literal(&source[17..22])),
+///         Box::new(Node::Literal(&source[23..24])),
+///     ),
+/// ]);
+///
+/// assert!(Check::default().check(source, &ast, "name:string, age:number").is_ok());
+/// assert!(Check::default().check(source, &ast, "age:string").is_err());
+/// ```
+#[derive(Debug, Default)]
+pub struct Check {
+    colored_errors: bool,
+}
+
+impl Check {
+    /// Underline the property in error previews with ANSI escape codes
+    pub fn with_colored_errors(self, colored_errors: bool) -> Self {
+        Self { colored_errors }
+    }
+
+    pub fn check(&self, source: &str, ast: &Node, require: &str) -> Result<(), CheckError> {
+        let requirements = self.requirements(require)?;
+
+        let Node::Object(children) = ast else {
+            return Err(CheckError::NotAnObject {
+                found: ast.type_name().to_string(),
+            });
+        };
+
+        for (property, expected) in requirements {
+            let (key, value) = children
+                .iter()
+                .find_map(|child| match child {
+                    Node::Property(key, value) => match key.as_ref() {
+                        Node::Literal(literal) if self.unquote(literal) == property => {
+                            Some((*literal, value.as_ref()))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .ok_or_else(|| CheckError::MissingProperty {
+                    property: property.to_string(),
+                })?;
+
+            if value.type_name() != expected {
+                let (start, line, column) = self.location(source, key);
+
+                return Err(CheckError::TypeMismatch {
+                    property: property.to_string(),
+                    expected: expected.to_string(),
+                    found: value.type_name().to_string(),
+                    error_preview: ErrorPreview.preview_span(
+                        source,
+                        start,
+                        start + key.len(),
+                        column,
+                        line,
+                        self.colored_errors,
+                    ),
+                    line,
+                    column,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn requirements<'a>(&self, require: &'a str) -> Result<Vec<(&'a str, &'a str)>, CheckError> {
+        require
+            .split(',')
+            .map(|requirement| match requirement.split_once(':') {
+                Some((property, expected))
+                    if !property.trim().is_empty() && TYPES.contains(&expected.trim()) =>
+                {
+                    Ok((property.trim(), expected.trim()))
+                }
+                _ => Err(CheckError::InvalidRequirement {
+                    requirement: requirement.trim().to_string(),
+                }),
+            })
+            .collect()
+    }
+
+    /// Byte offset, line number and column of a slice taken from the source
+    fn location(&self, source: &str, slice: &str) -> (usize, usize, usize) {
+        let start = (slice.as_ptr() as usize)
+            .checked_sub(source.as_ptr() as usize)
+            .filter(|start| source.is_char_boundary(*start))
+            .unwrap_or(0);
+
+        let before = &source[..start];
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .chars()
+            .rev()
+            .take_while(|&char| char != '\n')
+            .count()
+            + 1;
+
+        (start, line, column)
+    }
+
+    fn unquote<'a>(&self, literal: &'a str) -> &'a str {
+        literal
+            .strip_prefix('"')
+            .and_then(|literal| literal.strip_suffix('"'))
+            .unwrap_or(literal)
+    }
+}
+
+#[cfg(test)]
+mod check_tests {
+    use parser::parser::Parser;
+    use scanner::scanner::Scanner;
+
+    use super::*;
+
+    fn check(source: &str, require: &str) -> Result<(), CheckError> {
+        let tokens = Scanner::new(source).scan().unwrap();
+        let parser = Parser::new(source, tokens);
+        let ast = parser.parse().unwrap();
+
+        Check::default().check(source, &ast, require)
+    }
+
+    #[test]
+    fn pass_when_requirements_met() {
+        assert_eq!(
+            Ok(()),
+            check(
+                "{\"name\":\"jtools\",\"age\":1,\"tags\":[],\"extra\":null}",
+                "name:string,age:number, tags:array"
+            )
+        );
+    }
+
+    #[test]
+    fn error_on_missing_property() {
+        assert_eq!(
+            Err(CheckError::MissingProperty {
+                property: "age".to_string()
+            }),
+            check("{\"name\":\"jtools\"}", "name:string,age:number")
+        );
+    }
+
+    #[test]
+    fn error_on_type_mismatch() {
+        let error = check("{\n  \"age\": \"1\"\n}", "age:number").unwrap_err();
+
+        assert_eq!("type_mismatch", error.kind());
+        assert_eq!(Some((2, 3, "age")), error.position());
+    }
+
+    #[test]
+    fn error_on_invalid_requirement() {
+        assert_eq!(
+            Err(CheckError::InvalidRequirement {
+                requirement: "age:integer".to_string()
+            }),
+            check("{}", "age:integer")
+        );
+    }
+
+    #[test]
+    fn error_when_not_an_object() {
+        assert_eq!(
+            Err(CheckError::NotAnObject {
+                found: "array".to_string()
+            }),
+            check("[]", "age:number")
+        );
+    }
+}
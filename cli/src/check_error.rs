@@ -0,0 +1,111 @@
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq)]
+pub enum CheckError {
+    InvalidRequirement {
+        requirement: String,
+    },
+    NotAnObject {
+        found: String,
+    },
+    MissingProperty {
+        property: String,
+    },
+    TypeMismatch {
+        property: String,
+        expected: String,
+        found: String,
+        error_preview: String,
+        line: usize,
+        column: usize,
+    },
+}
+
+impl std::error::Error for CheckError {}
+
+impl CheckError {
+    /// Stable identifier for each error used by machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CheckError::InvalidRequirement { .. } => "invalid_requirement",
+            CheckError::NotAnObject { .. } => "not_an_object",
+            CheckError::MissingProperty { .. } => "missing_property",
+            CheckError::TypeMismatch { .. } => "type_mismatch",
+        }
+    }
+
+    /// Line number, display column and property name when the error points into the source
+    pub fn position(&self) -> Option<(usize, usize, &str)> {
+        match self {
+            CheckError::TypeMismatch {
+                property,
+                line,
+                column,
+                ..
+            } => Some((*line, *column, property)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for CheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckError::InvalidRequirement { requirement } => write!(
+                f,
+                "Invalid requirement \"{}\" expected <property>:<string | number | boolean | null | object | array>",
+                requirement
+            ),
+            CheckError::NotAnObject { found } => write!(f, "Expected object found {}", found),
+            CheckError::MissingProperty { property } => {
+                write!(f, "Missing required property \"{}\"", property)
+            }
+            CheckError::TypeMismatch {
+                property,
+                expected,
+                found,
+                error_preview,
+                ..
+            } => write!(
+                f,
+                "Expected property \"{}\" to be {} found {} {}",
+                property, expected, found, error_preview
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod check_error_tests {
+    use crate::check_error::CheckError;
+
+    #[test]
+    fn missing_property_message() {
+        assert_eq!(
+            "Missing required property \"name\"",
+            CheckError::MissingProperty {
+                property: "name".to_string()
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn type_mismatch_message() {
+        let error = CheckError::TypeMismatch {
+            property: "age".to_string(),
+            expected: "number".to_string(),
+            found: "string".to_string(),
+            error_preview: "error preview".to_string(),
+            line: 2,
+            column: 3,
+        };
+
+        assert_eq!(
+            "Expected property \"age\" to be number found string error preview",
+            error.to_string()
+        );
+        assert_eq!("type_mismatch", error.kind());
+        assert_eq!(Some((2, 3, "age")), error.position());
+    }
+}
@@ -1,22 +1,28 @@
 use std::path::PathBuf;
 
-use clap::{value_parser, ArgAction, Parser, Subcommand};
+use clap::{value_parser, ArgAction, Parser, Subcommand, ValueEnum};
+use token::parse_options::RootKind;
 
-#[derive(Subcommand, Debug, PartialEq)]
+#[derive(Subcommand, Clone, Debug, PartialEq)]
 pub enum Input {
-    /// File path
+    /// One or more file paths
     File {
-        path: PathBuf,
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
 
         /// Prevent file from being overriden with either formatted or minified output
         #[arg(short, long, default_value_t = false, action = ArgAction::SetTrue)]
         prevent_override: bool,
+
+        /// Copy the original file to <path>.bak before it is overriden
+        #[arg(short, long, default_value_t = false, action = ArgAction::SetTrue)]
+        backup: bool,
     },
     /// Text input
     Text { input: String },
 }
 
-#[derive(Subcommand, Debug, PartialEq)]
+#[derive(Subcommand, Clone, Debug, PartialEq)]
 pub enum Command {
     /// Parse
     Parse {
@@ -24,6 +30,34 @@ pub enum Command {
         #[arg(short, long, default_value_t = false)]
         verify: bool,
 
+        /// Report metrics such as the number of nodes and max depth after parsing
+        #[arg(short, long, default_value_t = false)]
+        stats: bool,
+
+        /// Print a hash of the document's values that ignores key order and whitespace
+        #[arg(short, long, default_value_t = false)]
+        fingerprint: bool,
+
+        /// Print the parsed AST as an indented tree of nodes instead of the JSON
+        #[arg(short, long, default_value_t = false)]
+        ast: bool,
+
+        /// Print the time spent scanning and parsing, and the throughput in MB/s, to stderr
+        #[arg(short, long, default_value_t = false)]
+        time: bool,
+
+        /// Keep the last value of a duplicate key and print a warning to stderr instead of failing
+        #[arg(long, default_value_t = false)]
+        warn_duplicates: bool,
+
+        /// Print only the value at a JSON path such as "$.servers[0].port", formatted
+        #[arg(long)]
+        select: Option<String>,
+
+        /// Minify the value selected with --select instead of formatting it
+        #[arg(short, long, default_value_t = false, requires = "select")]
+        minify: bool,
+
         /// Prevent writing input to stdin if successful
         #[arg(short, long, default_value_t = false)]
         prevent_write: bool,
@@ -37,6 +71,48 @@ pub enum Command {
         #[arg(short, long, value_parser = value_parser!(u8).range(0..=8))]
         spacing: Option<u8>,
 
+        /// Indent with "tab" or a custom string of spaces and tabs instead of --spacing
+        #[arg(short, long, value_parser = parse_indent, conflicts_with = "spacing")]
+        indent: Option<String>,
+
+        /// End the output with a newline when overriding files
+        #[arg(short, long, default_value_t = false)]
+        trailing_newline: bool,
+
+        /// Allow JSONC comments and keep those written before object properties
+        #[arg(short, long, default_value_t = false)]
+        keep_comments: bool,
+
+        /// Keep the last value of a duplicate key and print a warning to stderr instead of failing
+        #[arg(long, default_value_t = false)]
+        warn_duplicates: bool,
+
+        /// Escape non-ASCII characters in strings as \uXXXX
+        #[arg(short, long, default_value_t = false)]
+        ascii: bool,
+
+        /// Keep running and format the file again whenever it changes, until interrupted
+        #[cfg(feature = "watch")]
+        #[arg(short, long, default_value_t = false)]
+        watch: bool,
+
+        /// Prevent writing input to stdin if successful
+        #[arg(short, long, default_value_t = false)]
+        prevent_write: bool,
+
+        /// Write the output to this path instead of overriding the input or writing to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(subcommand)]
+        input: Input,
+    },
+    /// Check that top-level properties exist with the expected types
+    Check {
+        /// Comma separated list of property:type pairs e.g. "name:string,age:number"
+        #[arg(short, long)]
+        require: String,
+
         /// Prevent writing input to stdin if successful
         #[arg(short, long, default_value_t = false)]
         prevent_write: bool,
@@ -44,25 +120,233 @@ pub enum Command {
         #[command(subcommand)]
         input: Input,
     },
+    /// Compare two files structurally, ignoring key order and formatting
+    Diff {
+        /// Original file path
+        a: PathBuf,
+
+        /// Changed file path
+        b: PathBuf,
+    },
+    /// Deep merge an overlay file into a base file, printing the formatted result
+    Merge {
+        /// Base file path
+        base: PathBuf,
+
+        /// File path whose values override the base
+        overlay: PathBuf,
+
+        /// Join arrays instead of replacing the base array with the overlay
+        #[arg(short, long, default_value_t = false)]
+        concat_arrays: bool,
+    },
+    /// Convert JSON into another format such as flattened path=value lines
+    Convert {
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = ConvertFormat::Flat)]
+        format: ConvertFormat,
+
+        /// Remove the quotes around string values
+        #[arg(short, long, default_value_t = false)]
+        unquote_strings: bool,
+
+        #[command(subcommand)]
+        input: Input,
+    },
+    /// Sort object keys recursively keeping the existing indentation unless one is given
+    Sort {
+        /// Specifiy the number of spaces (0-8) to apply to the input
+        #[arg(short, long, value_parser = value_parser!(u8).range(0..=8))]
+        spacing: Option<u8>,
+
+        /// Indent with "tab" or a custom string of spaces and tabs instead of --spacing
+        #[arg(short, long, value_parser = parse_indent, conflicts_with = "spacing")]
+        indent: Option<String>,
+
+        /// End the output with a newline when overriding files
+        #[arg(short, long, default_value_t = false)]
+        trailing_newline: bool,
+
+        /// Prevent writing input to stdin if successful
+        #[arg(short, long, default_value_t = false)]
+        prevent_write: bool,
+
+        /// Write the output to this path instead of overriding the input or writing to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(subcommand)]
+        input: Input,
+    },
+    /// Print the tokens produced by the scanner, one per line, for debugging
+    #[command(hide = true)]
+    Tokens {
+        #[command(subcommand)]
+        input: Input,
+    },
     /// Minify
     Minify {
+        /// Rewrite numbers to their shortest equivalent e.g. 1.0 -> 1
+        #[arg(short, long, default_value_t = false)]
+        normalize_numbers: bool,
+
+        /// Escape non-ASCII characters in strings as \uXXXX
+        #[arg(short, long, default_value_t = false)]
+        ascii: bool,
+
+        /// Keep running and minify the file again whenever it changes, until interrupted
+        #[cfg(feature = "watch")]
+        #[arg(short, long, default_value_t = false)]
+        watch: bool,
+
         /// Prevent writing input to stdin if successful
         #[arg(short, long, default_value_t = false)]
         prevent_write: bool,
 
+        /// Write the output to this path instead of overriding the input or writing to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
         #[command(subcommand)]
         input: Input,
     },
 }
 
+fn parse_indent(indent: &str) -> Result<String, String> {
+    match indent {
+        "tab" => Ok("\t".to_string()),
+        indent if indent.chars().all(|char| char == ' ' || char == '\t') => Ok(indent.to_string()),
+        _ => Err("indent must be \"tab\" or only contain spaces and tabs".to_string()),
+    }
+}
+
+impl Command {
+    /// Split a command given several file paths into one command per file
+    ///
+    /// Commands writing to a single output path are not split so they fail as a whole.
+    pub fn per_file(self) -> Vec<Command> {
+        let paths = match self.input() {
+            Some(Input::File { paths, .. }) if paths.len() > 1 && self.output().is_none() => {
+                paths.clone()
+            }
+            _ => return vec![self],
+        };
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let mut command = self.clone();
+
+                if let Some(Input::File { paths, .. }) = command.input_mut() {
+                    *paths = vec![path];
+                }
+
+                command
+            })
+            .collect()
+    }
+
+    /// Whether to keep running and process the input again whenever it changes
+    #[cfg(feature = "watch")]
+    pub fn watch(&self) -> bool {
+        matches!(
+            self,
+            Command::Format { watch: true, .. } | Command::Minify { watch: true, .. }
+        )
+    }
+
+    /// Path to write the output to instead of the input
+    pub fn output(&self) -> Option<&PathBuf> {
+        match self {
+            Command::Format { output, .. }
+            | Command::Sort { output, .. }
+            | Command::Minify { output, .. } => output.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn input(&self) -> Option<&Input> {
+        match self {
+            Command::Parse { input, .. }
+            | Command::Format { input, .. }
+            | Command::Check { input, .. }
+            | Command::Convert { input, .. }
+            | Command::Sort { input, .. }
+            | Command::Tokens { input }
+            | Command::Minify { input, .. } => Some(input),
+            Command::Diff { .. } | Command::Merge { .. } => None,
+        }
+    }
+
+    fn input_mut(&mut self) -> Option<&mut Input> {
+        match self {
+            Command::Parse { input, .. }
+            | Command::Format { input, .. }
+            | Command::Check { input, .. }
+            | Command::Convert { input, .. }
+            | Command::Sort { input, .. }
+            | Command::Tokens { input }
+            | Command::Minify { input, .. } => Some(input),
+            Command::Diff { .. } | Command::Merge { .. } => None,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum ConvertFormat {
+    /// One path=value line per leaf e.g. servers[0].port=80
+    Flat,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum ErrorFormat {
+    /// Human readable error with a preview of the source
+    Text,
+    /// Machine readable JSON object written to stderr
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum Root {
+    Any,
+    Object,
+    Array,
+}
+
+impl From<Root> for RootKind {
+    fn from(root: Root) -> Self {
+        match root {
+            Root::Any => RootKind::Any,
+            Root::Object => RootKind::Object,
+            Root::Array => RootKind::Array,
+        }
+    }
+}
+
 #[derive(Parser, Debug, PartialEq)]
 #[command(name = "jtools", version)]
 pub struct CliArgs {
     #[command(subcommand)]
     pub command: Command,
+
+    /// Format used to report errors
+    #[arg(long, global = true, value_enum, default_value_t = ErrorFormat::Text)]
+    pub error_format: ErrorFormat,
+
+    /// Largest input file in bytes that will be read. Default=100MiB
+    #[arg(long, global = true, default_value_t = MAX_SIZE)]
+    pub max_size: u64,
+
+    /// Type the top-level value must have
+    #[arg(long, global = true, value_enum, default_value_t = Root::Any)]
+    pub root: Root,
 }
 
+/// Default limit of `--max-size` so large files are rejected before being read into memory
+pub const MAX_SIZE: u64 = 100 * 1024 * 1024;
+
 #[cfg(test)]
+#[allow(clippy::needless_borrows_for_generic_args)]
 mod cli_args_tests {
     use super::*;
 
@@ -72,31 +356,418 @@ mod cli_args_tests {
             CliArgs {
                 command: Command::Format {
                     spacing: Some(8),
+                    indent: None,
+                    trailing_newline: false,
+                    keep_comments: false,
+                    warn_duplicates: false,
+                    ascii: false,
+                    #[cfg(feature = "watch")]
+                    watch: false,
                     prevent_write: false,
+                    output: None,
                     input: Input::File {
                         prevent_override: false,
-                        path: PathBuf::from("data.json")
+                        backup: false,
+                        paths: vec![PathBuf::from("data.json")]
                     }
-                }
+                },
+                error_format: ErrorFormat::Text,
+                max_size: MAX_SIZE,
+                root: Root::Any,
             },
             CliArgs::parse_from(&["", "format", "-s", "8", "file", "data.json"])
         )
     }
 
+    #[test]
+    fn check_with_requirements() {
+        assert_eq!(
+            CliArgs {
+                command: Command::Check {
+                    require: "name:string,age:number".to_string(),
+                    prevent_write: false,
+                    input: Input::Text {
+                        input: "{}".to_string()
+                    }
+                },
+                error_format: ErrorFormat::Text,
+                max_size: MAX_SIZE,
+                root: Root::Any,
+            },
+            CliArgs::parse_from(["", "check", "-r", "name:string,age:number", "text", "{}"])
+        )
+    }
+
+    #[test]
+    fn format_with_trailing_newline() {
+        assert_eq!(
+            CliArgs {
+                command: Command::Format {
+                    spacing: None,
+                    indent: None,
+                    trailing_newline: true,
+                    keep_comments: false,
+                    warn_duplicates: false,
+                    ascii: false,
+                    #[cfg(feature = "watch")]
+                    watch: false,
+                    prevent_write: false,
+                    output: None,
+                    input: Input::File {
+                        prevent_override: false,
+                        backup: false,
+                        paths: vec![PathBuf::from("data.json")]
+                    }
+                },
+                error_format: ErrorFormat::Text,
+                max_size: MAX_SIZE,
+                root: Root::Any,
+            },
+            CliArgs::parse_from(["", "format", "-t", "file", "data.json"])
+        )
+    }
+
+    #[test]
+    #[cfg(feature = "watch")]
+    fn format_and_minify_with_watch() {
+        let watch = |args: &[&str]| CliArgs::parse_from(args).command.watch();
+
+        assert!(watch(&["", "format", "-w", "file", "data.json"]));
+        assert!(watch(&["", "minify", "--watch", "file", "data.json"]));
+        assert!(!watch(&["", "format", "file", "data.json"]));
+        assert!(CliArgs::try_parse_from(["", "parse", "--watch", "file", "data.json"]).is_err());
+    }
+
+    #[test]
+    fn diff_two_files() {
+        assert_eq!(
+            CliArgs {
+                command: Command::Diff {
+                    a: PathBuf::from("old.json"),
+                    b: PathBuf::from("new.json"),
+                },
+                error_format: ErrorFormat::Text,
+                max_size: MAX_SIZE,
+                root: Root::Any,
+            },
+            CliArgs::parse_from(["", "diff", "old.json", "new.json"])
+        )
+    }
+
+    #[test]
+    fn merge_two_files() {
+        assert_eq!(
+            Command::Merge {
+                base: PathBuf::from("base.json"),
+                overlay: PathBuf::from("local.json"),
+                concat_arrays: true,
+            },
+            CliArgs::parse_from(["", "merge", "-c", "base.json", "local.json"]).command
+        )
+    }
+
+    #[test]
+    fn dump_tokens() {
+        assert_eq!(
+            Command::Tokens {
+                input: Input::Text {
+                    input: "[]".to_string()
+                },
+            },
+            CliArgs::parse_from(["", "tokens", "text", "[]"]).command
+        )
+    }
+
+    #[test]
+    fn convert_to_flat() {
+        assert_eq!(
+            CliArgs {
+                command: Command::Convert {
+                    format: ConvertFormat::Flat,
+                    unquote_strings: true,
+                    input: Input::Text {
+                        input: "{}".to_string()
+                    }
+                },
+                error_format: ErrorFormat::Text,
+                max_size: MAX_SIZE,
+                root: Root::Any,
+            },
+            CliArgs::parse_from(["", "convert", "--format", "flat", "-u", "text", "{}"])
+        )
+    }
+
+    #[test]
+    fn minify_to_output() {
+        let CliArgs { command, .. } =
+            CliArgs::parse_from(["", "minify", "-o", "min/data.json", "file", "data.json"]);
+
+        assert_eq!(Some(&PathBuf::from("min/data.json")), command.output());
+    }
+
+    #[test]
+    fn parse_with_root() {
+        let CliArgs { root, .. } =
+            CliArgs::parse_from(["", "parse", "--root", "object", "text", "{}"]);
+
+        assert_eq!(Root::Object, root);
+        assert_eq!(RootKind::Object, RootKind::from(root));
+    }
+
+    #[test]
+    fn parse_with_max_size() {
+        let CliArgs { max_size, .. } =
+            CliArgs::parse_from(["", "parse", "--max-size", "1024", "file", "data.json"]);
+
+        assert_eq!(1024, max_size);
+    }
+
+    #[test]
+    fn split_multiple_files() {
+        let CliArgs { command, .. } =
+            CliArgs::parse_from(["", "minify", "file", "-p", "a.json", "b.json"]);
+
+        assert_eq!(
+            vec![
+                Command::Minify {
+                    normalize_numbers: false,
+                    ascii: false,
+                    #[cfg(feature = "watch")]
+                    watch: false,
+                    prevent_write: false,
+                    output: None,
+                    input: Input::File {
+                        paths: vec![PathBuf::from("a.json")],
+                        prevent_override: true,
+                        backup: false,
+                    }
+                },
+                Command::Minify {
+                    normalize_numbers: false,
+                    ascii: false,
+                    #[cfg(feature = "watch")]
+                    watch: false,
+                    prevent_write: false,
+                    output: None,
+                    input: Input::File {
+                        paths: vec![PathBuf::from("b.json")],
+                        prevent_override: true,
+                        backup: false,
+                    }
+                },
+            ],
+            command.per_file()
+        )
+    }
+
+    #[test]
+    fn sort_with_spacing() {
+        let CliArgs { command, .. } =
+            CliArgs::parse_from(["", "sort", "-s", "2", "file", "config.json"]);
+
+        assert_eq!(
+            Command::Sort {
+                spacing: Some(2),
+                indent: None,
+                trailing_newline: false,
+                prevent_write: false,
+                output: None,
+                input: Input::File {
+                    paths: vec![PathBuf::from("config.json")],
+                    prevent_override: false,
+                    backup: false,
+                },
+            },
+            command
+        );
+    }
+
+    #[test]
+    fn format_with_indent() {
+        let CliArgs { command, .. } =
+            CliArgs::parse_from(["", "format", "--indent", "tab", "text", "[]"]);
+
+        assert!(matches!(command, Command::Format { indent: Some(indent), .. } if indent == "\t"));
+
+        let CliArgs { command, .. } =
+            CliArgs::parse_from(["", "format", "-i", " \t", "text", "[]"]);
+
+        assert!(matches!(command, Command::Format { indent: Some(indent), .. } if indent == " \t"));
+
+        assert!(CliArgs::try_parse_from(["", "format", "-i", "--", "text", "[]"]).is_err());
+        assert!(
+            CliArgs::try_parse_from(["", "format", "-i", "tab", "-s", "2", "text", "[]"]).is_err()
+        );
+    }
+
     #[test]
     fn parse_print_and_verify() {
         assert_eq!(
             CliArgs {
                 command: Command::Parse {
                     verify: true,
+                    stats: false,
+                    fingerprint: false,
+                    ast: false,
+                    time: false,
+                    warn_duplicates: false,
+                    select: None,
+                    minify: false,
                     prevent_write: true,
                     input: Input::File {
                         prevent_override: true,
-                        path: PathBuf::from("data.json")
+                        backup: false,
+                        paths: vec![PathBuf::from("data.json")]
                     }
-                }
+                },
+                error_format: ErrorFormat::Text,
+                max_size: MAX_SIZE,
+                root: Root::Any,
             },
             CliArgs::parse_from(&["", "parse", "-p", "-v", "file", "-p", "data.json"])
         )
     }
+
+    #[test]
+    fn parse_with_stats() {
+        assert_eq!(
+            CliArgs {
+                command: Command::Parse {
+                    verify: false,
+                    stats: true,
+                    fingerprint: false,
+                    time: false,
+                    warn_duplicates: false,
+                    ast: false,
+                    select: None,
+                    minify: false,
+                    prevent_write: false,
+                    input: Input::Text {
+                        input: "[]".to_string()
+                    }
+                },
+                error_format: ErrorFormat::Text,
+                max_size: MAX_SIZE,
+                root: Root::Any,
+            },
+            CliArgs::parse_from(["", "parse", "--stats", "text", "[]"])
+        )
+    }
+
+    #[test]
+    fn parse_with_select() {
+        let CliArgs { command, .. } =
+            CliArgs::parse_from(["", "parse", "--select", "$.a[0]", "-m", "text", "[]"]);
+
+        assert!(matches!(
+            command,
+            Command::Parse { select: Some(select), minify: true, .. } if select == "$.a[0]"
+        ));
+        assert!(CliArgs::try_parse_from(["", "parse", "-m", "text", "[]"]).is_err());
+    }
+
+    #[test]
+    fn parse_with_ast() {
+        let CliArgs { command, .. } = CliArgs::parse_from(["", "parse", "-a", "text", "[]"]);
+
+        assert!(matches!(command, Command::Parse { ast: true, .. }));
+    }
+
+    #[test]
+    fn parse_with_time() {
+        let CliArgs { command, .. } = CliArgs::parse_from(["", "parse", "-t", "-v", "text", "[]"]);
+
+        assert!(matches!(
+            command,
+            Command::Parse {
+                time: true,
+                verify: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn format_file_with_backup() {
+        assert_eq!(
+            CliArgs {
+                command: Command::Format {
+                    spacing: None,
+                    indent: None,
+                    trailing_newline: false,
+                    keep_comments: false,
+                    warn_duplicates: false,
+                    ascii: false,
+                    #[cfg(feature = "watch")]
+                    watch: false,
+                    prevent_write: false,
+                    output: None,
+                    input: Input::File {
+                        prevent_override: false,
+                        backup: true,
+                        paths: vec![PathBuf::from("data.json")]
+                    }
+                },
+                error_format: ErrorFormat::Text,
+                max_size: MAX_SIZE,
+                root: Root::Any,
+            },
+            CliArgs::parse_from(["", "format", "file", "--backup", "data.json"])
+        )
+    }
+
+    #[test]
+    fn minify_with_json_errors() {
+        assert_eq!(
+            CliArgs {
+                command: Command::Minify {
+                    normalize_numbers: false,
+                    ascii: false,
+                    #[cfg(feature = "watch")]
+                    watch: false,
+                    prevent_write: false,
+                    output: None,
+                    input: Input::Text {
+                        input: "[]".to_string()
+                    }
+                },
+                error_format: ErrorFormat::Json,
+                max_size: MAX_SIZE,
+                root: Root::Any,
+            },
+            CliArgs::parse_from(["", "minify", "--error-format", "json", "text", "[]"])
+        )
+    }
+
+    #[test]
+    fn minify_with_normalized_numbers() {
+        assert_eq!(
+            CliArgs {
+                command: Command::Minify {
+                    normalize_numbers: true,
+                    ascii: false,
+                    #[cfg(feature = "watch")]
+                    watch: false,
+                    prevent_write: false,
+                    output: None,
+                    input: Input::Text {
+                        input: "[1.0]".to_string()
+                    }
+                },
+                error_format: ErrorFormat::Text,
+                max_size: MAX_SIZE,
+                root: Root::Any,
+            },
+            CliArgs::parse_from(["", "minify", "-n", "text", "[1.0]"])
+        )
+    }
+
+    #[test]
+    fn minify_with_ascii() {
+        let CliArgs { command, .. } = CliArgs::parse_from(["", "minify", "-a", "text", "[]"]);
+        assert!(matches!(command, Command::Minify { ascii: true, .. }));
+
+        let CliArgs { command, .. } = CliArgs::parse_from(["", "format", "--ascii", "text", "[]"]);
+        assert!(matches!(command, Command::Format { ascii: true, .. }));
+    }
 }
@@ -0,0 +1,141 @@
+use std::{error::Error, fmt::Display, io};
+
+use parser::parser_error::ParserError;
+use scanner::scanner_error::ScannerError;
+
+use crate::check_error::CheckError;
+
+/// Error from running a single command
+///
+/// ## Description
+///
+/// Errors in the input JSON, from scanning, parsing or checking, exit with code 1. Errors reading
+/// or writing files and input that cannot be processed at all, such as a file that is not `.json`,
+/// exit with code 2 so scripts can tell a bad document apart from a failed run.
+#[derive(Debug)]
+pub enum CliError {
+    Io(io::Error),
+    InvalidInput(String),
+    /// JSON path given to `parse --select` that does not match any value
+    PathNotFound(String),
+    Scan(ScannerError),
+    Parse(ParserError),
+    Check(CheckError),
+}
+
+impl CliError {
+    /// Stable identifier for each error used by machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CliError::Io(_) => "io",
+            CliError::InvalidInput(_) => "invalid_input",
+            CliError::PathNotFound(_) => "path_not_found",
+            CliError::Scan(error) => error.kind(),
+            CliError::Parse(error) => error.kind(),
+            CliError::Check(error) => error.kind(),
+        }
+    }
+
+    /// Line number, display column and offending slice when the error points into the source
+    pub fn position(&self) -> Option<(usize, usize, &str)> {
+        match self {
+            CliError::Scan(error) => Some(error.position()),
+            CliError::Parse(error) => Some(error.position()),
+            CliError::Check(error) => error.position(),
+            CliError::Io(_) | CliError::InvalidInput(_) | CliError::PathNotFound(_) => None,
+        }
+    }
+
+    /// Process exit code, 1 for errors in the document and 2 for errors running the command
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::PathNotFound(_)
+            | CliError::Scan(_)
+            | CliError::Parse(_)
+            | CliError::Check(_) => 1,
+            CliError::Io(_) | CliError::InvalidInput(_) => 2,
+        }
+    }
+}
+
+impl Error for CliError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CliError::Io(error) => Some(error),
+            CliError::InvalidInput(_) | CliError::PathNotFound(_) => None,
+            CliError::Scan(error) => Some(error),
+            CliError::Parse(error) => Some(error),
+            CliError::Check(error) => Some(error),
+        }
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Io(error) => write!(f, "{}", error),
+            CliError::InvalidInput(message) => write!(f, "{}", message),
+            CliError::PathNotFound(path) => write!(f, "Path not found: {}", path),
+            CliError::Scan(error) => write!(f, "{}", error),
+            CliError::Parse(error) => write!(f, "{}", error),
+            CliError::Check(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(error: io::Error) -> Self {
+        CliError::Io(error)
+    }
+}
+
+impl From<ScannerError> for CliError {
+    fn from(error: ScannerError) -> Self {
+        CliError::Scan(error)
+    }
+}
+
+impl From<ParserError> for CliError {
+    fn from(error: ParserError) -> Self {
+        CliError::Parse(error)
+    }
+}
+
+impl From<CheckError> for CliError {
+    fn from(error: CheckError) -> Self {
+        CliError::Check(error)
+    }
+}
+
+#[cfg(test)]
+mod cli_error_tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes_by_kind() {
+        let io = CliError::from(io::Error::new(io::ErrorKind::NotFound, "missing"));
+        let invalid = CliError::InvalidInput("only .json files are supported".to_string());
+        let check = CliError::from(CheckError::MissingProperty {
+            property: "name".to_string(),
+        });
+
+        assert_eq!(
+            (2, "io", "missing"),
+            (io.exit_code(), io.kind(), io.to_string().as_str())
+        );
+        assert_eq!((2, "invalid_input"), (invalid.exit_code(), invalid.kind()));
+        assert_eq!((1, "missing_property"), (check.exit_code(), check.kind()));
+        assert!(io.position().is_none());
+
+        let missing = CliError::PathNotFound("$.a[0]".to_string());
+
+        assert_eq!(
+            (1, "path_not_found", "Path not found: $.a[0]"),
+            (
+                missing.exit_code(),
+                missing.kind(),
+                missing.to_string().as_str()
+            )
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::fmt::Display;
+
+use ast::node::Node;
+use format::{literal::canonical_literal, minifier::Minifier};
+
+/// A single structural difference between two documents
+#[derive(Debug, PartialEq)]
+pub enum Difference {
+    Added {
+        path: String,
+        value: String,
+    },
+    Removed {
+        path: String,
+        value: String,
+    },
+    Changed {
+        path: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difference::Added { path, value } => write!(f, "+ {}: {}", path, value),
+            Difference::Removed { path, value } => write!(f, "- {}: {}", path, value),
+            Difference::Changed { path, from, to } => write!(f, "~ {}: {} -> {}", path, from, to),
+        }
+    }
+}
+
+/// Structural diff of two ASTs
+///
+/// ## Description
+///
+/// The two trees are walked in parallel. Objects are compared by key, ignoring the order of
+/// properties, and arrays are compared by index. Literals are compared by their canonical form,
+/// see `format::literal`, so `1.0` and `1` or `"\u0041"` and `"A"` are equal. Formatting is
+/// never reported as a difference.
+///
+/// Each difference is reported with its path, for example `servers[0].port`, where `$` is the
+/// root of the document. Values are shown minified.
+///
+/// ## Examples
+///
+/// ```
+/// use ast::node::Node;
+/// use cli::diff::{Diff, Difference};
+///
+/// let a = Node::Object(vec![Node::Property(
+///     Box::new(Node::Literal("\"port\"")),
+///     Box::new(Node::Literal("80")),
+/// )]);
+///
+/// let b = Node::Object(vec![Node::Property(
+///     Box::new(Node::Literal("\"port\"")),
+///     Box::new(Node::Literal("443")),
+/// )]);
+///
+/// assert_eq!(
+///     vec![Difference::Changed {
+///         path: "port".to_string(),
+///         from: "80".to_string(),
+///         to: "443".to_string(),
+///     }],
+///     Diff.diff(&a, &b)
+/// );
+/// ```
+pub struct Diff;
+
+impl Diff {
+    pub fn diff(&self, a: &Node, b: &Node) -> Vec<Difference> {
+        let mut differences = vec![];
+        self.depth_traversal(a, b, "", &mut differences);
+        differences
+    }
+
+    fn depth_traversal(&self, a: &Node, b: &Node, path: &str, differences: &mut Vec<Difference>) {
+        match (a, b) {
+            (Node::Object(a_children), Node::Object(b_children)) => {
+                let a_properties = self.properties(a_children);
+                let b_properties = self.properties(b_children);
+
+                for (key, a_value) in &a_properties {
+                    let path = self.key_path(path, key);
+
+                    match b_properties.iter().find(|(b_key, _)| b_key == key) {
+                        Some((_, b_value)) => {
+                            self.depth_traversal(a_value, b_value, &path, differences)
+                        }
+                        None => differences.push(Difference::Removed {
+                            path,
+                            value: self.value(a_value),
+                        }),
+                    }
+                }
+
+                for (key, b_value) in &b_properties {
+                    if !a_properties.iter().any(|(a_key, _)| a_key == key) {
+                        differences.push(Difference::Added {
+                            path: self.key_path(path, key),
+                            value: self.value(b_value),
+                        });
+                    }
+                }
+            }
+            (Node::Array(a_children), Node::Array(b_children)) => {
+                for index in 0..a_children.len().max(b_children.len()) {
+                    let path = format!("{}[{}]", path, index);
+
+                    match (a_children.get(index), b_children.get(index)) {
+                        (Some(a), Some(b)) => self.depth_traversal(a, b, &path, differences),
+                        (Some(a), None) => differences.push(Difference::Removed {
+                            path,
+                            value: self.value(a),
+                        }),
+                        (None, Some(b)) => differences.push(Difference::Added {
+                            path,
+                            value: self.value(b),
+                        }),
+                        (None, None) => {}
+                    }
+                }
+            }
+            (Node::Literal(a_literal), Node::Literal(b_literal))
+                if canonical_literal(a_literal) == canonical_literal(b_literal) => {}
+            _ => differences.push(Difference::Changed {
+                path: if path.is_empty() { "$" } else { path }.to_string(),
+                from: self.value(a),
+                to: self.value(b),
+            }),
+        }
+    }
+
+    /// Canonical key and value of each property of an object
+    fn properties<'a, 'source>(
+        &self,
+        children: &'a [Node<'source>],
+    ) -> Vec<(String, &'a Node<'source>)> {
+        children
+            .iter()
+            .filter_map(|child| match child {
+                Node::Property(key, value) => match key.as_ref() {
+                    Node::Literal(key) => Some((canonical_literal(key), value.as_ref())),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn key_path(&self, path: &str, key: &str) -> String {
+        let key = key
+            .strip_prefix('"')
+            .and_then(|key| key.strip_suffix('"'))
+            .unwrap_or(key);
+
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    }
+
+    fn value(&self, node: &Node) -> String {
+        Minifier::default().minify(node)
+    }
+}
+
+#[cfg(test)]
+mod diff_tests {
+    use parser::parser::Parser;
+    use scanner::scanner::Scanner;
+
+    use super::*;
+
+    fn diff(a: &str, b: &str) -> Vec<String> {
+        let a_tokens = Scanner::new(a).scan().unwrap();
+        let a_parser = Parser::new(a, a_tokens);
+        let a_ast = a_parser.parse().unwrap();
+
+        let b_tokens = Scanner::new(b).scan().unwrap();
+        let b_parser = Parser::new(b, b_tokens);
+        let b_ast = b_parser.parse().unwrap();
+
+        Diff.diff(&a_ast, &b_ast)
+            .iter()
+            .map(|difference| difference.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn ignore_order_and_formatting() {
+        assert!(diff(
+            "{\"a\":1.0,\"b\":[\"\\u0041\",null]}",
+            "{\n  \"b\": [\"A\", null],\n  \"a\": 1\n}"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn report_nested_changes() {
+        assert_eq!(
+            vec!["~ servers[0].port: 80 -> 443"],
+            diff(
+                "{\"servers\":[{\"port\":80}]}",
+                "{\"servers\":[{\"port\":443}]}"
+            )
+        );
+    }
+
+    #[test]
+    fn report_added_and_removed() {
+        assert_eq!(
+            vec![
+                "- name: \"jtools\"",
+                "+ tags[1]: {\"x\":[]}",
+                "+ version: 2"
+            ],
+            diff(
+                "{\"name\":\"jtools\",\"tags\":[1]}",
+                "{\"tags\":[1,{\"x\":[]}],\"version\":2}"
+            )
+        );
+    }
+
+    #[test]
+    fn report_type_changes() {
+        assert_eq!(vec!["~ $: [] -> {}"], diff("[]", "{}"));
+        assert_eq!(vec!["~ a: \"1\" -> 1"], diff("{\"a\":\"1\"}", "{\"a\":1}"));
+    }
+}
@@ -0,0 +1,142 @@
+use ast::node::Node;
+
+/// Flatten an AST into one `path=value` line per leaf
+///
+/// ## Description
+///
+/// A dotenv style view of nested JSON for grepping values in shell scripts. Object keys are
+/// joined with `.` and array indices are written in brackets, for example `servers[0].port=80`,
+/// where `$` is the root of the document. Empty objects and arrays are leaves written as `{}` and
+/// `[]` so no part of the document is lost.
+///
+/// Strings keep their quotes by default. When unquoted, escape sequences are left as written so
+/// every leaf stays on a single line.
+///
+/// ## Examples
+///
+/// ```
+/// use ast::node::Node;
+/// use cli::flatten::Flatten;
+///
+/// let ast = Node::Object(vec![Node::Property(
+///     Box::new(Node::Literal("\"a\"")),
+///     Box::new(Node::Object(vec![
+///         Node::Property(
+///             Box::new(Node::Literal("\"c\"")),
+///             Box::new(Node::Literal("1")),
+///         ),
+///         Node::Property(
+///             Box::new(Node::Literal("\"d\"")),
+///             Box::new(Node::Literal("\"two\"")),
+///         ),
+///     ])),
+/// )]);
+///
+/// assert_eq!(vec!["a.c=1", "a.d=\"two\""], Flatten::default().flatten(&ast));
+/// assert_eq!(
+///     vec!["a.c=1", "a.d=two"],
+///     Flatten::default().with_unquote_strings(true).flatten(&ast)
+/// );
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Flatten {
+    unquote_strings: bool,
+}
+
+impl Flatten {
+    pub fn with_unquote_strings(self, unquote_strings: bool) -> Self {
+        Self { unquote_strings }
+    }
+
+    pub fn flatten(&self, ast: &Node) -> Vec<String> {
+        let mut lines = vec![];
+        self.depth_traversal(ast, "", &mut lines);
+        lines
+    }
+
+    fn depth_traversal(&self, ast: &Node, path: &str, lines: &mut Vec<String>) {
+        match ast {
+            Node::Object(children) if !children.is_empty() => {
+                for (key, value) in ast.entries() {
+                    let path = if path.is_empty() {
+                        key.to_string()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+
+                    self.depth_traversal(value, &path, lines);
+                }
+            }
+            Node::Array(children) if !children.is_empty() => {
+                for (index, child) in ast.elements().enumerate() {
+                    self.depth_traversal(child, &format!("{}[{}]", path, index), lines);
+                }
+            }
+            Node::Property(_, value) => self.depth_traversal(value, path, lines),
+            _ => lines.push(format!(
+                "{}={}",
+                if path.is_empty() { "$" } else { path },
+                self.value(ast)
+            )),
+        }
+    }
+
+    fn value(&self, ast: &Node) -> String {
+        match ast {
+            Node::Literal(literal) if self.unquote_strings => literal
+                .strip_prefix('"')
+                .and_then(|literal| literal.strip_suffix('"'))
+                .unwrap_or(literal)
+                .to_string(),
+            _ => ast.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod flatten_tests {
+    use parser::parser::Parser;
+    use scanner::scanner::Scanner;
+
+    use super::*;
+
+    fn flatten(source: &str, unquote_strings: bool) -> Vec<String> {
+        let tokens = Scanner::new(source).scan().unwrap();
+        let parser = Parser::new(source, tokens);
+        let ast = parser.parse().unwrap();
+
+        Flatten::default()
+            .with_unquote_strings(unquote_strings)
+            .flatten(&ast)
+    }
+
+    #[test]
+    fn flatten_nested_objects_and_arrays() {
+        assert_eq!(
+            vec![
+                "name=\"jtools\"",
+                "servers[0].port=80",
+                "servers[0].tls=false",
+                "servers[1].port=443",
+                "tags=[]",
+                "meta={}",
+                "empty=null"
+            ],
+            flatten(
+                "{\"name\":\"jtools\",\"servers\":[{\"port\":80,\"tls\":false},{\"port\":443}],\
+                \"tags\":[],\"meta\":{},\"empty\":null}",
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn flatten_root_values() {
+        assert_eq!(vec!["$=1.5"], flatten("1.5", false));
+        assert_eq!(vec!["$={}"], flatten("{}", false));
+        assert_eq!(
+            vec!["[0]=true", "[1]=a\\nb"],
+            flatten("[true,\"a\\nb\"]", true)
+        );
+    }
+}
@@ -1,47 +1,318 @@
+use ast::node::Node;
+use check::Check;
 use clap::Parser as ClapParser;
-use cli_args::{CliArgs, Command, Input};
+use cli_args::{CliArgs, Command, ConvertFormat, ErrorFormat, Input};
+use cli_error::CliError;
+use diff::Diff;
+use flatten::Flatten;
 use format::{formatter::Formatter, minifier::Minifier};
-use parser::parser::Parser;
-use scanner::scanner::Scanner;
+use parser::{parser::Parser, warning::Warning};
+use scanner::{scanner::Scanner, scanner_error::ScannerError};
+use stats::Stats;
 use std::{
-    error::Error,
-    fs::{self, OpenOptions},
-    io::{self, stderr, stdout, Write},
+    fs::{self, File, OpenOptions},
+    io::{self, stderr, stdout, BufWriter, IsTerminal, Read, Write},
+    path::Path,
+    process::ExitCode,
+    time::Instant,
+};
+use timing::Timing;
+use token::{
+    escape::escape_json_string,
+    parse_options::{DuplicateKeys, ParseOptions, RootKind},
+    token::Token,
 };
 
+pub mod check;
+pub mod check_error;
 pub mod cli_args;
+pub mod cli_error;
+pub mod diff;
+pub mod flatten;
+pub mod stats;
+pub mod timing;
+#[cfg(feature = "watch")]
+pub mod watch;
 
 pub struct Cli;
 
 impl Cli {
-    pub fn run(&self) -> Result<(), io::Error> {
-        let CliArgs { command } = CliArgs::parse();
+    /// Run the command once per input file, reporting every error before exiting with failure
+    ///
+    /// The exit code is the highest `CliError::exit_code` of all errors, 0 if there were none.
+    pub fn run(&self) -> Result<ExitCode, io::Error> {
+        let CliArgs {
+            command,
+            error_format,
+            max_size,
+            root,
+        } = CliArgs::parse();
+
+        let root = RootKind::from(root);
+
+        #[cfg(feature = "watch")]
+        let watch = command.watch();
+
+        let commands = command.per_file();
+        let is_batch = commands.len() > 1;
+        let mut exit_code = 0;
+
+        for command in &commands {
+            exit_code = exit_code.max(self.report(
+                command.clone(),
+                is_batch,
+                &error_format,
+                max_size,
+                root,
+            )?);
+        }
+
+        #[cfg(feature = "watch")]
+        if watch {
+            self.watch(&commands, &error_format, max_size, root)?;
+        }
+
+        Ok(ExitCode::from(exit_code))
+    }
+
+    /// Process a command and write its output to stdout or its error to stderr, returning the
+    /// exit code of the error or 0 if successful
+    fn report(
+        &self,
+        command: Command,
+        is_batch: bool,
+        error_format: &ErrorFormat,
+        max_size: u64,
+        root: RootKind,
+    ) -> Result<u8, io::Error> {
+        let path = match command.input() {
+            Some(Input::File { paths, .. }) if is_batch => {
+                format!("{}: ", paths[0].to_string_lossy())
+            }
+            _ => String::new(),
+        };
+
+        match (
+            self.process_command(command, error_format, max_size, root),
+            error_format,
+        ) {
+            (Ok(data), _) => writeln!(stdout(), "{}{}", path, data)?,
+            (Err(error), ErrorFormat::Text) => {
+                writeln!(stderr(), "{}{}", path, error)?;
+                return Ok(error.exit_code());
+            }
+            (Err(error), ErrorFormat::Json) => {
+                writeln!(stderr(), "{}", self.error_json(&error))?;
+                return Ok(error.exit_code());
+            }
+        }
+
+        Ok(0)
+    }
+
+    /// Process each command again whenever its input file changes, until interrupted
+    ///
+    /// Every change is reported with the path of the file. Text input has nothing to watch and
+    /// returns immediately.
+    #[cfg(feature = "watch")]
+    fn watch(
+        &self,
+        commands: &[Command],
+        error_format: &ErrorFormat,
+        max_size: u64,
+        root: RootKind,
+    ) -> Result<(), io::Error> {
+        let paths: Vec<_> = commands
+            .iter()
+            .filter_map(|command| match command.input() {
+                Some(Input::File { paths, .. }) => paths.first().cloned(),
+                _ => None,
+            })
+            .collect();
 
-        match self.process_command(command) {
-            Ok(data) => writeln!(stdout(), "{}", data),
-            Err(error) => writeln!(stderr(), "{}", error),
+        if paths.len() != commands.len() {
+            writeln!(stderr(), "Nothing to watch, --watch requires file input")?;
+            return Ok(());
+        }
+
+        let mut watcher = watch::Watcher::new(paths);
+
+        loop {
+            let index = watcher.wait();
+
+            self.report(commands[index].clone(), true, error_format, max_size, root)?;
+
+            watcher.mark_seen(index);
         }
     }
 
-    fn process_command(&self, command: Command) -> Result<String, Box<dyn Error>> {
+    fn error_json(&self, error: &CliError) -> String {
+        let kind = error.kind();
+        let message = self.json_string(&error.to_string());
+
+        match error.position() {
+            Some((line, column, slice)) => format!(
+                "{{\"kind\":\"{}\",\"message\":{},\"line\":{},\"column\":{},\"slice\":{}}}",
+                kind,
+                message,
+                line,
+                column,
+                self.json_string(slice)
+            ),
+            None => format!(
+                "{{\"kind\":\"{}\",\"message\":{},\"line\":null,\"column\":null,\"slice\":null}}",
+                kind, message
+            ),
+        }
+    }
+
+    /// Scan the source and create a parser with the `--root` type and error colours of `options`
+    fn parser<'a>(
+        &self,
+        source: &'a str,
+        options: ParseOptions,
+    ) -> Result<Parser<'a>, ScannerError> {
+        let tokens = Scanner::new(source).with_options(options).scan()?;
+
+        Ok(Parser::new(source, tokens).with_options(options))
+    }
+
+    /// Colour error previews with the `colored` feature, only for text errors written to a
+    /// terminal so JSON errors and redirected output stay plain
+    fn colored_errors(&self, error_format: &ErrorFormat) -> bool {
+        cfg!(feature = "colored")
+            && matches!(error_format, ErrorFormat::Text)
+            && stderr().is_terminal()
+    }
+
+    /// Duplicate key policy of `--warn-duplicates`, strict by default
+    fn duplicate_keys(&self, warn_duplicates: bool) -> DuplicateKeys {
+        if warn_duplicates {
+            DuplicateKeys::Warn
+        } else {
+            DuplicateKeys::Error
+        }
+    }
+
+    /// Write each warning to stderr in the `--error-format`, the command continues and still
+    /// succeeds
+    fn warn(&self, warnings: &[Warning], error_format: &ErrorFormat) -> Result<(), io::Error> {
+        for warning in warnings {
+            match error_format {
+                ErrorFormat::Text => writeln!(stderr(), "Warning: {}", warning)?,
+                ErrorFormat::Json => writeln!(stderr(), "{}", self.warning_json(warning))?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Same shape as `Cli::error_json`, told apart from errors by the warning `kind`
+    fn warning_json(&self, warning: &Warning) -> String {
+        let (line, column, slice) = warning.position();
+
+        format!(
+            "{{\"kind\":\"{}\",\"message\":{},\"line\":{},\"column\":{},\"slice\":{}}}",
+            warning.kind(),
+            self.json_string(&warning.to_string()),
+            line,
+            column,
+            self.json_string(slice)
+        )
+    }
+
+    fn json_string(&self, value: &str) -> String {
+        escape_json_string(value)
+    }
+
+    fn process_command(
+        &self,
+        command: Command,
+        error_format: &ErrorFormat,
+        max_size: u64,
+        root: RootKind,
+    ) -> Result<String, CliError> {
+        let parse_options = ParseOptions {
+            require_root: root,
+            colored_errors: self.colored_errors(error_format),
+            ..ParseOptions::default()
+        };
+
         match command {
             Command::Parse {
                 verify,
+                stats,
+                fingerprint,
+                ast: tree,
+                time,
+                warn_duplicates,
+                select,
+                minify,
                 prevent_write,
                 input,
             } => {
-                let source = self.source(&input)?;
+                let source = self.source(&input, max_size)?;
+
+                let start = Instant::now();
+                let parser = self
+                    .parser(&source, parse_options)?
+                    .with_duplicate_keys(self.duplicate_keys(warn_duplicates));
+                let scan = start.elapsed();
 
-                let mut scanner = Scanner::new(&source);
-                let tokens = scanner.scan()?;
+                // Reported before any parser error so failing documents can be timed too
+                let report_timing = || -> io::Result<()> {
+                    if time {
+                        let timing = Timing {
+                            bytes: source.len(),
+                            scan,
+                            parse: start.elapsed() - scan,
+                        };
 
-                let parser = Parser::new(&source, tokens);
+                        writeln!(stderr(), "{}", timing)?;
+                    }
+
+                    Ok(())
+                };
 
                 if verify && !prevent_write {
-                    return Ok(parser.is_valid().to_string());
+                    let valid = parser.is_valid();
+                    report_timing()?;
+
+                    return Ok(valid.to_string());
+                }
+
+                let ast = parser.parse_with_warnings();
+                report_timing()?;
+
+                let (ast, warnings) = ast?;
+                self.warn(&warnings, error_format)?;
+
+                let node = match &select {
+                    Some(path) => ast
+                        .query(path)
+                        .ok_or_else(|| CliError::PathNotFound(path.to_string()))?,
+                    None => &ast,
+                };
+
+                if stats {
+                    return Ok(Stats::from(node).to_string());
                 }
 
-                parser.parse()?;
+                if fingerprint {
+                    return Ok(format!("{:016x}", node.fingerprint()));
+                }
+
+                if tree {
+                    return Ok(node.to_tree_string());
+                }
+
+                if select.is_some() && !prevent_write {
+                    if minify {
+                        return Ok(Minifier::default().minify(node));
+                    }
+
+                    return Ok(Formatter::default().format(node));
+                }
 
                 if prevent_write {
                     return Ok("Parse successful".to_string());
@@ -52,97 +323,869 @@ impl Cli {
             Command::Format {
                 prevent_write,
                 spacing,
+                indent,
+                ascii,
+                #[cfg(feature = "watch")]
+                    watch: _,
+                trailing_newline,
+                keep_comments,
+                warn_duplicates,
+                output,
+                input,
+            } => {
+                let source = self.source(&input, max_size)?;
+
+                let options = ParseOptions {
+                    allow_comments: keep_comments,
+                    preserve_comments: keep_comments,
+                    duplicate_keys: self.duplicate_keys(warn_duplicates),
+                    ..parse_options
+                };
+
+                let tokens = Scanner::new(&source).with_options(options).scan()?;
+
+                let parser = Parser::new(&source, tokens.clone()).with_options(options);
+                let (ast, warnings) = parser.parse_with_warnings()?;
+                self.warn(&warnings, error_format)?;
+
+                let formatter = match (spacing, indent) {
+                    (_, Some(indent)) => Formatter::default().with_indent(indent),
+                    (Some(space), None) => Formatter::new(space as usize),
+                    (None, None) => Formatter::default(),
+                }
+                .with_trailing_newline(trailing_newline)
+                .with_ensure_ascii(ascii);
+
+                if keep_comments {
+                    let json = formatter.format_with_comments(&source, &tokens, &ast);
+
+                    if let Some(output) = output {
+                        self.write_output(&output, |file| file.write_all(json.as_bytes()))?;
+
+                        return Ok("Format successful".to_string());
+                    }
+
+                    self.is_file_then_override(&input, |file| file.write_all(json.as_bytes()))?;
+
+                    if prevent_write {
+                        return Ok("Format successful".to_string());
+                    }
+
+                    // stdout is already terminated with a newline
+                    return Ok(json.strip_suffix('\n').unwrap_or(&json).to_string());
+                }
+
+                if let Some(output) = output {
+                    self.write_output(&output, |file| formatter.format_to(&ast, file))?;
+
+                    return Ok("Format successful".to_string());
+                }
+
+                if prevent_write {
+                    self.is_file_then_override(&input, |file| formatter.format_to(&ast, file))?;
+
+                    return Ok("Format successful".to_string());
+                }
+
+                let json = formatter.format(&ast);
+
+                self.is_file_then_override(&input, |file| file.write_all(json.as_bytes()))?;
+
+                // stdout is already terminated with a newline
+                Ok(json.strip_suffix('\n').unwrap_or(&json).to_string())
+            }
+            Command::Check {
+                require,
+                prevent_write,
                 input,
             } => {
-                let source = self.source(&input)?;
+                let source = self.source(&input, max_size)?;
+
+                let parser = self.parser(&source, parse_options)?;
+                let ast = parser.parse()?;
 
-                let mut scanner = Scanner::new(&source);
-                let tokens = scanner.scan()?;
+                Check::default()
+                    .with_colored_errors(parse_options.colored_errors)
+                    .check(&source, &ast, &require)?;
 
-                let parser = Parser::new(&source, tokens);
+                if prevent_write {
+                    return Ok("Check successful".to_string());
+                }
+
+                Ok(source.to_string())
+            }
+            Command::Diff { a, b } => {
+                let a_source = self.read_file(&a, max_size)?;
+                let b_source = self.read_file(&b, max_size)?;
+
+                let a_parser = self.parser(&a_source, parse_options)?;
+                let a_ast = a_parser.parse()?;
+
+                let b_parser = self.parser(&b_source, parse_options)?;
+                let b_ast = b_parser.parse()?;
+
+                let differences = Diff.diff(&a_ast, &b_ast);
+
+                if differences.is_empty() {
+                    return Ok("No differences".to_string());
+                }
+
+                Ok(differences
+                    .iter()
+                    .map(|difference| difference.to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n"))
+            }
+            Command::Merge {
+                base,
+                overlay,
+                concat_arrays,
+            } => {
+                let base_source = self.read_file(&base, max_size)?;
+                let overlay_source = self.read_file(&overlay, max_size)?;
+
+                let base_parser = self.parser(&base_source, parse_options)?;
+                let base_ast = base_parser.parse()?;
+
+                let overlay_parser = self.parser(&overlay_source, parse_options)?;
+                let overlay_ast = overlay_parser.parse()?;
+
+                let ast = Node::merge(base_ast, overlay_ast, concat_arrays);
+
+                Ok(Formatter::default().format(&ast))
+            }
+            Command::Convert {
+                format,
+                unquote_strings,
+                input,
+            } => {
+                let source = self.source(&input, max_size)?;
+
+                let parser = self.parser(&source, parse_options)?;
                 let ast = parser.parse()?;
 
-                let formatter = match spacing {
-                    Some(space) => Formatter::new(space as usize),
-                    None => Formatter::default(),
+                match format {
+                    ConvertFormat::Flat => Ok(Flatten::default()
+                        .with_unquote_strings(unquote_strings)
+                        .flatten(&ast)
+                        .join("\n")),
+                }
+            }
+            Command::Sort {
+                spacing,
+                indent,
+                trailing_newline,
+                prevent_write,
+                output,
+                input,
+            } => {
+                let source = self.source(&input, max_size)?;
+
+                let parser = self.parser(&source, parse_options)?;
+                let mut ast = parser.parse()?;
+
+                ast.sort_keys();
+
+                let indent = match (spacing, indent) {
+                    (_, Some(indent)) => Some(indent),
+                    (Some(space), None) => Some(" ".repeat(space as usize)),
+                    (None, None) => self.indent_of(&source),
                 };
 
-                let json = formatter.format(&ast);
+                let mut json = match indent {
+                    Some(indent) => Formatter::default().with_indent(indent).format(&ast),
+                    None => Minifier::default().minify(&ast),
+                };
+
+                if trailing_newline {
+                    json.push('\n');
+                }
 
-                self.is_file_then_override(&input, &json)?;
+                if let Some(output) = output {
+                    self.write_output(&output, |file| file.write_all(json.as_bytes()))?;
+
+                    return Ok("Sort successful".to_string());
+                }
+
+                self.is_file_then_override(&input, |file| file.write_all(json.as_bytes()))?;
 
                 if prevent_write {
-                    return Ok("Format successful".to_string());
+                    return Ok("Sort successful".to_string());
                 }
 
-                Ok(json)
+                // stdout is already terminated with a newline
+                Ok(json.strip_suffix('\n').unwrap_or(&json).to_string())
+            }
+            Command::Tokens { input } => {
+                let source = self.source(&input, max_size)?;
+
+                let tokens = Scanner::new(&source).with_options(parse_options).scan()?;
+
+                Ok(tokens
+                    .iter()
+                    .map(|token| self.token_line(&source, token))
+                    .collect::<Vec<String>>()
+                    .join("\n"))
             }
             Command::Minify {
+                normalize_numbers,
+                ascii,
+                #[cfg(feature = "watch")]
+                    watch: _,
                 prevent_write,
+                output,
                 input,
             } => {
-                let source = self.source(&input)?;
-
-                let mut scanner = Scanner::new(&source);
-                let tokens = scanner.scan()?;
+                let source = self.source(&input, max_size)?;
 
-                let parser = Parser::new(&source, tokens);
+                let parser = self.parser(&source, parse_options)?;
                 let ast = parser.parse()?;
 
-                let minifier = Minifier;
-                let json = minifier.minify(&ast);
+                let minifier = Minifier::default()
+                    .with_normalize_numbers(normalize_numbers)
+                    .with_ensure_ascii(ascii);
+
+                if let Some(output) = output {
+                    self.write_output(&output, |file| minifier.minify_to(&ast, file))?;
 
-                self.is_file_then_override(&input, &json)?;
+                    return Ok("Minify successful".to_string());
+                }
 
                 if prevent_write {
+                    self.is_file_then_override(&input, |file| minifier.minify_to(&ast, file))?;
+
                     return Ok("Minify successful".to_string());
                 }
 
+                let json = minifier.minify(&ast);
+
+                self.is_file_then_override(&input, |file| file.write_all(json.as_bytes()))?;
+
                 Ok(json)
             }
         }
     }
 
-    fn source(&self, input_type: &Input) -> Result<String, Box<dyn Error>> {
+    /// Token type, line, byte indices, column indices and the source slice of a token
+    fn token_line(&self, source: &str, token: &Token) -> String {
+        let Token {
+            token_type,
+            line_number,
+            indices: (start, end),
+            column_indices: (column_start, column_end),
+        } = token;
+
+        format!(
+            "{:?} line={} bytes={}..{} columns={}..{} {}",
+            token_type,
+            line_number,
+            start,
+            end,
+            column_start,
+            column_end,
+            &source[*start..*end]
+        )
+        .trim_end()
+        .to_string()
+    }
+
+    /// Indent unit of formatted JSON, taken from the first indented line, or `None` when the
+    /// source is on a single line and so treated as minified
+    fn indent_of(&self, source: &str) -> Option<String> {
+        if !source.trim().contains('\n') {
+            return None;
+        }
+
+        let indent = source
+            .lines()
+            .skip(1)
+            .map(|line| {
+                let content = line.trim_start_matches([' ', '\t']);
+                (&line[..line.len() - content.len()], content)
+            })
+            .find(|(indent, content)| !indent.is_empty() && !content.is_empty())
+            .map_or("", |(indent, _)| indent);
+
+        Some(indent.to_string())
+    }
+
+    fn source(&self, input_type: &Input, max_size: u64) -> Result<String, CliError> {
         match input_type {
-            Input::File { path, .. } => {
-                match path.extension() {
-                    Some(extension) if extension == "json" => {}
-                    _ => {
-                        return Err(io::Error::new(
-                            io::ErrorKind::InvalidInput,
-                            "Invalid input only .json files are supported",
-                        )
-                        .into())
-                    }
-                }
+            Input::File { paths, .. } => match paths.as_slice() {
+                [path] => self.read_file(path, max_size),
+                _ => Err(CliError::InvalidInput(
+                    "Invalid input expected a single file path".to_string(),
+                )),
+            },
+            Input::Text { input } => Ok(input.to_string()),
+        }
+    }
 
-                fs::read_to_string(&path).map_err(|error| {
-                    io::Error::new(
-                        error.kind(),
-                        format!(
-                            "No such file or directory \"{}\" found",
-                            path.to_string_lossy()
-                        ),
-                    )
-                    .into()
-                })
+    /// Read a .json file of at most `max_size` bytes
+    ///
+    /// The size is checked from the metadata before reading and the read itself is capped, so
+    /// files that do not report their size, such as pipes, cannot exhaust memory either.
+    fn read_file(&self, path: &Path, max_size: u64) -> Result<String, CliError> {
+        match path.extension() {
+            Some(extension) if extension == "json" => {}
+            _ => {
+                return Err(CliError::InvalidInput(
+                    "Invalid input only .json files are supported".to_string(),
+                ))
             }
-            Input::Text { input } => Ok(input.to_string()),
         }
+
+        let file = File::open(path).map_err(|error| {
+            io::Error::new(
+                error.kind(),
+                format!(
+                    "No such file or directory \"{}\" found",
+                    path.to_string_lossy()
+                ),
+            )
+        })?;
+
+        let too_large = || {
+            CliError::InvalidInput(format!(
+                "File \"{}\" exceeds the maximum size of {} bytes, see --max-size",
+                path.to_string_lossy(),
+                max_size
+            ))
+        };
+
+        if file.metadata()?.len() > max_size {
+            return Err(too_large());
+        }
+
+        let mut source = String::new();
+        file.take(max_size.saturating_add(1))
+            .read_to_string(&mut source)?;
+
+        if source.len() as u64 > max_size {
+            return Err(too_large());
+        }
+
+        Ok(source)
     }
 
-    fn is_file_then_override(&self, input: &Input, json: &str) -> Result<(), Box<dyn Error>> {
+    /// Stream the output into a new or truncated file at `path`, leaving the input untouched
+    fn write_output<F>(&self, path: &Path, write: F) -> Result<(), CliError>
+    where
+        F: Fn(&mut BufWriter<File>) -> io::Result<()>,
+    {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.is_dir() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "No such directory \"{}\" found to write the output",
+                        parent.to_string_lossy()
+                    ),
+                )
+                .into());
+            }
+        }
+
+        let mut file = BufWriter::new(File::create(path)?);
+
+        write(&mut file)?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// Stream the output into each file unless overriding has been prevented
+    fn is_file_then_override<F>(&self, input: &Input, write: F) -> Result<(), CliError>
+    where
+        F: Fn(&mut BufWriter<File>) -> io::Result<()>,
+    {
         if let Input::File {
-            path,
+            paths,
             prevent_override: false,
+            backup,
         } = input
         {
-            let mut file = OpenOptions::new().write(true).truncate(true).open(&path)?;
+            for path in paths {
+                if *backup {
+                    let mut backup_path = path.clone().into_os_string();
+                    backup_path.push(".bak");
 
-            let _ = file.write_all(&json.as_bytes())?;
+                    fs::copy(path, &backup_path)?;
+                }
+
+                let mut file =
+                    BufWriter::new(OpenOptions::new().write(true).truncate(true).open(path)?);
+
+                write(&mut file)?;
+                file.flush()?;
+            }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod cli_tests {
+    use cli_args::MAX_SIZE;
+
+    use super::*;
+
+    #[test]
+    fn scanner_error_as_json() {
+        let error = Scanner::new("[\n\"hello]").scan().unwrap_err();
+
+        assert_eq!(
+            format!(
+                "{{\"kind\":\"unterminated_string\",\"message\":{},\"line\":2,\"column\":1,\"slice\":\"\\\"hello]\"}}",
+                Cli.json_string(&error.to_string())
+            ),
+            Cli.error_json(&error.into())
+        );
+    }
+
+    #[test]
+    fn parser_error_as_json() {
+        let source = "[1 2]";
+        let tokens = Scanner::new(source).scan().unwrap();
+        let error = Parser::new(source, tokens).parse().unwrap_err();
+
+        assert_eq!(
+            format!(
+                "{{\"kind\":\"unexpected_token\",\"message\":{},\"line\":1,\"column\":4,\"slice\":\"2\"}}",
+                Cli.json_string(&error.to_string())
+            ),
+            Cli.error_json(&error.into())
+        );
+    }
+
+    #[test]
+    fn warning_as_json() {
+        let warning = Warning::DuplicateProperty {
+            property: "\"a\"".to_string(),
+            error_preview: "preview".to_string(),
+            line: 3,
+            column: 5,
+        };
+
+        assert_eq!(
+            format!(
+                "{{\"kind\":\"duplicate_property\",\"message\":{},\"line\":3,\"column\":5,\"slice\":\"\\\"a\\\"\"}}",
+                Cli.json_string(&warning.to_string())
+            ),
+            Cli.warning_json(&warning)
+        );
+    }
+
+    #[test]
+    fn never_color_json_errors() {
+        let parse = |input: &str| Command::Parse {
+            verify: false,
+            stats: false,
+            fingerprint: false,
+            ast: false,
+            time: false,
+            warn_duplicates: false,
+            select: None,
+            minify: false,
+            prevent_write: false,
+            input: Input::Text {
+                input: input.to_string(),
+            },
+        };
+        let check = Command::Check {
+            require: "a:string".to_string(),
+            prevent_write: false,
+            input: Input::Text {
+                input: "{\"a\":1}".to_string(),
+            },
+        };
+
+        assert!(!Cli.colored_errors(&ErrorFormat::Json));
+
+        for command in [parse("{\"a\":1,}"), parse("[\"a]"), check] {
+            let error = Cli
+                .process_command(command, &ErrorFormat::Json, MAX_SIZE, RootKind::Any)
+                .unwrap_err();
+
+            assert!(!Cli.error_json(&error).contains("\\u001b"));
+        }
+    }
+
+    #[test]
+    fn io_error_as_json() {
+        let error = Cli.read_file(Path::new("data.txt"), MAX_SIZE).unwrap_err();
+
+        assert_eq!(2, error.exit_code());
+        assert_eq!(
+            "{\"kind\":\"invalid_input\",\"message\":\"Invalid input only .json files are supported\",\"line\":null,\"column\":null,\"slice\":null}",
+            Cli.error_json(&error)
+        );
+    }
+
+    #[test]
+    fn backup_file_before_override() {
+        let path = std::env::temp_dir().join("jtools_backup_before_override.json");
+        let backup_path = std::env::temp_dir().join("jtools_backup_before_override.json.bak");
+
+        fs::write(&path, "[ 1, 2 ]").unwrap();
+
+        let input = Input::File {
+            paths: vec![path.clone()],
+            prevent_override: false,
+            backup: true,
+        };
+
+        Cli.is_file_then_override(&input, |file| file.write_all(b"[1,2]"))
+            .unwrap();
+
+        assert_eq!("[1,2]", fs::read_to_string(&path).unwrap());
+        assert_eq!("[ 1, 2 ]", fs::read_to_string(&backup_path).unwrap());
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(backup_path).unwrap();
+    }
+
+    #[test]
+    fn write_output_to_separate_path() {
+        let path = std::env::temp_dir().join("jtools_write_output_to_separate_path.json");
+
+        Cli.write_output(&path, |file| file.write_all(b"[1,2]"))
+            .unwrap();
+
+        assert_eq!("[1,2]", fs::read_to_string(&path).unwrap());
+
+        fs::remove_file(path).unwrap();
+
+        let missing = std::env::temp_dir()
+            .join("jtools_missing_output_directory")
+            .join("data.json");
+
+        assert!(Cli
+            .write_output(&missing, |file| file.write_all(b"[]"))
+            .unwrap_err()
+            .to_string()
+            .starts_with("No such directory"));
+    }
+
+    #[test]
+    fn sort_file_keeping_indentation() {
+        let path = std::env::temp_dir().join("jtools_sort_file_keeping_indentation.json");
+
+        fs::write(
+            &path,
+            "{\n\t\"b\": [{\"d\": 1, \"c\": 2}],\n\t\"a\": null\n}",
+        )
+        .unwrap();
+
+        let sort = |path: &Path| Command::Sort {
+            spacing: None,
+            indent: None,
+            trailing_newline: false,
+            prevent_write: true,
+            output: None,
+            input: Input::File {
+                paths: vec![path.to_path_buf()],
+                prevent_override: false,
+                backup: false,
+            },
+        };
+
+        assert_eq!(
+            "Sort successful",
+            Cli.process_command(sort(&path), &ErrorFormat::Text, MAX_SIZE, RootKind::Any)
+                .unwrap()
+        );
+        assert_eq!(
+            "{\n\t\"a\": null,\n\t\"b\": [\n\t\t{\n\t\t\t\"c\": 2,\n\t\t\t\"d\": 1\n\t\t}\n\t]\n}",
+            fs::read_to_string(&path).unwrap()
+        );
+
+        fs::write(&path, "{\"b\":1,\"a\":2}").unwrap();
+        Cli.process_command(sort(&path), &ErrorFormat::Text, MAX_SIZE, RootKind::Any)
+            .unwrap();
+
+        assert_eq!("{\"a\":2,\"b\":1}", fs::read_to_string(&path).unwrap());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn print_one_token_per_line() {
+        let command = Command::Tokens {
+            input: Input::Text {
+                input: "{\n  \"a\": 1\n}".to_string(),
+            },
+        };
+
+        assert_eq!(
+            [
+                "LeftBrace line=1 bytes=0..1 columns=1..2 {",
+                "String line=2 bytes=4..7 columns=3..6 \"a\"",
+                "Colon line=2 bytes=7..8 columns=6..7 :",
+                "Number line=2 bytes=9..10 columns=8..9 1",
+                "RightBrace line=3 bytes=11..12 columns=1..2 }",
+                "Eof line=3 bytes=12..12 columns=2..2",
+            ]
+            .join("\n"),
+            Cli.process_command(command, &ErrorFormat::Text, MAX_SIZE, RootKind::Any)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn minify_to_ascii() {
+        let command = Command::Minify {
+            normalize_numbers: false,
+            ascii: true,
+            #[cfg(feature = "watch")]
+            watch: false,
+            prevent_write: false,
+            output: None,
+            input: Input::Text {
+                input: "{\"city\": \"Zürich\"}".to_string(),
+            },
+        };
+
+        assert_eq!(
+            "{\"city\":\"Z\\u00fcrich\"}",
+            Cli.process_command(command, &ErrorFormat::Text, MAX_SIZE, RootKind::Any)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn format_keeping_comments() {
+        let command = Command::Format {
+            spacing: Some(2),
+            indent: None,
+            trailing_newline: false,
+            keep_comments: true,
+            warn_duplicates: false,
+            ascii: false,
+            #[cfg(feature = "watch")]
+            watch: false,
+            prevent_write: false,
+            output: None,
+            input: Input::Text {
+                input: "{\n// port\n\"port\": 80, \"tags\": [/* none */]}".to_string(),
+            },
+        };
+
+        assert_eq!(
+            "{\n  // port\n  \"port\": 80,\n  \"tags\": []\n}",
+            Cli.process_command(command, &ErrorFormat::Text, MAX_SIZE, RootKind::Any)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn format_with_duplicate_key_warnings() {
+        let format = |warn_duplicates| Command::Format {
+            spacing: Some(0),
+            indent: None,
+            trailing_newline: false,
+            keep_comments: false,
+            warn_duplicates,
+            ascii: false,
+            #[cfg(feature = "watch")]
+            watch: false,
+            prevent_write: false,
+            output: None,
+            input: Input::Text {
+                input: "{\"a\": 1, \"b\": 2, \"a\": 3}".to_string(),
+            },
+        };
+
+        assert_eq!(
+            "{\n\"a\": 3,\n\"b\": 2\n}",
+            Cli.process_command(format(true), &ErrorFormat::Text, MAX_SIZE, RootKind::Any)
+                .unwrap()
+        );
+        assert!(matches!(
+            Cli.process_command(format(false), &ErrorFormat::Text, MAX_SIZE, RootKind::Any),
+            Err(CliError::Parse(
+                parser::parser_error::ParserError::DuplicateProperty { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn print_fingerprint() {
+        let parse = |input: &str| Command::Parse {
+            verify: false,
+            stats: false,
+            fingerprint: true,
+            ast: false,
+            time: false,
+            warn_duplicates: false,
+            select: None,
+            minify: false,
+            prevent_write: false,
+            input: Input::Text {
+                input: input.to_string(),
+            },
+        };
+
+        let fingerprint = Cli
+            .process_command(
+                parse("{\"a\": 1, \"b\": [true]}"),
+                &ErrorFormat::Text,
+                MAX_SIZE,
+                RootKind::Any,
+            )
+            .unwrap();
+
+        assert_eq!(16, fingerprint.len());
+        assert_eq!(
+            fingerprint,
+            Cli.process_command(
+                parse("{\"b\":[true],\"a\":1.0}"),
+                &ErrorFormat::Text,
+                MAX_SIZE,
+                RootKind::Any
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn select_json_path() {
+        let parse = |select: &str, minify| Command::Parse {
+            verify: false,
+            stats: false,
+            fingerprint: false,
+            ast: false,
+            time: false,
+            warn_duplicates: false,
+            select: Some(select.to_string()),
+            minify,
+            prevent_write: false,
+            input: Input::Text {
+                input: "{\"servers\": [{\"ports\": [80, 443]}]}".to_string(),
+            },
+        };
+
+        assert_eq!(
+            "[\n    80,\n    443\n]",
+            Cli.process_command(
+                parse("$.servers[0].ports", false),
+                &ErrorFormat::Text,
+                MAX_SIZE,
+                RootKind::Any
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "{\"ports\":[80,443]}",
+            Cli.process_command(
+                parse("$.servers[0]", true),
+                &ErrorFormat::Text,
+                MAX_SIZE,
+                RootKind::Any
+            )
+            .unwrap()
+        );
+
+        let error = Cli
+            .process_command(
+                parse("$.servers[1]", false),
+                &ErrorFormat::Text,
+                MAX_SIZE,
+                RootKind::Any,
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            (1, "Path not found: $.servers[1]"),
+            (error.exit_code(), error.to_string().as_str())
+        );
+    }
+
+    #[test]
+    fn print_ast_tree() {
+        let command = Command::Parse {
+            verify: false,
+            stats: false,
+            fingerprint: false,
+            ast: true,
+            time: false,
+            warn_duplicates: false,
+            select: None,
+            minify: false,
+            prevent_write: false,
+            input: Input::Text {
+                input: "{\"a\": [1]}".to_string(),
+            },
+        };
+
+        assert_eq!(
+            "Object\n  Property\n    Literal \"a\"\n    Array\n      Literal 1",
+            Cli.process_command(command, &ErrorFormat::Text, MAX_SIZE, RootKind::Any)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn restrict_root_value() {
+        let parse = |input: &str| Command::Parse {
+            verify: false,
+            stats: false,
+            fingerprint: false,
+            ast: false,
+            time: false,
+            warn_duplicates: false,
+            select: None,
+            minify: false,
+            prevent_write: true,
+            input: Input::Text {
+                input: input.to_string(),
+            },
+        };
+
+        assert!(Cli
+            .process_command(parse("{}"), &ErrorFormat::Text, MAX_SIZE, RootKind::Object)
+            .is_ok());
+
+        let error = Cli
+            .process_command(parse("[]"), &ErrorFormat::Text, MAX_SIZE, RootKind::Object)
+            .unwrap_err();
+
+        assert!(matches!(error, CliError::Parse(_)));
+        assert_eq!("invalid_root", error.kind());
+    }
+
+    #[test]
+    fn detect_indent_unit() {
+        assert_eq!(None, Cli.indent_of(" [1, 2] \n"));
+        assert_eq!(
+            Some("  ".to_string()),
+            Cli.indent_of("[\n\n  1,\n    {}\n]")
+        );
+        assert_eq!(Some(String::new()), Cli.indent_of("[\n1\n]"));
+    }
+
+    #[test]
+    fn reject_files_over_max_size() {
+        let path = std::env::temp_dir().join("jtools_reject_files_over_max_size.json");
+
+        fs::write(&path, "[1, 2, 3]").unwrap();
+
+        assert_eq!("[1, 2, 3]", Cli.read_file(&path, 9).unwrap());
+        assert!(Cli
+            .read_file(&path, 8)
+            .unwrap_err()
+            .to_string()
+            .contains("exceeds the maximum size of 8 bytes"));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn escape_json_string() {
+        assert_eq!(
+            "\"\\\"a\\\\b\\n\\u001b\"",
+            Cli.json_string("\"a\\b\n\u{1b}")
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt::Display;
+
+use ast::node::Node;
+
+/// Metrics of a parsed document
+///
+/// ## Description
+///
+/// Collected in a single traversal of the AST to help spot pathological inputs such as an array
+/// with hundreds of thousands of elements. Every JSON value is counted as a node, property keys
+/// are not. Depth is the deepest nesting of objects and arrays, so a lone literal has a depth of
+/// 0 and `[[]]` has a depth of 2.
+///
+/// ## Examples
+///
+/// ```
+/// use ast::node::Node;
+/// use cli::stats::Stats;
+///
+/// let ast = Node::Array(vec![Node::Literal("1"), Node::Array(vec![])]);
+/// let stats = Stats::from(&ast);
+///
+/// assert_eq!(3, stats.nodes);
+/// assert_eq!(2, stats.max_depth);
+/// assert_eq!(2, stats.largest_array);
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Stats {
+    pub nodes: usize,
+    pub max_depth: usize,
+    pub objects: usize,
+    pub arrays: usize,
+    pub strings: usize,
+    pub numbers: usize,
+    pub booleans: usize,
+    pub nulls: usize,
+    pub largest_array: usize,
+}
+
+impl From<&Node<'_>> for Stats {
+    fn from(ast: &Node<'_>) -> Self {
+        let mut stats = Stats::default();
+        let mut stack = vec![(ast, 0)];
+
+        while let Some((node, depth)) = stack.pop() {
+            if let Node::Property(_, value) = node {
+                stack.push((value, depth));
+                continue;
+            }
+
+            stats.nodes += 1;
+
+            match node {
+                Node::Object(children) => {
+                    stats.objects += 1;
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    stack.extend(children.iter().map(|child| (child, depth + 1)));
+                }
+                Node::Array(children) => {
+                    stats.arrays += 1;
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    stats.largest_array = stats.largest_array.max(children.len());
+                    stack.extend(children.iter().map(|child| (child, depth + 1)));
+                }
+                literal => match literal.type_name() {
+                    "string" => stats.strings += 1,
+                    "number" => stats.numbers += 1,
+                    "boolean" => stats.booleans += 1,
+                    _ => stats.nulls += 1,
+                },
+            }
+        }
+
+        stats
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Nodes: {}", self.nodes)?;
+        writeln!(f, "Max depth: {}", self.max_depth)?;
+        writeln!(f, "Objects: {}", self.objects)?;
+        writeln!(f, "Arrays: {}", self.arrays)?;
+        writeln!(f, "Strings: {}", self.strings)?;
+        writeln!(f, "Numbers: {}", self.numbers)?;
+        writeln!(f, "Booleans: {}", self.booleans)?;
+        writeln!(f, "Nulls: {}", self.nulls)?;
+        write!(f, "Largest array: {}", self.largest_array)
+    }
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use parser::parser::Parser;
+    use scanner::scanner::Scanner;
+
+    use super::*;
+
+    #[test]
+    fn count_document_metrics() {
+        let source = "{\"name\":\"jtools\",\"tags\":[1,2.5,[true,null]],\"meta\":{\"ok\":false}}";
+        let tokens = Scanner::new(source).scan().unwrap();
+        let parser = Parser::new(source, tokens);
+        let ast = parser.parse().unwrap();
+
+        assert_eq!(
+            Stats {
+                nodes: 10,
+                max_depth: 3,
+                objects: 2,
+                arrays: 2,
+                strings: 1,
+                numbers: 2,
+                booleans: 2,
+                nulls: 1,
+                largest_array: 3,
+            },
+            Stats::from(&ast)
+        );
+    }
+
+    #[test]
+    fn literal_has_no_depth() {
+        let stats = Stats::from(&Node::Literal("\"json\""));
+
+        assert_eq!(
+            Stats {
+                nodes: 1,
+                strings: 1,
+                ..Stats::default()
+            },
+            stats
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::{fmt::Display, time::Duration};
+
+/// Wall-clock time spent scanning and parsing a document
+///
+/// ## Description
+///
+/// Reported by `jtools parse --time` to check performance on real data without the benchmark
+/// harness. Throughput is the size of the source in megabytes, 1,000,000 bytes, divided by the
+/// combined scanning and parsing time.
+///
+/// ## Examples
+///
+/// ```
+/// use cli::timing::Timing;
+/// use std::time::Duration;
+///
+/// let timing = Timing {
+///     bytes: 50_000_000,
+///     scan: Duration::from_millis(150),
+///     parse: Duration::from_millis(100),
+/// };
+///
+/// assert_eq!(200.0, timing.throughput());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Timing {
+    pub bytes: usize,
+    pub scan: Duration,
+    pub parse: Duration,
+}
+
+impl Timing {
+    pub fn total(&self) -> Duration {
+        self.scan + self.parse
+    }
+
+    /// Megabytes per second over the total time, 0 if no time was measured
+    pub fn throughput(&self) -> f64 {
+        let seconds = self.total().as_secs_f64();
+
+        if seconds == 0.0 {
+            return 0.0;
+        }
+
+        self.bytes as f64 / 1_000_000.0 / seconds
+    }
+}
+
+impl Display for Timing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Bytes: {}", self.bytes)?;
+        writeln!(f, "Scan: {:.3?}", self.scan)?;
+        writeln!(f, "Parse: {:.3?}", self.parse)?;
+        writeln!(f, "Total: {:.3?}", self.total())?;
+        write!(f, "Throughput: {:.2} MB/s", self.throughput())
+    }
+}
+
+#[cfg(test)]
+mod timing_tests {
+    use super::*;
+
+    #[test]
+    fn report_breakdown() {
+        let timing = Timing {
+            bytes: 3_000_000,
+            scan: Duration::from_millis(2),
+            parse: Duration::from_millis(1),
+        };
+
+        assert_eq!(
+            "Bytes: 3000000\nScan: 2.000ms\nParse: 1.000ms\nTotal: 3.000ms\nThroughput: 1000.00 MB/s",
+            timing.to_string()
+        );
+        assert_eq!(0.0, Timing::default().throughput());
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// A watched file with its modification time and contents when it was last seen
+#[derive(Debug)]
+struct WatchedFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    contents: Option<Vec<u8>>,
+}
+
+/// Poll files for changes to their contents
+///
+/// ## Description
+///
+/// The modification time of each file is checked every `interval`. When it changes the file is
+/// only read once it has stayed the same for `debounce`, so an editor saving in several writes
+/// is reported once. A change is only reported if the contents differ from when the file was
+/// last seen, so touching a file or writing the same contents back does not trigger a change.
+///
+/// After processing a change, `mark_seen` records the file as written so formatting it in place
+/// is not reported as another change. Files that cannot be read, for example while an editor
+/// replaces them, are skipped until they can be read again.
+///
+/// ## Examples
+///
+/// ```
+/// use cli::watch::Watcher;
+/// use std::{fs, time::Duration};
+///
+/// let path = std::env::temp_dir().join("jtools_watch_doc_example.json");
+/// fs::write(&path, "[1, 2]").unwrap();
+///
+/// let mut watcher = Watcher::new(vec![path.clone()]).with_debounce(Duration::ZERO);
+///
+/// assert_eq!(None, watcher.poll());
+///
+/// fs::write(&path, "[1, 2, 3]").unwrap();
+///
+/// assert_eq!(Some(0), watcher.poll());
+/// assert_eq!(None, watcher.poll());
+/// # fs::remove_file(path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Watcher {
+    files: Vec<WatchedFile>,
+    interval: Duration,
+    debounce: Duration,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let files = paths
+            .into_iter()
+            .map(|path| WatchedFile {
+                modified: modified(&path),
+                contents: fs::read(&path).ok(),
+                path,
+            })
+            .collect();
+
+        Self {
+            files,
+            interval: Duration::from_millis(200),
+            debounce: Duration::from_millis(100),
+        }
+    }
+
+    pub fn with_interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    pub fn with_debounce(self, debounce: Duration) -> Self {
+        Self { debounce, ..self }
+    }
+
+    /// Block until a file changes, returning its index
+    pub fn wait(&mut self) -> usize {
+        loop {
+            if let Some(index) = self.poll() {
+                return index;
+            }
+
+            thread::sleep(self.interval);
+        }
+    }
+
+    /// Index of the first file whose contents changed since it was last seen, without waiting
+    /// for one to change
+    pub fn poll(&mut self) -> Option<usize> {
+        for (index, file) in self.files.iter_mut().enumerate() {
+            let mut current = modified(&file.path);
+
+            if current == file.modified {
+                continue;
+            }
+
+            // Wait for successive writes to finish before reading the file
+            loop {
+                thread::sleep(self.debounce);
+
+                let settled = modified(&file.path);
+
+                if settled == current {
+                    break;
+                }
+
+                current = settled;
+            }
+
+            file.modified = current;
+
+            let Ok(contents) = fs::read(&file.path) else {
+                continue;
+            };
+
+            if file.contents.as_ref() != Some(&contents) {
+                file.contents = Some(contents);
+
+                return Some(index);
+            }
+        }
+
+        None
+    }
+
+    /// Record a file as seen after writing to it, so the write is not reported as a change
+    pub fn mark_seen(&mut self, index: usize) {
+        if let Some(file) = self.files.get_mut(index) {
+            file.modified = modified(&file.path);
+            file.contents = fs::read(&file.path).ok();
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod watch_tests {
+    use std::fs::File;
+
+    use super::*;
+
+    /// Write to a file and move its modification time forward, as writes in quick succession
+    /// can otherwise keep the same time
+    fn write(path: &Path, contents: &str, seconds: u64) {
+        fs::write(path, contents).unwrap();
+
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(seconds))
+            .unwrap();
+    }
+
+    #[test]
+    fn report_changed_contents_only() {
+        let path = std::env::temp_dir().join("jtools_report_changed_contents_only.json");
+        let other = std::env::temp_dir().join("jtools_report_changed_contents_only_other.json");
+
+        fs::write(&path, "[1, 2]").unwrap();
+        fs::write(&other, "[]").unwrap();
+
+        let mut watcher =
+            Watcher::new(vec![other.clone(), path.clone()]).with_debounce(Duration::ZERO);
+
+        assert_eq!(None, watcher.poll());
+
+        write(&path, "[1, 2]", 10);
+        assert_eq!(None, watcher.poll());
+
+        write(&path, "[1, 2, 3]", 20);
+        assert_eq!(Some(1), watcher.poll());
+        assert_eq!(None, watcher.poll());
+
+        write(&path, "[1,2,3]", 30);
+        watcher.mark_seen(1);
+        assert_eq!(None, watcher.poll());
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(None, watcher.poll());
+
+        write(&path, "[4]", 40);
+        assert_eq!(Some(1), watcher.poll());
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(other).unwrap();
+    }
+}
@@ -1,4 +1,15 @@
-use unicode_width::UnicodeWidthStr;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const RED_UNDERLINE: &str = "\x1b[4;31m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+const ELLIPSIS: &str = "…";
 
 #[derive(Debug, PartialEq)]
 pub struct ErrorPreview;
@@ -38,6 +49,15 @@ pub struct ErrorPreview;
 ///   |           ^---Column=12
 ///   |
 /// ```
+///
+/// Lines are cut `32` characters either side of the error. An ellipsis, `…`, marks each side
+/// that was cut so it is clear the line continues, and the pointer is shifted to match.
+///
+/// ## Colour
+///
+/// `preview_span` with `colored` set underlines the error span in red and draws the pointer in
+/// bold using ANSI escape codes. Previews are kept in error messages, so colour is left to the
+/// caller, which should only ask for it when the message is written to a terminal as text.
 impl ErrorPreview {
     pub fn preview(
         &self,
@@ -45,26 +65,66 @@ impl ErrorPreview {
         start: usize,
         column_start: usize,
         line_number: usize,
+    ) -> String {
+        self.render(source, start, start, column_start, line_number, false)
+    }
+
+    pub fn preview_span(
+        &self,
+        source: &str,
+        start: usize,
+        end: usize,
+        column_start: usize,
+        line_number: usize,
+        colored: bool,
+    ) -> String {
+        self.render(source, start, end, column_start, line_number, colored)
+    }
+
+    fn render(
+        &self,
+        source: &str,
+        start: usize,
+        end: usize,
+        column_start: usize,
+        line_number: usize,
+        colored: bool,
     ) -> String {
         let limit = 32;
 
         let (backwards, forwards) = source.split_at(start);
 
-        let back_preview = backwards
+        // One character past the limit is taken to know if the line was cut
+        let mut back_preview = backwards
             .chars()
             .rev()
-            .take_while(|&char| char != '\n')
-            .take(limit)
-            .collect::<Vec<char>>()
-            .into_iter()
-            .rev()
-            .collect::<String>();
+            .take_while(|&char| char != '\n' && char != '\r')
+            .take(limit + 1)
+            .collect::<Vec<char>>();
 
-        let forward_preview = forwards
+        let back_ellipsis = if back_preview.len() > limit {
+            back_preview.pop();
+            ELLIPSIS
+        } else {
+            ""
+        };
+
+        let back_preview = back_preview.into_iter().rev().collect::<String>();
+
+        let mut forward_preview = forwards
             .chars()
-            .take_while(|&char| char != '\n')
-            .take(limit)
-            .collect::<String>();
+            .take_while(|&char| char != '\n' && char != '\r')
+            .take(limit + 1)
+            .collect::<Vec<char>>();
+
+        let forward_ellipsis = if forward_preview.len() > limit {
+            forward_preview.pop();
+            ELLIPSIS
+        } else {
+            ""
+        };
+
+        let forward_preview = forward_preview.into_iter().collect::<String>();
 
         let back_preview = back_preview.trim_start();
         let forward_preview = forward_preview.trim_end();
@@ -75,13 +135,40 @@ impl ErrorPreview {
         let above_sign = self.sign(&mut backwards.lines().rev());
         let below_sign = self.sign(&mut forwards.lines());
 
-        let error_preview = format!("{}{}", back_preview, forward_preview);
+        let (error_preview, pointer) = if colored {
+            let mut span = end.saturating_sub(start).min(forward_preview.len());
 
-        let pointer = format!("^---Column={}", column_start);
-        let pointer_position = " ".repeat(back_preview.width());
+            while !forward_preview.is_char_boundary(span) {
+                span -= 1;
+            }
+
+            let (span, rest) = forward_preview.split_at(span);
+
+            (
+                format!("{back_ellipsis}{back_preview}{RED_UNDERLINE}{span}{RESET}{rest}{forward_ellipsis}"),
+                format!("{BOLD}^---Column={column_start}{RESET}"),
+            )
+        } else {
+            (
+                format!(
+                    "{}{}{}{}",
+                    back_ellipsis, back_preview, forward_preview, forward_ellipsis
+                ),
+                format!("^---Column={}", column_start),
+            )
+        };
+        // Tabs are copied so the terminal expands them to the same tab stops on both lines
+        let pointer_position = " ".repeat(back_ellipsis.width())
+            + &back_preview
+                .chars()
+                .map(|char| match char {
+                    '\t' => "\t".to_string(),
+                    char => " ".repeat(char.width().unwrap_or(0)),
+                })
+                .collect::<String>();
 
         [
-            format!("\n"),
+            "\n".to_string(),
             format!("{indent}{above_sign}|\n"),
             format!("{indent} |\n"),
             format!("{line_number} |{error_preview}\n"),
@@ -94,7 +181,11 @@ impl ErrorPreview {
 
     fn sign(&self, lines: &mut impl Iterator) -> &str {
         lines.next();
-        lines.next().is_some().then(|| "+").unwrap_or(" ")
+        if lines.next().is_some() {
+            "+"
+        } else {
+            " "
+        }
     }
 }
 
@@ -102,6 +193,79 @@ impl ErrorPreview {
 mod preview_tests {
     use super::*;
 
+    #[test]
+    fn stop_preview_at_crlf() {
+        let source = "[\r\n  bad\r\n]";
+        let ep = ErrorPreview;
+
+        assert_eq!(
+            "\n +|\n  |\n2 |bad\n  |^---Column=3\n +|",
+            ep.preview(source, 5, 3, 2)
+        );
+    }
+
+    #[test]
+    fn align_pointer_after_tabs() {
+        let source = "[1,\t😀 @]";
+        let ep = ErrorPreview;
+
+        assert_eq!(
+            "\n  |\n  |\n1 |[1,\t😀 @]\n  |   \t   ^---Column=11\n  |",
+            ep.preview(source, 9, 11, 1)
+        );
+    }
+
+    #[test]
+    fn highlight_error_span() {
+        let source = "{ \"😀\": bad }";
+        let ep = ErrorPreview;
+
+        assert_eq!(
+            "\n  |\n  |\n1 |{ \"😀\": \x1b[4;31mbad\x1b[0m }\n  |        \x1b[1m^---Column=8\x1b[0m\n  |",
+            ep.preview_span(source, 10, 13, 8, 1, true)
+        );
+    }
+
+    #[test]
+    fn plain_preview_unless_colored() {
+        let source = "{ \"error\": bad }";
+        let ep = ErrorPreview;
+
+        assert_eq!(
+            ep.preview(source, 11, 12, 1),
+            ep.preview_span(source, 11, 14, 12, 1, false)
+        );
+    }
+
+    #[test]
+    fn mark_truncated_context_with_ellipsis() {
+        let source = format!("[{}, bad, {}]", "1".repeat(40), "2".repeat(40));
+        let ep = ErrorPreview;
+
+        assert_eq!(
+            format!(
+                "\n  |\n  |\n1 |…{}, bad, {}…\n  |{}^---Column=44\n  |",
+                "1".repeat(30),
+                "2".repeat(27),
+                " ".repeat(33)
+            ),
+            ep.preview(&source, 43, 44, 1)
+        );
+
+        // Exactly 32 characters either side are not cut
+        let source = format!("{}x{}", "1".repeat(32), "2".repeat(31));
+
+        assert_eq!(
+            format!(
+                "\n  |\n  |\n1 |{}x{}\n  |{}^---Column=33\n  |",
+                "1".repeat(32),
+                "2".repeat(31),
+                " ".repeat(32)
+            ),
+            ep.preview(&source, 32, 33, 1)
+        );
+    }
+
     #[test]
     fn display_error() {
         let source = "{ \"error\": bad }";
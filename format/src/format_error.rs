@@ -0,0 +1,84 @@
+use std::{error::Error, fmt::Display};
+
+/// AST that cannot be written as valid JSON, returned by `Formatter::try_format` and
+/// `Minifier::try_minify`
+///
+/// Each variant holds the JSON path, such as `$.servers[0]`, of the offending node.
+#[derive(Debug, PartialEq)]
+pub enum FormatError {
+    /// Object child other than a `Node::Property`
+    NonPropertyChild { path: String, found: String },
+    /// `Node::Property` outside of an object
+    MisplacedProperty { path: String },
+    /// Property key other than a string literal
+    InvalidKey { path: String, key: String },
+}
+
+impl FormatError {
+    /// Stable identifier for each error used by machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FormatError::NonPropertyChild { .. } => "non_property_child",
+            FormatError::MisplacedProperty { .. } => "misplaced_property",
+            FormatError::InvalidKey { .. } => "invalid_key",
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match self {
+            FormatError::NonPropertyChild { path, .. }
+            | FormatError::MisplacedProperty { path }
+            | FormatError::InvalidKey { path, .. } => path,
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::NonPropertyChild { path, found } => {
+                write!(f, "Expected property in object at {} found {}", path, found)
+            }
+            FormatError::MisplacedProperty { path } => {
+                write!(f, "Unexpected property outside of an object at {}", path)
+            }
+            FormatError::InvalidKey { path, key } => {
+                write!(f, "Expected string key at {} found {}", path, key)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod format_error_tests {
+    use super::*;
+
+    #[test]
+    fn error_messages() {
+        assert_eq!(
+            "Expected property in object at $.a[0] found boolean",
+            FormatError::NonPropertyChild {
+                path: "$.a[0]".to_string(),
+                found: "boolean".to_string(),
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "Unexpected property outside of an object at $[1]",
+            FormatError::MisplacedProperty {
+                path: "$[1]".to_string(),
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "Expected string key at $ found 1",
+            FormatError::InvalidKey {
+                path: "$".to_string(),
+                key: "1".to_string(),
+            }
+            .to_string()
+        );
+    }
+}
@@ -1,17 +1,39 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::{self, Write},
+    io,
+    ops::Range,
+};
+
 use ast::node::Node;
+use token::{token::Token, token_type::TokenType};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    format_error::FormatError,
+    io_writer::IoWriter,
+    literal::{ascii_literal, canonical_literal},
+    validate::validate,
+};
+
+/// Pairs of output and source byte ranges produced by `Formatter::format_with_map`
+pub type SourceMap = Vec<(Range<usize>, Range<usize>)>;
 
 /// Format JSON converting AST into String
 ///
 /// ## Description
 ///
-/// The formatter converts an AST into a string of JSON given some amount of space provided by the
-/// user. The algorithm uses depth traversal, O(n), to walk the AST incrementing and
-/// decrementing a depth pointer as it goes along.
+/// The formatter converts an AST into a string of JSON given some amount of space, or a custom
+/// indent such as a tab, provided by the user. The algorithm uses depth traversal, O(n), to walk
+/// the AST incrementing and decrementing a depth pointer as it goes along. Output is written
+/// incrementally during the traversal so `format_to` can stream large documents to a file or
+/// stdout without building the whole string first.
 ///
 /// ## Enhancements 🔨
 ///
 /// - It would be interesting to implement the formatter using the visitor pattern to see if it
-/// provides better code reuse and/or small performance improvements.
+///   provides better code reuse and/or small performance improvements.
 ///
 /// ## Examples
 ///
@@ -40,110 +62,869 @@ use ast::node::Node;
 ///     42
 /// ]
 /// ```
+///
+/// Arrays containing only literals can be kept on a single line:
+///
+/// ```
+/// use ast::node::Node;
+/// use format::formatter::Formatter;
+///
+/// let ast = Node::Array(vec![Node::Literal("1"), Node::Literal("2"), Node::Literal("3")]);
+///
+/// let formatter = Formatter::default().with_compact_arrays(true);
+///
+/// assert_eq!("[1, 2, 3]", formatter.format(&ast));
+/// ```
+///
+/// With a maximum width, objects and arrays that fit on the rest of their line are written on a
+/// single line and only longer ones are expanded:
+///
+/// ```
+/// use ast::node::Node;
+/// use format::formatter::Formatter;
+///
+/// let ast = Node::Object(vec![
+///     Node::Property(
+///         Box::new(Node::Literal("\"short\"")),
+///         Box::new(Node::Array(vec![Node::Literal("1"), Node::Literal("2")])),
+///     ),
+///     Node::Property(
+///         Box::new(Node::Literal("\"long\"")),
+///         Box::new(Node::Array(vec![
+///             Node::Literal("\"abcdefghij\""),
+///             Node::Literal("\"klmnopqrst\""),
+///         ])),
+///     ),
+/// ]);
+///
+/// assert_eq!(
+///     "{\n    \"short\": [1, 2],\n    \"long\": [\n        \"abcdefghij\",\n        \"klmnopqrst\"\n    ]\n}",
+///     Formatter::default().with_max_width(30).format(&ast)
+/// );
+/// ```
+///
+/// Empty containers are collapsed and containers with a single child expanded by default, both
+/// can be changed:
+///
+/// ```
+/// use ast::node::Node;
+/// use format::formatter::Formatter;
+///
+/// let ast = Node::Array(vec![Node::Array(vec![Node::Literal("1")]), Node::Object(vec![])]);
+///
+/// let formatter = Formatter::new(2)
+///     .with_collapse_empty(false)
+///     .with_collapse_single(true);
+///
+/// assert_eq!("[\n  [1],\n  {\n  }\n]", formatter.format(&ast));
+/// assert_eq!("{\n}", formatter.format(&Node::Object(vec![])));
+/// ```
+///
+/// Output can end with a single newline, as expected by most Unix tools, when writing to files:
+///
+/// ```
+/// use ast::node::Node;
+/// use format::formatter::Formatter;
+///
+/// let formatter = Formatter::default().with_trailing_newline(true);
+///
+/// assert_eq!("[]\n", formatter.format(&Node::Array(vec![])));
+/// ```
+///
+/// The separator between keys and values follows the `ColonStyle`, `": "` by default:
+///
+/// ```
+/// use ast::node::Node;
+/// use format::formatter::{ColonStyle, Formatter};
+///
+/// let ast = Node::Object(vec![Node::Property(
+///     Box::new(Node::Literal("\"key\"")),
+///     Box::new(Node::Literal("1")),
+/// )]);
+///
+/// let formatter = Formatter::new(0).with_colon_style(ColonStyle::SpaceAround);
+///
+/// assert_eq!("{\n\"key\" : 1\n}", formatter.format(&ast));
+/// ```
+///
+/// The canonical formatter rewrites literals into a single spelling, see `format::literal`, so
+/// documents with the same values format identically:
+///
+/// ```
+/// use ast::node::Node;
+/// use format::formatter::Formatter;
+///
+/// let ast = Node::Array(vec![Node::Literal("\"\\u0041\""), Node::Literal("1.0e2")]);
+///
+/// assert_eq!("[\n    \"A\",\n    100\n]", Formatter::canonical().format(&ast));
+/// ```
+///
+/// ## Options
+///
+/// `Formatter::new` and `Formatter::default` are shortcuts for common setups. Every option has a
+/// consuming `with_*` method so they can be chained in any order, later calls overriding earlier
+/// ones:
+///
+/// ```
+/// use ast::node::Node;
+/// use format::formatter::{ColonStyle, Formatter};
+///
+/// let ast = Node::Object(vec![
+///     Node::Property(Box::new(Node::Literal("\"b\"")), Box::new(Node::Literal("1"))),
+///     Node::Property(Box::new(Node::Literal("\"a\"")), Box::new(Node::Literal("2"))),
+/// ]);
+///
+/// let formatter = Formatter::default()
+///     .with_spaces(2)
+///     .with_sort_keys(true)
+///     .with_colon_style(ColonStyle::Compact)
+///     .with_trailing_newline(true);
+///
+/// assert_eq!("{\n  \"a\":2,\n  \"b\":1\n}\n", formatter.format(&ast));
+/// ```
 #[derive(Debug, PartialEq)]
 pub struct Formatter {
-    space: usize,
+    indent: String,
+    compact_arrays: bool,
+    trailing_newline: bool,
+    canonical: bool,
+    ensure_ascii: bool,
+    colon_style: ColonStyle,
+    max_width: Option<usize>,
+    collapse_empty: bool,
+    collapse_single: bool,
+    space_in_empty: bool,
+    sort_keys: bool,
+    align_values: bool,
+    base_indent: usize,
+}
+
+/// Separator written between a property key and its value
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ColonStyle {
+    /// `"key":value`
+    Compact,
+    /// `"key": value`
+    #[default]
+    SpaceAfter,
+    /// `"key" : value`
+    SpaceAround,
+}
+
+impl ColonStyle {
+    fn separator(&self) -> &'static str {
+        match self {
+            ColonStyle::Compact => ":",
+            ColonStyle::SpaceAfter => ": ",
+            ColonStyle::SpaceAround => " : ",
+        }
+    }
 }
 
 impl Default for Formatter {
     fn default() -> Self {
-        Self { space: 4 }
+        Self {
+            indent: " ".repeat(4),
+            compact_arrays: false,
+            trailing_newline: false,
+            canonical: false,
+            ensure_ascii: false,
+            colon_style: ColonStyle::default(),
+            max_width: None,
+            collapse_empty: true,
+            collapse_single: false,
+            space_in_empty: false,
+            sort_keys: false,
+            align_values: false,
+            base_indent: 0,
+        }
     }
 }
 
 impl Formatter {
     pub fn new(space: usize) -> Self {
-        Self { space }
+        Self::default().with_spaces(space)
+    }
+
+    /// Indent each level of depth with `space` spaces, 4 by default
+    pub fn with_spaces(self, space: usize) -> Self {
+        self.with_indent(" ".repeat(space))
+    }
+
+    /// Use a custom indent unit, such as `"\t"`, for each level of depth
+    pub fn with_indent(self, indent: impl Into<String>) -> Self {
+        Self {
+            indent: indent.into(),
+            ..self
+        }
+    }
+
+    pub fn canonical() -> Self {
+        Self::default().with_canonical(true)
+    }
+
+    pub fn with_canonical(self, canonical: bool) -> Self {
+        Self { canonical, ..self }
+    }
+
+    /// Escape every non-ASCII character in string literals as `\uXXXX`, see
+    /// `format::literal::ascii_literal`, for systems that mangle other encodings
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use format::formatter::Formatter;
+    ///
+    /// let ast = Node::Array(vec![Node::Literal("\"café\"")]);
+    ///
+    /// assert_eq!("[\n    \"caf\\u00e9\"\n]", Formatter::default().with_ensure_ascii(true).format(&ast));
+    /// ```
+    pub fn with_ensure_ascii(self, ensure_ascii: bool) -> Self {
+        Self {
+            ensure_ascii,
+            ..self
+        }
+    }
+
+    pub fn with_compact_arrays(self, compact_arrays: bool) -> Self {
+        Self {
+            compact_arrays,
+            ..self
+        }
+    }
+
+    pub fn with_colon_style(self, colon_style: ColonStyle) -> Self {
+        Self {
+            colon_style,
+            ..self
+        }
+    }
+
+    /// Keep an object or array on one line when its opening column plus its single-line
+    /// rendering, such as `{"a": 1, "b": [2]}`, is at most `max_width` characters
+    ///
+    /// Without a maximum width, the default, every non-empty object and array is expanded.
+    pub fn with_max_width(self, max_width: usize) -> Self {
+        Self {
+            max_width: Some(max_width),
+            ..self
+        }
+    }
+
+    /// Write empty objects and arrays as `{}` and `[]`, the default, instead of over two lines
+    pub fn with_collapse_empty(self, collapse_empty: bool) -> Self {
+        Self {
+            collapse_empty,
+            ..self
+        }
+    }
+
+    /// Keep objects and arrays with a single child on one line, such as `{"a": [1]}`, when the
+    /// child is a literal, a collapsed empty container or itself collapses
+    pub fn with_collapse_single(self, collapse_single: bool) -> Self {
+        Self {
+            collapse_single,
+            ..self
+        }
+    }
+
+    /// Write a space inside collapsed empty objects and arrays, `{ }` and `[ ]`, instead of `{}`
+    /// and `[]`, the default
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use format::formatter::Formatter;
+    ///
+    /// let ast = Node::Array(vec![Node::Object(vec![]), Node::Array(vec![])]);
+    ///
+    /// assert_eq!(
+    ///     "[\n    { },\n    [ ]\n]",
+    ///     Formatter::default().with_space_in_empty(true).format(&ast)
+    /// );
+    /// ```
+    pub fn with_space_in_empty(self, space_in_empty: bool) -> Self {
+        Self {
+            space_in_empty,
+            ..self
+        }
+    }
+
+    /// Write the properties of every object ordered by their unquoted key without changing the AST
+    ///
+    /// The sort is stable so duplicate keys keep their order, as with `Node::sort_keys`.
+    pub fn with_sort_keys(self, sort_keys: bool) -> Self {
+        Self { sort_keys, ..self }
+    }
+
+    /// Pad the keys of each multi-line object to the display width of its longest key so the
+    /// separators and values line up in a column
+    ///
+    /// Only properties of the same object are aligned, nested objects are aligned independently.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use format::formatter::Formatter;
+    ///
+    /// let ast = Node::Object(vec![
+    ///     Node::Property(Box::new(Node::Literal("\"a\"")), Box::new(Node::Literal("1"))),
+    ///     Node::Property(Box::new(Node::Literal("\"name\"")), Box::new(Node::Literal("2"))),
+    /// ]);
+    ///
+    /// assert_eq!(
+    ///     "{\n    \"a\"   : 1,\n    \"name\": 2\n}",
+    ///     Formatter::default().with_align_values(true).format(&ast)
+    /// );
+    /// ```
+    pub fn with_align_values(self, align_values: bool) -> Self {
+        Self {
+            align_values,
+            ..self
+        }
+    }
+
+    /// Start every line but the first with `base_indent` extra spaces, including the line of the
+    /// closing delimiter, so the output can be embedded in an already indented YAML or Markdown
+    /// block after its first character
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use format::formatter::Formatter;
+    ///
+    /// let ast = Node::Array(vec![Node::Literal("1")]);
+    ///
+    /// assert_eq!("[\n      1\n  ]", Formatter::default().with_base_indent(2).format(&ast));
+    /// ```
+    pub fn with_base_indent(self, base_indent: usize) -> Self {
+        Self {
+            base_indent,
+            ..self
+        }
+    }
+
+    pub fn with_trailing_newline(self, trailing_newline: bool) -> Self {
+        Self {
+            trailing_newline,
+            ..self
+        }
     }
 
     pub fn format(&self, ast: &Node) -> String {
-        self.depth_traversal(&ast, 0)
+        let mut json = String::new();
+
+        // Writing to a String cannot fail
+        let _ = self.write(ast, None, &mut json);
+
+        json
     }
 
-    fn depth_traversal(&self, ast: &Node, mut depth: usize) -> String {
-        match ast {
-            Node::Object(children) => {
-                if children.is_empty() {
-                    return String::from("{}");
-                }
+    /// Format only if the AST can be written as valid JSON
+    ///
+    /// Returns a `FormatError` instead of invalid output for objects with children other than
+    /// properties, properties outside of objects and keys that are not string literals.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use format::{format_error::FormatError, formatter::Formatter};
+    ///
+    /// let ast = Node::Object(vec![Node::Literal("true")]);
+    ///
+    /// assert_eq!(
+    ///     Err(FormatError::NonPropertyChild {
+    ///         path: "$".to_string(),
+    ///         found: "boolean".to_string(),
+    ///     }),
+    ///     Formatter::default().try_format(&ast)
+    /// );
+    /// ```
+    pub fn try_format(&self, ast: &Node) -> Result<String, FormatError> {
+        validate(ast)?;
 
-                let delimeter_spacing = " ".repeat(depth * self.space);
-                depth += 1;
-                let children_spacing = " ".repeat(depth * self.space);
+        Ok(self.format(ast))
+    }
 
-                let mut object = String::from("{\n");
+    /// Format directly into a writer, such as a file or stdout, without building a `String`
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use format::formatter::Formatter;
+    ///
+    /// let mut json = vec![];
+    ///
+    /// Formatter::new(2)
+    ///     .format_to(&Node::Array(vec![Node::Literal("1")]), &mut json)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(b"[\n  1\n]", json.as_slice());
+    /// ```
+    pub fn format_to<W: io::Write>(&self, ast: &Node, writer: &mut W) -> io::Result<()> {
+        let mut writer = IoWriter::new(writer);
 
-                let values = children
-                    .iter()
-                    .enumerate()
-                    .map(|(i, child)| {
-                        let mut value = String::new();
-                        value.push_str(&children_spacing);
-                        value.push_str(&self.depth_traversal(child, depth));
+        self.write(ast, None, &mut writer)
+            .map_err(|_| writer.into_error())
+    }
+
+    fn write<W: Write>(&self, ast: &Node, comments: Option<&Comments>, out: &mut W) -> fmt::Result {
+        self.depth_traversal(ast, 0, 0, comments, out)?;
+
+        if self.trailing_newline {
+            out.write_char('\n')?;
+        }
+
+        Ok(())
+    }
 
-                        if i < children.len() - 1 {
-                            value.push_str(",");
-                        }
+    /// Format and map each literal in the output back to its position in the source
+    ///
+    /// Returns pairs of output and source byte ranges, in output order, for every literal
+    /// including object keys. Literals that were not sliced from `source` are not mapped.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use format::formatter::Formatter;
+    ///
+    /// let source = "[true,1]";
+    /// let ast = Node::Array(vec![Node::Literal(&source[1..5]), Node::Literal(&source[6..7])]);
+    ///
+    /// let (json, map) = Formatter::default().format_with_map(source, &ast);
+    ///
+    /// assert_eq!("[\n    true,\n    1\n]", json);
+    /// assert_eq!(vec![(6..10, 1..5), (16..17, 6..7)], map);
+    /// ```
+    pub fn format_with_map(&self, source: &str, ast: &Node) -> (String, SourceMap) {
+        let json = self.format(ast);
 
-                        value.push_str("\n");
-                        return value;
-                    })
-                    .collect::<String>();
+        let mut literals = vec![];
+        self.literals(ast, &mut literals);
 
-                object.push_str(&values);
-                object.push_str(&delimeter_spacing);
-                object.push_str("}");
+        let mut map = vec![];
+        let mut cursor = 0;
 
-                depth -= 1;
+        // Whitespace and delimiters never contain the start of a literal so each literal is the
+        // next match after the previous one
+        for literal in literals {
+            let output = self.literal(literal);
 
-                return object;
+            let Some(start) = json[cursor..]
+                .find(output.as_ref())
+                .map(|start| start + cursor)
+            else {
+                continue;
+            };
+
+            cursor = start + output.len();
+
+            let source_start = (literal.as_ptr() as usize)
+                .checked_sub(source.as_ptr() as usize)
+                .filter(|source_start| source_start + literal.len() <= source.len());
+
+            if let Some(source_start) = source_start {
+                map.push((start..cursor, source_start..source_start + literal.len()));
+            }
+        }
+
+        (json, map)
+    }
+
+    /// Format and write each comment back on its own line before the object property it precedes
+    ///
+    /// `tokens` are the tokens of `source` scanned with `ParseOptions::preserve_comments`. A
+    /// comment is attached to a property when the next token after it is the property key, so
+    /// leading comments survive reformatting. Other comments, such as those before array values,
+    /// after the last property or after a value on the same line, are dropped. Objects with
+    /// comments are always expanded. Keys are matched to comments by their position in `source`, so
+    /// only an AST sliced from `source`, as it is when parsed from it, gets its comments back.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use format::formatter::Formatter;
+    /// use token::{token::Token, token_type::TokenType};
+    ///
+    /// let source = "{// port\n\"a\":1}";
+    /// let tokens = vec![
+    ///     Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+    ///     Token::new(TokenType::Comment, 1, (1, 8), (2, 9)),
+    ///     Token::new(TokenType::String, 2, (9, 12), (1, 4)),
+    ///     Token::new(TokenType::Colon, 2, (12, 13), (4, 5)),
+    ///     Token::new(TokenType::Number, 2, (13, 14), (5, 6)),
+    ///     Token::new(TokenType::RightBrace, 2, (14, 15), (6, 7)),
+    ///     Token::new(TokenType::Eof, 2, (15, 15), (7, 7)),
+    /// ];
+    /// let ast = Node::Object(vec![Node::Property(
+    ///     Box::new(Node::Literal(&source[9..12])),
+    ///     Box::new(Node::Literal(&source[13..14])),
+    /// )]);
+    ///
+    /// assert_eq!(
+    ///     "{\n  // port\n  \"a\": 1\n}",
+    ///     Formatter::new(2).format_with_comments(source, &tokens, &ast)
+    /// );
+    /// ```
+    pub fn format_with_comments(&self, source: &str, tokens: &[Token], ast: &Node) -> String {
+        // Comments before each key, keyed by the span of the key in the source
+        let mut spans = HashMap::new();
+        let mut leading = vec![];
+
+        for token in tokens {
+            match token.token_type {
+                TokenType::Comment => {
+                    leading.push(source[token.indices.0..token.indices.1].to_string());
+                }
+                _ if leading.is_empty() => {}
+                TokenType::String | TokenType::Identifier => {
+                    spans.insert(token.indices, std::mem::take(&mut leading));
+                }
+                _ => leading.clear(),
+            }
+        }
+
+        let mut json = String::new();
+        let comments = (!spans.is_empty()).then(|| Comments::new(ast, source, &mut spans));
+
+        // Writing to a String cannot fail
+        let _ = self.write(ast, comments.as_ref(), &mut json);
+
+        json
+    }
+
+    fn literals<'a>(&self, ast: &Node<'a>, literals: &mut Vec<&'a str>) {
+        match ast {
+            Node::Object(children) | Node::Array(children) => {
+                for (_, child) in self.ordered(children) {
+                    self.literals(child, literals);
+                }
             }
             Node::Property(key, value) => {
-                return format!(
-                    "{}: {}",
-                    self.depth_traversal(key, depth),
-                    self.depth_traversal(value, depth)
-                )
+                self.literals(key, literals);
+                self.literals(value, literals);
+            }
+            Node::Literal(literal) => literals.push(literal),
+        }
+    }
+
+    /// Write `ast` starting at `column` of a line indented to `depth`
+    ///
+    /// `comments` are the comments within `ast` from `format_with_comments`, objects and arrays
+    /// with comments are never written on a single line.
+    fn depth_traversal<W: Write>(
+        &self,
+        ast: &Node,
+        depth: usize,
+        column: usize,
+        comments: Option<&Comments>,
+        out: &mut W,
+    ) -> fmt::Result {
+        match ast {
+            Node::Object(children) | Node::Array(children)
+                if !children.is_empty()
+                    && (self.collapses(ast) || self.fits(ast, column))
+                    && !comments.is_some_and(|comments| comments.commented) =>
+            {
+                self.inline(ast, out)
+            }
+            Node::Object(children) if children.is_empty() && self.collapse_empty => {
+                self.empty('{', '}', out)
+            }
+            Node::Object(children) => {
+                out.write_str("{\n")?;
+                self.children(children, depth, comments, out)?;
+                out.write_char('}')
+            }
+            Node::Property(..) => self.property(ast, depth, column, 0, comments, out),
+            Node::Array(children) if children.is_empty() && self.collapse_empty => {
+                self.empty('[', ']', out)
+            }
+            Node::Array(children)
+                if self.compact_arrays
+                    && children
+                        .iter()
+                        .all(|child| matches!(child, Node::Literal(_))) =>
+            {
+                self.inline(ast, out)
             }
             Node::Array(children) => {
+                out.write_str("[\n")?;
+                self.children(children, depth, comments, out)?;
+                out.write_char(']')
+            }
+            Node::Literal(literal) => out.write_str(&self.literal(literal)),
+        }
+    }
+
+    /// Write a key padded to at least `key_width` display columns, the separator and the value,
+    /// other nodes are written as they are
+    fn property<W: Write>(
+        &self,
+        ast: &Node,
+        depth: usize,
+        column: usize,
+        key_width: usize,
+        comments: Option<&Comments>,
+        out: &mut W,
+    ) -> fmt::Result {
+        let Node::Property(key, value) = ast else {
+            return self.depth_traversal(ast, depth, column, comments, out);
+        };
+
+        self.depth_traversal(key, depth, column, None, out)?;
+
+        let padding = key_width.saturating_sub(self.display_width(key));
+        write!(out, "{:padding$}", "")?;
+        out.write_str(self.colon_style.separator())?;
+
+        let column = column + self.width(key) + padding + self.colon_style.separator().len();
+
+        let comments = comments.and_then(|comments| comments.children.first());
+
+        self.depth_traversal(value, depth, column, comments, out)
+    }
+
+    /// Display width of the widest key among `children` with `align_values`, otherwise 0
+    fn key_width(&self, children: &[Node]) -> usize {
+        if !self.align_values {
+            return 0;
+        }
+
+        children
+            .iter()
+            .filter_map(|child| match child {
+                Node::Property(key, _) => Some(self.display_width(key)),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Columns a literal takes up in a terminal, where wide characters such as CJK take two
+    fn display_width(&self, ast: &Node) -> usize {
+        match ast {
+            Node::Literal(literal) => self.literal(literal).width(),
+            _ => 0,
+        }
+    }
+
+    /// Write each child on its own line one level deeper, followed by the closing delimiter
+    /// spacing
+    fn children<W: Write>(
+        &self,
+        children: &[Node],
+        depth: usize,
+        comments: Option<&Comments>,
+        out: &mut W,
+    ) -> fmt::Result {
+        let key_width = self.key_width(children);
+        let column = self.base_indent + self.indent.chars().count() * (depth + 1);
+
+        for (i, (index, child)) in self.ordered(children).into_iter().enumerate() {
+            let comments = comments.and_then(|comments| comments.children.get(index));
+
+            for comment in comments.map_or(&[][..], |comments| &comments.leading) {
+                self.spacing(depth + 1, out)?;
+                out.write_str(comment)?;
+                out.write_char('\n')?;
+            }
+
+            self.spacing(depth + 1, out)?;
+            self.property(child, depth + 1, column, key_width, comments, out)?;
+
+            if i < children.len() - 1 {
+                out.write_char(',')?;
+            }
+
+            out.write_char('\n')?;
+        }
+
+        self.spacing(depth, out)
+    }
+
+    /// Write `ast` on a single line with `, ` between children
+    fn inline<W: Write>(&self, ast: &Node, out: &mut W) -> fmt::Result {
+        match ast {
+            Node::Object(children) | Node::Array(children) => {
+                let (open, close) = match ast {
+                    Node::Object(_) => ('{', '}'),
+                    _ => ('[', ']'),
+                };
+
                 if children.is_empty() {
-                    return String::from("[]");
+                    return self.empty(open, close, out);
                 }
 
-                let delimeter_spacing = " ".repeat(depth * self.space);
-                depth += 1;
-                let children_spacing = " ".repeat(depth * self.space);
+                out.write_char(open)?;
 
-                let mut array = String::from("[\n");
+                for (i, (_, child)) in self.ordered(children).into_iter().enumerate() {
+                    if i > 0 {
+                        out.write_str(", ")?;
+                    }
 
-                let values = children
-                    .iter()
-                    .enumerate()
-                    .map(|(i, child)| {
-                        let mut value = String::new();
-                        value.push_str(&children_spacing);
-                        value.push_str(&self.depth_traversal(child, depth));
+                    self.inline(child, out)?;
+                }
+
+                out.write_char(close)
+            }
+            Node::Property(key, value) => {
+                self.inline(key, out)?;
+                out.write_str(self.colon_style.separator())?;
+                self.inline(value, out)
+            }
+            Node::Literal(literal) => out.write_str(&self.literal(literal)),
+        }
+    }
+
+    /// Write an empty object or array on one line, with a space inside with `space_in_empty`
+    fn empty<W: Write>(&self, open: char, close: char, out: &mut W) -> fmt::Result {
+        out.write_char(open)?;
 
-                        if i < children.len() - 1 {
-                            value.push_str(",");
-                        }
+        if self.space_in_empty {
+            out.write_char(' ')?;
+        }
+
+        out.write_char(close)
+    }
+
+    /// Children with their index in the order they are written, sorted by key with `sort_keys`
+    fn ordered<'a, 'source>(
+        &self,
+        children: &'a [Node<'source>],
+    ) -> Vec<(usize, &'a Node<'source>)> {
+        let mut ordered: Vec<(usize, &Node)> = children.iter().enumerate().collect();
 
-                        value.push_str("\n");
-                        return value;
-                    })
-                    .collect::<String>();
+        if self.sort_keys {
+            ordered.sort_by(|(_, a), (_, b)| a.key().cmp(&b.key()));
+        }
+
+        ordered
+    }
+
+    /// Whether `ast` is kept on one line by `collapse_empty` or `collapse_single`
+    fn collapses(&self, ast: &Node) -> bool {
+        match ast {
+            Node::Object(children) | Node::Array(children) => match children.as_slice() {
+                [] => self.collapse_empty,
+                [child] => self.collapse_single && self.collapses(child),
+                _ => false,
+            },
+            Node::Property(_, value) => self.collapses(value),
+            Node::Literal(_) => true,
+        }
+    }
+
+    /// Whether the single-line rendering of `ast` starting at `column` is within the max width
+    fn fits(&self, ast: &Node, column: usize) -> bool {
+        self.max_width
+            .and_then(|max_width| max_width.checked_sub(column))
+            .and_then(|budget| self.inline_width(ast, budget))
+            .is_some()
+    }
 
-                array.push_str(&values);
-                array.push_str(&delimeter_spacing);
-                array.push_str("]");
+    /// Width of the single-line rendering of `ast`, or `None` as soon as it exceeds `budget` so
+    /// large subtrees are not measured in full
+    fn inline_width(&self, ast: &Node, budget: usize) -> Option<usize> {
+        let width = match ast {
+            Node::Object(children) | Node::Array(children) => {
+                let mut width = match children.len() {
+                    0 if self.space_in_empty => 3,
+                    len => 2 + len.saturating_sub(1) * 2,
+                };
 
-                depth -= 1;
+                for child in children {
+                    width += self.inline_width(child, budget.checked_sub(width)?)?;
+                }
 
-                return array;
+                width
+            }
+            Node::Property(key, value) => {
+                let width = self.width(key) + self.colon_style.separator().len();
+                width + self.inline_width(value, budget.checked_sub(width)?)?
             }
-            Node::Literal(literal) => return literal.to_string(),
+            Node::Literal(_) => self.width(ast),
+        };
+
+        Some(width).filter(|&width| width <= budget)
+    }
+
+    /// Characters in a literal as it is written, 0 for objects and arrays
+    fn width(&self, ast: &Node) -> usize {
+        match ast {
+            Node::Literal(literal) => self.literal(literal).chars().count(),
+            _ => 0,
         }
     }
+
+    /// Literal as it is written, rewritten by `canonical` and `ensure_ascii`
+    fn literal<'a>(&self, literal: &'a str) -> Cow<'a, str> {
+        match (self.canonical, self.ensure_ascii) {
+            (false, false) => Cow::Borrowed(literal),
+            (false, true) => ascii_literal(literal),
+            (true, false) => Cow::Owned(canonical_literal(literal)),
+            (true, true) => Cow::Owned(ascii_literal(&canonical_literal(literal)).into_owned()),
+        }
+    }
+
+    fn spacing<W: Write>(&self, depth: usize, out: &mut W) -> fmt::Result {
+        for _ in 0..self.base_indent {
+            out.write_char(' ')?;
+        }
+
+        for _ in 0..depth {
+            out.write_str(&self.indent)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Comments written before properties, mirroring the AST passed to `format_with_comments`
+#[derive(Debug, Default)]
+struct Comments {
+    /// Comments before a property, empty for other nodes
+    leading: Vec<String>,
+    /// Whether the node or any node within it has leading comments
+    commented: bool,
+    /// Comments of each child, or of the value of a property, empty when nothing within the node
+    /// has comments
+    children: Vec<Comments>,
+}
+
+impl Comments {
+    /// Attach the comments in `spans` to the properties of `ast` whose key is at that span of
+    /// `source`
+    fn new(ast: &Node, source: &str, spans: &mut HashMap<(usize, usize), Vec<String>>) -> Self {
+        let (leading, children) = match ast {
+            Node::Object(children) | Node::Array(children) => (
+                vec![],
+                children
+                    .iter()
+                    .map(|child| Self::new(child, source, spans))
+                    .collect(),
+            ),
+            Node::Property(key, value) => (
+                Self::span(key, source)
+                    .and_then(|span| spans.remove(&span))
+                    .unwrap_or_default(),
+                vec![Self::new(value, source, spans)],
+            ),
+            Node::Literal(_) => (vec![], vec![]),
+        };
+
+        let commented = !leading.is_empty() || children.iter().any(|comments| comments.commented);
+
+        Self {
+            leading,
+            commented,
+            children: if commented { children } else { vec![] },
+        }
+    }
+
+    /// Span of a key literal within `source`, `None` if it was not sliced from `source`
+    fn span(key: &Node, source: &str) -> Option<(usize, usize)> {
+        let Node::Literal(key) = key else {
+            return None;
+        };
+
+        (key.as_ptr() as usize)
+            .checked_sub(source.as_ptr() as usize)
+            .map(|start| (start, start + key.len()))
+            .filter(|&(_, end)| end <= source.len())
+    }
 }
 
 #[cfg(test)]
@@ -177,6 +958,462 @@ mod format_tests {
         );
     }
 
+    #[test]
+    fn format_compact_arrays() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"weights\"")),
+            Box::new(Node::Array(vec![
+                Node::Array(vec![Node::Literal("1"), Node::Literal("2")]),
+                Node::Array(vec![]),
+                Node::Array(vec![Node::Object(vec![])]),
+            ])),
+        )]);
+
+        let f = Formatter::default().with_compact_arrays(true);
+
+        assert_eq!(
+            "{\n    \"weights\": [\n        [1, 2],\n        [],\n        [\n            {}\n        ]\n    ]\n}",
+            f.format(&ast)
+        );
+    }
+
+    #[test]
+    fn compact_arrays_off_by_default() {
+        let ast = Node::Array(vec![Node::Literal("1"), Node::Literal("2")]);
+
+        assert_eq!("[\n    1,\n    2\n]", Formatter::default().format(&ast));
+    }
+
+    #[test]
+    fn format_within_max_width() {
+        let ast = Node::Array(vec![
+            Node::Object(vec![Node::Property(
+                Box::new(Node::Literal("\"a\"")),
+                Box::new(Node::Array(vec![Node::Literal("1"), Node::Object(vec![])])),
+            )]),
+            Node::Array(vec![
+                Node::Literal("\"0123456789\""),
+                Node::Literal("\"0123456789\""),
+            ]),
+        ]);
+
+        // The first element is 14 characters wide and starts at column 4
+        assert_eq!(
+            "[\n    {\"a\": [1, {}]},\n    [\n        \"0123456789\",\n        \"0123456789\"\n    ]\n]",
+            Formatter::default().with_max_width(18).format(&ast)
+        );
+        assert_eq!(
+            "[\n    {\n        \"a\": [\n            1,\n            {}\n        ]\n    },\n    [\n        \"0123456789\",\n        \"0123456789\"\n    ]\n]",
+            Formatter::default().with_max_width(17).format(&ast)
+        );
+        assert_eq!(
+            "[{\"a\": [1, {}]}, [\"0123456789\", \"0123456789\"]]",
+            Formatter::default().with_max_width(46).format(&ast)
+        );
+        assert_eq!(
+            Formatter::default().format(&ast),
+            Formatter::default().with_max_width(0).format(&ast)
+        );
+    }
+
+    #[test]
+    fn collapse_empty_and_single_containers() {
+        let ast = Node::Object(vec![
+            Node::Property(
+                Box::new(Node::Literal("\"a\"")),
+                Box::new(Node::Array(vec![Node::Object(vec![Node::Property(
+                    Box::new(Node::Literal("\"b\"")),
+                    Box::new(Node::Array(vec![])),
+                )])])),
+            ),
+            Node::Property(
+                Box::new(Node::Literal("\"c\"")),
+                Box::new(Node::Array(vec![Node::Literal("1"), Node::Literal("2")])),
+            ),
+        ]);
+
+        assert_eq!(
+            "{\n  \"a\": [{\"b\": []}],\n  \"c\": [\n    1,\n    2\n  ]\n}",
+            Formatter::new(2).with_collapse_single(true).format(&ast)
+        );
+        assert_eq!(
+            "{\n  \"a\": [\n    {\n      \"b\": [\n      ]\n    }\n  ],\n  \"c\": [\n    1,\n    2\n  ]\n}",
+            Formatter::new(2)
+                .with_collapse_single(true)
+                .with_collapse_empty(false)
+                .format(&ast)
+        );
+    }
+
+    #[test]
+    fn format_space_in_empty_containers() {
+        let ast = Node::Object(vec![
+            Node::Property(
+                Box::new(Node::Literal("\"a\"")),
+                Box::new(Node::Array(vec![Node::Object(vec![])])),
+            ),
+            Node::Property(
+                Box::new(Node::Literal("\"b\"")),
+                Box::new(Node::Array(vec![])),
+            ),
+        ]);
+
+        let f = Formatter::new(2).with_space_in_empty(true);
+
+        assert_eq!(
+            "{\n  \"a\": [\n    { }\n  ],\n  \"b\": [ ]\n}",
+            f.format(&ast)
+        );
+        assert_eq!(
+            "{\n  \"a\": [{ }],\n  \"b\": [ ]\n}",
+            Formatter::new(2)
+                .with_space_in_empty(true)
+                .with_collapse_single(true)
+                .format(&ast)
+        );
+        assert_eq!(
+            "{\"a\": [{ }], \"b\": [ ]}",
+            Formatter::new(2)
+                .with_space_in_empty(true)
+                .with_max_width(22)
+                .format(&ast)
+        );
+        assert_eq!(
+            "{\n  \"a\": [{ }],\n  \"b\": [ ]\n}",
+            Formatter::new(2)
+                .with_space_in_empty(true)
+                .with_max_width(21)
+                .format(&ast)
+        );
+        assert_eq!(
+            "[\n]",
+            Formatter::new(2)
+                .with_space_in_empty(true)
+                .with_collapse_empty(false)
+                .format(&Node::Array(vec![]))
+        );
+    }
+
+    #[test]
+    fn format_with_trailing_newline() {
+        let ast = Node::Object(vec![]);
+
+        let f = Formatter::default()
+            .with_trailing_newline(true)
+            .with_trailing_newline(true);
+
+        assert_eq!("{}\n", f.format(&ast));
+        assert_eq!("{}\n", f.format(&ast));
+        assert_eq!("{}", Formatter::default().format(&ast));
+    }
+
+    #[test]
+    fn format_canonical_literals() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"\\u006Bey\"")),
+            Box::new(Node::Array(vec![
+                Node::Literal("\"\\uD83D\\uDE00\\/\""),
+                Node::Literal("-0.50E1"),
+                Node::Literal("null"),
+            ])),
+        )]);
+
+        assert_eq!(
+            "{\n    \"key\": [\n        \"😀/\",\n        -5,\n        null\n    ]\n}",
+            Formatter::canonical().format(&ast)
+        );
+    }
+
+    #[test]
+    fn format_ascii_literals() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"clé\"")),
+            Box::new(Node::Array(vec![
+                Node::Literal("\"\\uD83D\\uDE00\""),
+                Node::Literal("\"€\""),
+            ])),
+        )]);
+
+        assert_eq!(
+            "{\n    \"cl\\u00e9\": [\n        \"\\uD83D\\uDE00\",\n        \"\\u20ac\"\n    ]\n}",
+            Formatter::default().with_ensure_ascii(true).format(&ast)
+        );
+        assert_eq!(
+            "{\n    \"cl\\u00e9\": [\n        \"\\ud83d\\ude00\",\n        \"\\u20ac\"\n    ]\n}",
+            Formatter::canonical().with_ensure_ascii(true).format(&ast)
+        );
+    }
+
+    #[test]
+    fn format_aligned_values() {
+        let property = |key, value| Node::Property(Box::new(Node::Literal(key)), Box::new(value));
+
+        let ast = Node::Object(vec![
+            property("\"id\"", Node::Literal("1")),
+            property(
+                "\"nested\"",
+                Node::Object(vec![
+                    property("\"名前\"", Node::Literal("true")),
+                    property("\"long_key\"", Node::Literal("null")),
+                ]),
+            ),
+            property("\"x\"", Node::Array(vec![])),
+        ]);
+
+        assert_eq!(
+            [
+                "{",
+                "  \"id\"     : 1,",
+                "  \"nested\" : {",
+                "    \"名前\"     : true,",
+                "    \"long_key\" : null",
+                "  },",
+                "  \"x\"      : []",
+                "}",
+            ]
+            .join("\n"),
+            Formatter::new(2)
+                .with_align_values(true)
+                .with_colon_style(ColonStyle::SpaceAround)
+                .format(&ast)
+        );
+        assert_eq!(
+            "{\n  \"id\": 1,\n  \"nested\": {\n    \"名前\": true,\n    \"long_key\": null\n  },\n  \"x\": []\n}",
+            Formatter::new(2).format(&ast)
+        );
+    }
+
+    #[test]
+    fn format_with_source_map() {
+        let source = "{\"a\":[1.0, null]}";
+
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal(&source[1..4])),
+            Box::new(Node::Array(vec![
+                Node::Literal(&source[6..9]),
+                Node::Literal(&source[11..15]),
+            ])),
+        )]);
+
+        let (json, map) = Formatter::canonical()
+            .with_compact_arrays(true)
+            .format_with_map(source, &ast);
+
+        assert_eq!("{\n    \"a\": [1, null]\n}", json);
+        assert_eq!(vec![(6..9, 1..4), (12..13, 6..9), (15..19, 11..15)], map);
+    }
+
+    #[test]
+    fn skip_literals_outside_source() {
+        let ast = Node::Array(vec![Node::Literal("true")]);
+
+        let (json, map) = Formatter::default().format_with_map("[false]", &ast);
+
+        assert_eq!("[\n    true\n]", json);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn format_with_leading_comments() {
+        let source = "{/* a */ \"a\": {// b\n\"b\": [1]}, // dropped\n}";
+        let tokens = vec![
+            Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+            Token::new(TokenType::Comment, 1, (1, 8), (2, 9)),
+            Token::new(TokenType::String, 1, (9, 12), (10, 13)),
+            Token::new(TokenType::Colon, 1, (12, 13), (13, 14)),
+            Token::new(TokenType::LeftBrace, 1, (14, 15), (15, 16)),
+            Token::new(TokenType::Comment, 1, (15, 19), (16, 20)),
+            Token::new(TokenType::String, 2, (20, 23), (1, 4)),
+            Token::new(TokenType::Colon, 2, (23, 24), (4, 5)),
+            Token::new(TokenType::LeftBracket, 2, (25, 26), (6, 7)),
+            Token::new(TokenType::Number, 2, (26, 27), (7, 8)),
+            Token::new(TokenType::RightBracket, 2, (27, 28), (8, 9)),
+            Token::new(TokenType::RightBrace, 2, (28, 29), (9, 10)),
+            Token::new(TokenType::Comma, 2, (29, 30), (10, 11)),
+            Token::new(TokenType::Comment, 2, (31, 41), (12, 22)),
+            Token::new(TokenType::RightBrace, 3, (42, 43), (1, 2)),
+            Token::new(TokenType::Eof, 3, (43, 43), (2, 2)),
+        ];
+
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal(&source[9..12])),
+            Box::new(Node::Object(vec![Node::Property(
+                Box::new(Node::Literal(&source[20..23])),
+                Box::new(Node::Array(vec![Node::Literal(&source[26..27])])),
+            )])),
+        )]);
+
+        let formatter = Formatter::new(2).with_max_width(80);
+
+        assert_eq!(
+            "{\n  /* a */\n  \"a\": {\n    // b\n    \"b\": [1]\n  }\n}",
+            formatter.format_with_comments(source, &tokens, &ast)
+        );
+        assert_eq!("{\"a\": {\"b\": [1]}}", formatter.format(&ast));
+    }
+
+    #[test]
+    fn format_sorted_keys_with_comments() {
+        let source = "{\"b\":1,// a\n\"a\":2}";
+        let tokens = vec![
+            Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+            Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+            Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+            Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+            Token::new(TokenType::Comma, 1, (6, 7), (7, 8)),
+            Token::new(TokenType::Comment, 1, (7, 11), (8, 12)),
+            Token::new(TokenType::String, 2, (12, 15), (1, 4)),
+            Token::new(TokenType::Colon, 2, (15, 16), (4, 5)),
+            Token::new(TokenType::Number, 2, (16, 17), (5, 6)),
+            Token::new(TokenType::RightBrace, 2, (17, 18), (6, 7)),
+            Token::new(TokenType::Eof, 2, (18, 18), (7, 7)),
+        ];
+
+        let property = |key, value| Node::Property(Box::new(Node::Literal(key)), Box::new(value));
+
+        let ast = Node::Object(vec![
+            property(&source[1..4], Node::Literal(&source[5..6])),
+            property(&source[12..15], Node::Literal(&source[16..17])),
+        ]);
+
+        assert_eq!(
+            "{\n  // a\n  \"a\": 2,\n  \"b\": 1\n}",
+            Formatter::new(2)
+                .with_sort_keys(true)
+                .format_with_comments(source, &tokens, &ast)
+        );
+
+        // Keys not sliced from the source are not matched with its comments
+        let ast = Node::Object(vec![
+            property("\"b\"", Node::Literal("1")),
+            property("\"a\"", Node::Literal("2")),
+        ]);
+
+        assert_eq!(
+            "{\n  \"b\": 1,\n  \"a\": 2\n}",
+            Formatter::new(2).format_with_comments(source, &tokens, &ast)
+        );
+    }
+
+    #[test]
+    fn share_formatter_between_threads() {
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        assert_sync(&Formatter::default());
+    }
+
+    #[test]
+    fn format_with_sorted_keys() {
+        let property = |key, value| Node::Property(Box::new(Node::Literal(key)), Box::new(value));
+
+        let ast = Node::Object(vec![
+            property("\"c\"", Node::Literal("1")),
+            property(
+                "\"a\"",
+                Node::Array(vec![Node::Object(vec![
+                    property("\"y\"", Node::Literal("2")),
+                    property("\"x\"", Node::Literal("3")),
+                ])]),
+            ),
+            property("\"b\"", Node::Literal("4")),
+        ]);
+
+        let formatter = Formatter::new(1).with_sort_keys(true);
+
+        assert_eq!(
+            "{\n \"a\": [\n  {\n   \"x\": 3,\n   \"y\": 2\n  }\n ],\n \"b\": 4,\n \"c\": 1\n}",
+            formatter.format(&ast)
+        );
+        assert_eq!(
+            "{\"a\": [{\"x\": 3, \"y\": 2}], \"b\": 4, \"c\": 1}",
+            formatter.with_max_width(80).format(&ast)
+        );
+    }
+
+    #[test]
+    fn format_with_base_indent() {
+        let ast = Node::Object(vec![
+            Node::Property(
+                Box::new(Node::Literal("\"a\"")),
+                Box::new(Node::Array(vec![Node::Literal("1"), Node::Literal("2")])),
+            ),
+            Node::Property(
+                Box::new(Node::Literal("\"b\"")),
+                Box::new(Node::Literal("3")),
+            ),
+        ]);
+
+        let formatter = Formatter::new(2).with_base_indent(4);
+
+        assert_eq!(
+            "{\n      \"a\": [\n        1,\n        2\n      ],\n      \"b\": 3\n    }",
+            formatter.format(&ast)
+        );
+        assert_eq!(
+            "{\n  \"a\": [1, 2],\n  \"b\": 3\n}",
+            Formatter::new(2).with_max_width(16).format(&ast)
+        );
+        assert_eq!(
+            formatter.format(&ast),
+            formatter.with_max_width(16).format(&ast)
+        );
+        assert_eq!(
+            "1",
+            Formatter::default()
+                .with_base_indent(4)
+                .format(&Node::Literal("1"))
+        );
+    }
+
+    #[test]
+    fn format_with_tab_indent() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"tags\"")),
+            Box::new(Node::Array(vec![Node::Literal("1")])),
+        )]);
+
+        let f = Formatter::new(2).with_indent("\t");
+
+        assert_eq!("{\n\t\"tags\": [\n\t\t1\n\t]\n}", f.format(&ast));
+    }
+
+    #[test]
+    fn format_to_writer() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"tags\"")),
+            Box::new(Node::Array(vec![Node::Literal("1"), Node::Object(vec![])])),
+        )]);
+
+        let f = Formatter::default().with_trailing_newline(true);
+        let mut json = vec![];
+
+        f.format_to(&ast, &mut json).unwrap();
+
+        assert_eq!(f.format(&ast).as_bytes(), json.as_slice());
+    }
+
+    #[test]
+    fn format_colon_styles() {
+        let ast = Node::Property(
+            Box::new(Node::Literal("\"key\"")),
+            Box::new(Node::Literal("\"value\"")),
+        );
+
+        assert_eq!("\"key\": \"value\"", Formatter::default().format(&ast));
+        assert_eq!(
+            "\"key\":\"value\"",
+            Formatter::default()
+                .with_colon_style(ColonStyle::Compact)
+                .format(&ast)
+        );
+        assert_eq!(
+            "\"key\" : \"value\"",
+            Formatter::default()
+                .with_colon_style(ColonStyle::SpaceAround)
+                .format(&ast)
+        );
+    }
+
     #[test]
     fn format_property() {
         let ast = Node::Property(
@@ -198,11 +1435,45 @@ mod format_tests {
 
     #[test]
     fn create_formatter() {
-        assert_eq!(Formatter { space: 2 }, Formatter::new(2));
+        assert_eq!(
+            Formatter {
+                indent: "  ".to_string(),
+                compact_arrays: false,
+                trailing_newline: false,
+                canonical: false,
+                ensure_ascii: false,
+                colon_style: ColonStyle::SpaceAfter,
+                max_width: None,
+                collapse_empty: true,
+                collapse_single: false,
+                space_in_empty: false,
+                sort_keys: false,
+                align_values: false,
+                base_indent: 0,
+            },
+            Formatter::new(2)
+        );
     }
 
     #[test]
     fn create_default_formatter() {
-        assert_eq!(Formatter { space: 4 }, Formatter::default());
+        assert_eq!(
+            Formatter {
+                indent: "    ".to_string(),
+                compact_arrays: false,
+                trailing_newline: false,
+                canonical: false,
+                ensure_ascii: false,
+                colon_style: ColonStyle::SpaceAfter,
+                max_width: None,
+                collapse_empty: true,
+                collapse_single: false,
+                space_in_empty: false,
+                sort_keys: false,
+                align_values: false,
+                base_indent: 0,
+            },
+            Formatter::default()
+        );
     }
 }
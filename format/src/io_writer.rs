@@ -0,0 +1,61 @@
+use std::{fmt, io};
+
+/// Adapter writing the `fmt::Write` traversals of the formatter and minifier to an `io::Write`
+///
+/// `fmt::Error` carries no information so the underlying `io::Error` is kept to be returned to
+/// the caller once the traversal stops.
+pub(crate) struct IoWriter<'a, W: io::Write> {
+    writer: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<'a, W: io::Write> IoWriter<'a, W> {
+    pub(crate) fn new(writer: &'a mut W) -> Self {
+        Self {
+            writer,
+            error: None,
+        }
+    }
+
+    pub(crate) fn into_error(self) -> io::Error {
+        self.error
+            .unwrap_or_else(|| io::Error::other("failed to write JSON"))
+    }
+}
+
+impl<W: io::Write> fmt::Write for IoWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.writer.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+#[cfg(test)]
+mod io_writer_tests {
+    use std::fmt::Write;
+
+    use super::*;
+
+    struct Full;
+
+    impl io::Write for Full {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::StorageFull, "full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn keep_io_error() {
+        let mut full = Full;
+        let mut writer = IoWriter::new(&mut full);
+
+        assert!(writer.write_str("[]").is_err());
+        assert_eq!(io::ErrorKind::StorageFull, writer.into_error().kind());
+    }
+}
@@ -1,2 +1,9 @@
+pub mod format_error;
 pub mod formatter;
+mod io_writer;
+mod limited_writer;
+pub mod literal;
 pub mod minifier;
+pub mod over_limit;
+pub mod preserving;
+mod validate;
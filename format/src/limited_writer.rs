@@ -0,0 +1,75 @@
+use std::fmt;
+
+use crate::over_limit::OverLimit;
+
+/// Adapter passing writes through until their total length would exceed `max_bytes`
+///
+/// Each write is passed on whole or not at all, so the output is a prefix of the complete output
+/// that never splits a literal or character. Once the limit is crossed nothing more is written
+/// but lengths are still counted so the full length can be reported.
+pub(crate) struct LimitedWriter<'a, W: fmt::Write> {
+    writer: &'a mut W,
+    max_bytes: usize,
+    bytes: usize,
+}
+
+impl<'a, W: fmt::Write> LimitedWriter<'a, W> {
+    pub(crate) fn new(writer: &'a mut W, max_bytes: usize) -> Self {
+        Self {
+            writer,
+            max_bytes,
+            bytes: 0,
+        }
+    }
+
+    /// Total length of everything written, or `OverLimit` if it exceeds `max_bytes`
+    pub(crate) fn finish(self) -> Result<usize, OverLimit> {
+        if self.bytes > self.max_bytes {
+            return Err(OverLimit {
+                max_bytes: self.max_bytes,
+                bytes: self.bytes,
+            });
+        }
+
+        Ok(self.bytes)
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for LimitedWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.bytes += s.len();
+
+        if self.bytes > self.max_bytes {
+            return Ok(());
+        }
+
+        self.writer.write_str(s)
+    }
+}
+
+#[cfg(test)]
+mod limited_writer_tests {
+    use std::fmt::Write;
+
+    use super::*;
+
+    #[test]
+    fn stop_writing_at_limit() {
+        let mut json = String::new();
+        let mut writer = LimitedWriter::new(&mut json, 5);
+
+        writer.write_str("[").unwrap();
+        writer.write_str("\"é\"").unwrap();
+        writer.write_str(",").unwrap();
+        writer.write_str("1").unwrap();
+
+        assert_eq!(
+            Err(OverLimit {
+                max_bytes: 5,
+                bytes: 7
+            }),
+            writer.finish()
+        );
+        assert_eq!("[\"é\"", json);
+    }
+}
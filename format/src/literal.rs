@@ -0,0 +1,176 @@
+use std::{borrow::Cow, fmt::Write};
+
+use token::{
+    escape::{escape_json_string, unescape_json_string},
+    number::decimal,
+};
+
+/// Canonical form of a JSON literal
+///
+/// ## Description
+///
+/// AST literals are slices of the original source, so two documents with the same meaning can
+/// spell a literal differently, for example `"\u0041"` and `"A"`, or `1.0e2` and `100`. The
+/// functions in this module decode a literal and encode it again in a single canonical spelling:
+///
+/// - Strings are decoded and escaped again with `token::escape`, so only `"`, `\`, and control
+///   characters are escaped. Characters from surrogate pairs such as `\uD83D\uDE00` are written
+///   as is.
+/// - Numbers use the shortest representation that parses back to the same `f64`, written either
+///   in plain or exponent notation, whichever is shorter.
+///
+/// Literals that cannot be canonicalised without changing their value are returned unchanged.
+/// Examples are strings with a lone surrogate and integers beyond the exact range of `f64`.
+///
+/// ## Examples
+///
+/// ```
+/// use format::literal::canonical_literal;
+///
+/// assert_eq!("\"A😀\"", canonical_literal("\"\\u0041\\uD83D\\uDE00\""));
+/// assert_eq!("100", canonical_literal("1.0e2"));
+/// assert_eq!("true", canonical_literal("true"));
+/// ```
+pub fn canonical_literal(literal: &str) -> String {
+    let canonical = match literal.as_bytes().first() {
+        Some(b'"') => canonical_string(literal),
+        Some(b'-' | b'0'..=b'9') => canonical_number(literal),
+        _ => None,
+    };
+
+    canonical.unwrap_or_else(|| literal.to_string())
+}
+
+/// Re-encode a string literal, returning `None` if it cannot be decoded
+pub fn canonical_string(literal: &str) -> Option<String> {
+    unescape_json_string(literal)
+        .ok()
+        .map(|value| escape_json_string(&value))
+}
+
+/// Shortest spelling of a number literal, returning `None` if it would change its value
+pub fn canonical_number(literal: &str) -> Option<String> {
+    let number = literal
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())?;
+
+    let exponent = format!("{:e}", number);
+
+    if decimal(literal)? != decimal(&exponent)? {
+        return None;
+    }
+
+    let plain = format!("{}", number);
+
+    if exponent.len() < plain.len() {
+        Some(exponent)
+    } else {
+        Some(plain)
+    }
+}
+
+/// Escape every non-ASCII character of a string literal as `\uXXXX`
+///
+/// Characters outside the Basic Multilingual Plane are written as a surrogate pair, for example
+/// `😀` becomes `\ud83d\ude00`. Escape sequences are always ASCII, so every non-ASCII character in
+/// a literal is a character written as is and can be replaced without decoding the rest of the
+/// literal. Existing escapes, including lone surrogates, are kept as written and literals other
+/// than strings are returned unchanged.
+///
+/// ```
+/// use format::literal::ascii_literal;
+///
+/// assert_eq!("\"caf\\u00e9 \\ud83d\\ude00\"", ascii_literal("\"café 😀\""));
+/// assert_eq!("\"plain\"", ascii_literal("\"plain\""));
+/// ```
+pub fn ascii_literal(literal: &str) -> Cow<'_, str> {
+    if literal.is_ascii() || !literal.starts_with('"') {
+        return Cow::Borrowed(literal);
+    }
+
+    let mut ascii = String::with_capacity(literal.len() + 16);
+
+    for char in literal.chars() {
+        if char.is_ascii() {
+            ascii.push(char);
+            continue;
+        }
+
+        for unit in char.encode_utf16(&mut [0; 2]) {
+            // Writing to a String cannot fail
+            let _ = write!(ascii, "\\u{:04x}", unit);
+        }
+    }
+
+    Cow::Owned(ascii)
+}
+
+#[cfg(test)]
+mod literal_tests {
+    use super::*;
+
+    #[test]
+    fn canonical_strings() {
+        assert_eq!("\"A\"", canonical_literal("\"\\u0041\""));
+        assert_eq!("\"/\"", canonical_literal("\"\\/\""));
+        assert_eq!("\"é\"", canonical_literal("\"\\u00E9\""));
+        assert_eq!("\"\\u001f\"", canonical_literal("\"\\u001F\""));
+        assert_eq!(
+            "\"\\n\\t\\\"\\\\\"",
+            canonical_literal("\"\\u000a\\t\\\"\\u005C\"")
+        );
+        assert_eq!("\"😀\"", canonical_literal("\"\\uD83D\\uDE00\""));
+        assert_eq!("\"😀\"", canonical_literal("\"😀\""));
+    }
+
+    #[test]
+    fn keep_lone_surrogates() {
+        assert_eq!("\"\\uD83D\"", canonical_literal("\"\\uD83D\""));
+        assert_eq!(
+            "\"\\uDE00\\uD83D\"",
+            canonical_literal("\"\\uDE00\\uD83D\"")
+        );
+        assert_eq!("\"\\uD83Dx\"", canonical_literal("\"\\uD83Dx\""));
+    }
+
+    #[test]
+    fn canonical_numbers() {
+        assert_eq!("100", canonical_literal("1.0e2"));
+        assert_eq!("1", canonical_literal("1.0"));
+        assert_eq!("1e3", canonical_literal("1E3"));
+        assert_eq!("999", canonical_literal("9.99e2"));
+        assert_eq!("0.5", canonical_literal("5e-1"));
+        assert_eq!("1e21", canonical_literal("1000000000000000000000"));
+        assert_eq!("1e-7", canonical_literal("0.0000001"));
+        assert_eq!("-0", canonical_literal("-0.0"));
+        assert_eq!("0", canonical_literal("0e10"));
+    }
+
+    #[test]
+    fn keep_numbers_that_do_not_round_trip() {
+        assert_eq!(
+            "12345678901234567890",
+            canonical_literal("12345678901234567890")
+        );
+        assert_eq!(
+            "0.10000000000000000001",
+            canonical_literal("0.10000000000000000001")
+        );
+    }
+
+    #[test]
+    fn ascii_strings() {
+        assert_eq!("\"caf\\u00e9\"", ascii_literal("\"café\""));
+        assert_eq!("\"\\u20ac1\"", ascii_literal("\"€1\""));
+        assert_eq!("\"\\ud83d\\ude00\"", ascii_literal("\"😀\""));
+        assert_eq!("\"\\u00E9\\uD83D\"", ascii_literal("\"\\u00E9\\uD83D\""));
+        assert_eq!("true", ascii_literal("true"));
+    }
+
+    #[test]
+    fn keep_keywords() {
+        assert_eq!("null", canonical_literal("null"));
+        assert_eq!("false", canonical_literal("false"));
+    }
+}
@@ -1,11 +1,26 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Write},
+    io,
+};
+
 use ast::node::Node;
 
+use crate::{
+    format_error::FormatError,
+    io_writer::IoWriter,
+    limited_writer::LimitedWriter,
+    literal::{ascii_literal, canonical_number},
+    over_limit::OverLimit,
+    validate::validate,
+};
+
 /// Minify JSON converting AST into String
 ///
 /// ## Description
 ///
 /// The minifier converts an AST into a string of JSON without formatting using depth traversal,
-/// O(n), to walk the tree.
+/// O(n), to walk the tree. The traversal is shared with the `Display` implementation of `Node`.
 ///
 /// ## Examples
 /// ```
@@ -17,57 +32,327 @@ use ast::node::Node;
 ///     Node::Literal("42"),
 /// ]);
 ///
-/// let minifier = Minifier;
+/// let minifier = Minifier::default();
 ///
 /// assert_eq!("[[true,false],42]", minifier.minify(&ast));
 /// ```
-pub struct Minifier;
+///
+/// Numbers are kept exactly as written unless number normalisation is enabled, which rewrites each
+/// number to its shortest equivalent. Numbers that cannot be represented exactly by an `f64` are
+/// left untouched.
+///
+/// ```
+/// use ast::node::Node;
+/// use format::minifier::Minifier;
+///
+/// let ast = Node::Array(vec![Node::Literal("1.0"), Node::Literal("1000000e0")]);
+///
+/// let minifier = Minifier::default().with_normalize_numbers(true);
+///
+/// assert_eq!("[1,1e6]", minifier.minify(&ast));
+/// ```
+///
+/// Arrays representing sets can be canonicalised by sorting their elements lexically by their
+/// minified output and dropping duplicates. This changes the meaning of the document so it is
+/// opt-in, objects and scalars are unaffected.
+///
+/// ```
+/// use ast::node::Node;
+/// use format::minifier::Minifier;
+///
+/// let ast = Node::Array(vec![
+///     Node::Literal("\"b\""),
+///     Node::Literal("1.0"),
+///     Node::Literal("\"a\""),
+///     Node::Literal("1"),
+/// ]);
+///
+/// let minifier = Minifier::default()
+///     .with_normalize_numbers(true)
+///     .with_sorted_arrays(true);
+///
+/// assert_eq!("[\"a\",\"b\",1]", minifier.minify(&ast));
+/// ```
+///
+/// Non-ASCII characters in strings can be escaped as `\uXXXX` for systems that mangle other
+/// encodings, see `format::literal::ascii_literal`.
+///
+/// ```
+/// use ast::node::Node;
+/// use format::minifier::Minifier;
+///
+/// let ast = Node::Array(vec![Node::Literal("\"café\"")]);
+///
+/// let minifier = Minifier::default().with_ensure_ascii(true);
+///
+/// assert_eq!("[\"caf\\u00e9\"]", minifier.minify(&ast));
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Minifier {
+    normalize_numbers: bool,
+    sorted_arrays: bool,
+    ensure_ascii: bool,
+}
 
 impl Minifier {
+    pub fn with_normalize_numbers(self, normalize_numbers: bool) -> Self {
+        Self {
+            normalize_numbers,
+            ..self
+        }
+    }
+
+    pub fn with_sorted_arrays(self, sorted_arrays: bool) -> Self {
+        Self {
+            sorted_arrays,
+            ..self
+        }
+    }
+
+    pub fn with_ensure_ascii(self, ensure_ascii: bool) -> Self {
+        Self {
+            ensure_ascii,
+            ..self
+        }
+    }
+
     pub fn minify(&self, ast: &Node) -> String {
-        self.depth_traversal(ast)
-    }
-
-    fn depth_traversal(&self, ast: &Node) -> String {
-        match ast {
-            Node::Object(children) => format!(
-                "{{{}}}",
-                children
-                    .iter()
-                    .enumerate()
-                    .map(|(i, child)| {
-                        let mut child = self.depth_traversal(child);
-
-                        if i < children.len() - 1 {
-                            child.push_str(",");
-                        }
-
-                        child
-                    })
-                    .collect::<String>()
-            ),
-            Node::Property(key, value) => format!(
-                "{}:{}",
-                self.depth_traversal(key),
-                self.depth_traversal(value)
-            ),
-            Node::Array(children) => format!(
-                "[{}]",
-                children
-                    .iter()
-                    .enumerate()
-                    .map(|(i, child)| {
-                        let mut child = self.depth_traversal(child);
-
-                        if i < children.len() - 1 {
-                            child.push_str(",");
-                        }
-
-                        child
-                    })
-                    .collect::<String>()
-            ),
-            Node::Literal(literal) => literal.to_string(),
+        let mut json = String::new();
+
+        // Writing to a String cannot fail
+        let _ = self.write(ast, &mut json);
+
+        json
+    }
+
+    /// Minify only if the AST can be written as valid JSON
+    ///
+    /// `minify` writes whatever it is given, so a hand-built AST such as an object containing a
+    /// bare literal produces `{true}`. This instead returns a `FormatError` for objects with
+    /// children other than properties, properties outside of objects and keys that are not string
+    /// literals.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use format::minifier::Minifier;
+    ///
+    /// let minifier = Minifier::default();
+    ///
+    /// assert_eq!(Ok("[true]".to_string()), minifier.try_minify(&Node::Array(vec![Node::Literal("true")])));
+    /// assert!(minifier.try_minify(&Node::Object(vec![Node::Literal("true")])).is_err());
+    /// ```
+    pub fn try_minify(&self, ast: &Node) -> Result<String, FormatError> {
+        validate(ast)?;
+
+        Ok(self.minify(ast))
+    }
+
+    /// Minify directly into a writer, such as a file or stdout, without building a `String`
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use format::minifier::Minifier;
+    ///
+    /// let mut json = vec![];
+    ///
+    /// Minifier::default()
+    ///     .minify_to(&Node::Array(vec![Node::Literal("1")]), &mut json)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(b"[1]", json.as_slice());
+    /// ```
+    pub fn minify_to<W: io::Write>(&self, ast: &Node, writer: &mut W) -> io::Result<()> {
+        let mut writer = IoWriter::new(writer);
+
+        self.write(ast, &mut writer)
+            .map_err(|_| writer.into_error())
+    }
+
+    /// Minify only if the output is at most `max_bytes` long
+    ///
+    /// Output beyond the limit is never built, only measured, so `OverLimit` holds the length the
+    /// complete output would have had.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use format::{minifier::Minifier, over_limit::OverLimit};
+    ///
+    /// let ast = Node::Array(vec![Node::Literal("\"café\""), Node::Literal("1")]);
+    ///
+    /// assert_eq!(Ok("[\"café\",1]".to_string()), Minifier::default().minify_within(&ast, 11));
+    /// assert_eq!(
+    ///     Err(OverLimit { max_bytes: 10, bytes: 11 }),
+    ///     Minifier::default().minify_within(&ast, 10)
+    /// );
+    /// ```
+    pub fn minify_within(&self, ast: &Node, max_bytes: usize) -> Result<String, OverLimit> {
+        let mut json = String::new();
+        let mut writer = LimitedWriter::new(&mut json, max_bytes);
+
+        // Writing to a String cannot fail
+        let _ = self.write(ast, &mut writer);
+
+        writer.finish()?;
+
+        Ok(json)
+    }
+
+    /// Minify into a writer, stopping before the output would exceed `max_bytes`
+    ///
+    /// Returns the number of bytes written. Over the limit, only the output that fits is written,
+    /// ending at a whole token or literal so multi-byte characters are never split, and an
+    /// `io::ErrorKind::FileTooLarge` error is returned holding an `OverLimit`.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use format::{minifier::Minifier, over_limit::OverLimit};
+    ///
+    /// let ast = Node::Array(vec![Node::Literal("\"café\""), Node::Literal("1")]);
+    /// let mut json = vec![];
+    ///
+    /// let error = Minifier::default()
+    ///     .minify_to_within(&ast, &mut json, 8)
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     Some(&OverLimit { max_bytes: 8, bytes: 11 }),
+    ///     error.get_ref().and_then(|error| error.downcast_ref())
+    /// );
+    /// assert_eq!("[\"café\"".as_bytes(), json.as_slice());
+    /// ```
+    pub fn minify_to_within<W: io::Write>(
+        &self,
+        ast: &Node,
+        writer: &mut W,
+        max_bytes: usize,
+    ) -> io::Result<usize> {
+        let mut writer = IoWriter::new(writer);
+        let mut limited = LimitedWriter::new(&mut writer, max_bytes);
+
+        if self.write(ast, &mut limited).is_err() {
+            return Err(writer.into_error());
+        }
+
+        limited
+            .finish()
+            .map_err(|over_limit| io::Error::new(io::ErrorKind::FileTooLarge, over_limit))
+    }
+
+    fn write<W: Write>(&self, ast: &Node, out: &mut W) -> fmt::Result {
+        if self.sorted_arrays {
+            return self.sorted_traversal(ast, out);
+        }
+
+        ast.write_minified(out, &|out: &mut W, literal| {
+            out.write_str(&self.literal(literal))
+        })
+    }
+
+    /// Minify with the elements of each array sorted and deduplicated
+    ///
+    /// Uses an explicit stack like `Node::write_minified` so deep trees cannot overflow. Output is
+    /// written straight to `out`, only the elements of an array with more than one element are
+    /// minified into strings to be sorted before the array is written.
+    fn sorted_traversal<W: Write>(&self, ast: &Node, out: &mut W) -> fmt::Result {
+        let mut stack = vec![Sorted::Node(ast)];
+        // Elements of each array being sorted, innermost last, with the element being minified
+        let mut arrays: Vec<(Vec<String>, String)> = vec![];
+
+        while let Some(next) = stack.pop() {
+            let json: &mut dyn Write = match arrays.last_mut() {
+                Some((_, element)) => element,
+                None => out,
+            };
+
+            match next {
+                Sorted::Node(Node::Object(children)) => {
+                    json.write_char('{')?;
+                    stack.push(Sorted::Delimiter('}'));
+                    push_children(&mut stack, children);
+                }
+                Sorted::Node(Node::Property(key, value)) => {
+                    stack.push(Sorted::Node(value));
+                    stack.push(Sorted::Delimiter(':'));
+                    stack.push(Sorted::Node(key));
+                }
+                Sorted::Node(Node::Array(children)) if children.len() < 2 => {
+                    json.write_char('[')?;
+                    stack.push(Sorted::Delimiter(']'));
+                    push_children(&mut stack, children);
+                }
+                Sorted::Node(Node::Array(children)) => {
+                    arrays.push((Vec::with_capacity(children.len()), String::new()));
+                    stack.push(Sorted::Array);
+
+                    for child in children.iter().rev() {
+                        stack.push(Sorted::Element);
+                        stack.push(Sorted::Node(child));
+                    }
+                }
+                Sorted::Node(Node::Literal(literal)) => json.write_str(&self.literal(literal))?,
+                Sorted::Delimiter(delimiter) => json.write_char(delimiter)?,
+                Sorted::Element => {
+                    if let Some((elements, element)) = arrays.last_mut() {
+                        elements.push(std::mem::take(element));
+                    }
+                }
+                Sorted::Array => {
+                    let Some((mut elements, _)) = arrays.pop() else {
+                        continue;
+                    };
+
+                    elements.sort();
+                    elements.dedup();
+
+                    let json: &mut dyn Write = match arrays.last_mut() {
+                        Some((_, element)) => element,
+                        None => out,
+                    };
+
+                    write!(json, "[{}]", elements.join(","))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn literal<'a>(&self, literal: &'a str) -> Cow<'a, str> {
+        if self.normalize_numbers
+            && literal.starts_with(|char: char| char == '-' || char.is_ascii_digit())
+        {
+            if let Some(number) = canonical_number(literal) {
+                if number.len() <= literal.len() {
+                    return Cow::Owned(number);
+                }
+            }
+        }
+
+        if self.ensure_ascii {
+            return ascii_literal(literal);
+        }
+
+        Cow::Borrowed(literal)
+    }
+}
+
+/// Step of the sorted traversal
+enum Sorted<'a, 'source> {
+    Node(&'a Node<'source>),
+    Delimiter(char),
+    /// End of an element of the innermost array being sorted
+    Element,
+    /// End of the innermost array being sorted
+    Array,
+}
+
+/// Push children in reverse, separated by commas, so they are popped in order
+fn push_children<'a, 'source>(stack: &mut Vec<Sorted<'a, 'source>>, children: &'a [Node<'source>]) {
+    for (i, child) in children.iter().enumerate().rev() {
+        stack.push(Sorted::Node(child));
+
+        if i > 0 {
+            stack.push(Sorted::Delimiter(','));
         }
     }
 }
@@ -83,11 +368,115 @@ mod minifier_tests {
             Box::new(Node::Literal("false")),
         )]);
 
-        let m = Minifier;
+        let m = Minifier::default();
 
         assert_eq!("{\"foundTreasure\":false}", m.minify(&ast));
     }
 
+    #[test]
+    fn minify_within_limit() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"emoji\"")),
+            Box::new(Node::Literal("\"😀\"")),
+        )]);
+
+        let m = Minifier::default();
+        let json = m.minify(&ast);
+
+        assert_eq!(Ok(json.clone()), m.minify_within(&ast, json.len()));
+        assert_eq!(
+            Err(OverLimit {
+                max_bytes: json.len() - 1,
+                bytes: json.len()
+            }),
+            m.minify_within(&ast, json.len() - 1)
+        );
+        assert_eq!(
+            Err(OverLimit {
+                max_bytes: 14,
+                bytes: 24
+            }),
+            Minifier::default()
+                .with_ensure_ascii(true)
+                .minify_within(&ast, 14)
+        );
+
+        let mut written = vec![];
+
+        assert_eq!(
+            json.len(),
+            m.minify_to_within(&ast, &mut written, 100).unwrap()
+        );
+        assert_eq!(json.as_bytes(), written.as_slice());
+
+        written.clear();
+
+        let error = m.minify_to_within(&ast, &mut written, 12).unwrap_err();
+
+        assert_eq!(io::ErrorKind::FileTooLarge, error.kind());
+        assert_eq!(b"{\"emoji\":", written.as_slice());
+    }
+
+    #[test]
+    fn minify_to_writer() {
+        let ast = Node::Array(vec![Node::Literal("1.0"), Node::Object(vec![])]);
+
+        let m = Minifier::default().with_normalize_numbers(true);
+        let mut json = vec![];
+
+        m.minify_to(&ast, &mut json).unwrap();
+
+        assert_eq!(b"[1,{}]", json.as_slice());
+    }
+
+    #[test]
+    fn minify_sorted_arrays() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"sets\"")),
+            Box::new(Node::Array(vec![
+                Node::Array(vec![Node::Literal("2"), Node::Literal("1")]),
+                Node::Object(vec![]),
+                Node::Array(vec![
+                    Node::Literal("1"),
+                    Node::Literal("2"),
+                    Node::Literal("1"),
+                ]),
+                Node::Literal("null"),
+            ])),
+        )]);
+
+        let m = Minifier::default().with_sorted_arrays(true);
+
+        assert_eq!("{\"sets\":[[1,2],null,{}]}", m.minify(&ast));
+        assert_eq!(
+            "{\"sets\":[[2,1],{},[1,2,1],null]}",
+            Minifier::default().minify(&ast)
+        );
+    }
+
+    #[test]
+    fn minify_deeply_nested_sorted_arrays() {
+        let depth = 100_000;
+        let mut ast = Node::Array(vec![Node::Literal("2"), Node::Literal("1")]);
+
+        for _ in 0..depth {
+            ast = Node::Array(vec![ast]);
+        }
+
+        assert_eq!(
+            format!("{}[1,2]{}", "[".repeat(depth), "]".repeat(depth)),
+            Minifier::default().with_sorted_arrays(true).minify(&ast)
+        );
+
+        // Dropping the tree recursively would overflow the stack so unwrap it one level at a time
+        while let Node::Array(mut children) = ast {
+            match children.pop() {
+                Some(child) => ast = child,
+                None => break,
+            }
+        }
+    }
+
     #[test]
     fn minify_properties() {
         let ast = Node::Property(
@@ -95,11 +484,60 @@ mod minifier_tests {
             Box::new(Node::Literal("\"in a bottle\"")),
         );
 
-        let m = Minifier;
+        let m = Minifier::default();
 
         assert_eq!("\"message\":\"in a bottle\"", m.minify(&ast));
     }
 
+    #[test]
+    fn minify_normalized_numbers() {
+        let ast = Node::Array(vec![
+            Node::Literal("1.0"),
+            Node::Literal("1E3"),
+            Node::Literal("15e-9"),
+            Node::Literal("-0.50"),
+            Node::Literal("9007199254740993"),
+            Node::Literal("\"1.0\""),
+        ]);
+
+        let m = Minifier::default().with_normalize_numbers(true);
+
+        assert_eq!(
+            "[1,1e3,15e-9,-0.5,9007199254740993,\"1.0\"]",
+            m.minify(&ast)
+        );
+    }
+
+    #[test]
+    fn minify_ascii_strings() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"naïve\"")),
+            Box::new(Node::Array(vec![
+                Node::Literal("\"😀\""),
+                Node::Literal("\"\\u00e9\""),
+                Node::Literal("1.0"),
+            ])),
+        )]);
+
+        let m = Minifier::default().with_ensure_ascii(true);
+
+        assert_eq!(
+            "{\"na\\u00efve\":[\"\\ud83d\\ude00\",\"\\u00e9\",1.0]}",
+            m.minify(&ast)
+        );
+        assert_eq!(
+            "{\"naïve\":[\"😀\",\"\\u00e9\",1.0]}",
+            Minifier::default().minify(&ast)
+        );
+    }
+
+    #[test]
+    fn keep_numbers_by_default() {
+        let ast = Node::Array(vec![Node::Literal("1.0"), Node::Literal("1E3")]);
+
+        assert_eq!("[1.0,1E3]", Minifier::default().minify(&ast));
+    }
+
     #[test]
     fn minify_arrays() {
         let ast = Node::Array(vec![
@@ -107,7 +545,7 @@ mod minifier_tests {
             Node::Literal("42"),
         ]);
 
-        let m = Minifier;
+        let m = Minifier::default();
 
         assert_eq!("[[true,false],42]", m.minify(&ast));
     }
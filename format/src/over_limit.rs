@@ -0,0 +1,39 @@
+use std::{error::Error, fmt::Display};
+
+/// Minified output longer than allowed, returned by `Minifier::minify_within` and
+/// `Minifier::minify_to_within`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OverLimit {
+    pub max_bytes: usize,
+    /// Byte length of the complete minified output
+    pub bytes: usize,
+}
+
+impl Error for OverLimit {}
+
+impl Display for OverLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Minified output of {} bytes exceeds the limit of {} bytes",
+            self.bytes, self.max_bytes
+        )
+    }
+}
+
+#[cfg(test)]
+mod over_limit_tests {
+    use super::*;
+
+    #[test]
+    fn error_message() {
+        assert_eq!(
+            "Minified output of 12 bytes exceeds the limit of 10 bytes",
+            OverLimit {
+                max_bytes: 10,
+                bytes: 12
+            }
+            .to_string()
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use std::fmt::Write;
+
+use ast::node::Node;
+use token::{token::Token, token_type::TokenType};
+
+/// Write an AST back into its source, keeping the original whitespace and comments
+///
+/// ## Description
+///
+/// The formatter and minifier each impose their own layout. `format_preserving` instead reuses
+/// the text between tokens of `source`, so a document parsed from `source` and written back
+/// unchanged is byte-identical to it, and an edit such as changing one value leaves everything
+/// else as it was.
+///
+/// `tokens` are the tokens `source` was scanned into. The layout between two tokens is the source
+/// between them, which includes any comments when scanned with `ParseOptions::allow_comments`.
+/// Nodes of `ast` are matched to the source as follows:
+///
+/// - Literals are always written as they are in the AST.
+/// - An object or array is matched with the container of the same type at the same position in
+///   the source. Elements are matched by index and properties by their key as written, so
+///   properties can be reordered without losing the layout between a key and its value.
+/// - Elements and properties beyond those in the source reuse the separator before the last one
+///   in the source, so an appended value lines up with the values before it.
+/// - Containers that cannot be matched, for example a literal replaced by an array, are written
+///   minified.
+///
+/// ## Examples
+///
+/// ```
+/// use ast::node::Node;
+/// use format::preserving::format_preserving;
+/// use token::{token::Token, token_type::TokenType};
+///
+/// let source = "[1,  2] // ids";
+/// let tokens = vec![
+///     Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+///     Token::new(TokenType::Number, 1, (1, 2), (2, 3)),
+///     Token::new(TokenType::Comma, 1, (2, 3), (3, 4)),
+///     Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+///     Token::new(TokenType::RightBracket, 1, (6, 7), (7, 8)),
+///     Token::new(TokenType::Eof, 1, (7, 7), (8, 8)),
+/// ];
+///
+/// let ast = Node::Array(vec![Node::Literal("1"), Node::Literal("5"), Node::Literal("7")]);
+///
+/// assert_eq!("[1,  5,  7] // ids", format_preserving(source, &tokens, &ast));
+/// ```
+pub fn format_preserving(source: &str, tokens: &[Token], ast: &Node) -> String {
+    let layout = Layout {
+        source,
+        tokens: tokens
+            .iter()
+            .filter(|token| !matches!(token.token_type, TokenType::Comment | TokenType::Eof))
+            .collect(),
+    };
+
+    let mut json = String::with_capacity(source.len());
+
+    if layout.tokens.is_empty() {
+        write_minified(ast, &mut json);
+        return json;
+    }
+
+    let end = layout.end(0);
+
+    json.push_str(layout.slice(0, layout.tokens[0].indices.0));
+    layout.write(ast, Some(0), &mut json);
+    json.push_str(layout.slice(layout.tokens[end - 1].indices.1, source.len()));
+
+    json
+}
+
+/// Significant tokens of the source, which the text between them is taken from
+struct Layout<'a> {
+    source: &'a str,
+    tokens: Vec<&'a Token>,
+}
+
+/// Token indices of an element, or of a property from its key to the end of its value
+#[derive(Debug, Clone, Copy)]
+struct Member {
+    start: usize,
+    end: usize,
+}
+
+impl Layout<'_> {
+    /// Write `ast`, matching it with the value starting at token `at` in the source
+    fn write(&self, ast: &Node, at: Option<usize>, json: &mut String) {
+        let open = at.and_then(|at| Some((at, &self.tokens.get(at)?.token_type)));
+
+        match (ast, open) {
+            (Node::Array(children), Some((at, TokenType::LeftBracket)))
+            | (Node::Object(children), Some((at, TokenType::LeftBrace))) => {
+                self.write_container(children, at, json)
+            }
+            (Node::Literal(literal), _) => json.push_str(literal),
+            _ => write_minified(ast, json),
+        }
+    }
+
+    fn write_container(&self, children: &[Node], open: usize, json: &mut String) {
+        let (members, close) = self.members(open);
+
+        json.push_str(self.text(open));
+
+        for (index, child) in children.iter().enumerate() {
+            self.write_separator(&members, open, index, json);
+
+            match child {
+                Node::Property(key, value) => {
+                    self.write_property(&members, index, key, value, json)
+                }
+                _ => self.write(child, members.get(index).map(|member| member.start), json),
+            }
+        }
+
+        match members.last() {
+            Some(last) if !children.is_empty() => json.push_str(self.between(last.end - 1, close)),
+            None if children.is_empty() => json.push_str(self.between(open, close)),
+            _ => {}
+        }
+
+        json.push_str(self.text(close));
+    }
+
+    fn write_property(
+        &self,
+        members: &[Member],
+        index: usize,
+        key: &Node,
+        value: &Node,
+        json: &mut String,
+    ) {
+        let written =
+            |member: &&Member| matches!(key, Node::Literal(key) if *key == self.text(member.start));
+
+        let member = members
+            .get(index)
+            .filter(written)
+            .or_else(|| members.iter().find(written));
+
+        self.write(key, None, json);
+
+        match member.or(members.first()) {
+            Some(layout) => json.push_str(self.between(layout.start, layout.start + 2)),
+            None => json.push(':'),
+        }
+
+        self.write(value, member.map(|member| member.start + 2), json);
+    }
+
+    /// Write the text before the member at `index`, including the comma before it
+    fn write_separator(&self, members: &[Member], open: usize, index: usize, json: &mut String) {
+        match (index, members.len()) {
+            (0, 0) => {}
+            (0, _) => json.push_str(self.between(open, members[0].start)),
+            (_, 0) => json.push(','),
+            (index, len) if index < len => {
+                json.push_str(self.between(members[index - 1].end - 1, members[index].start))
+            }
+            // With a single member there is no comma to copy, so the indent of the first is used
+            (_, 1) => {
+                json.push(',');
+                json.push_str(self.between(open, members[0].start));
+            }
+            (_, len) => {
+                json.push_str(self.between(members[len - 2].end - 1, members[len - 1].start))
+            }
+        }
+    }
+
+    /// Members of the container opened at token `open` and the index of its closing token
+    fn members(&self, open: usize) -> (Vec<Member>, usize) {
+        let mut members = vec![];
+        let mut start = open + 1;
+
+        while let Some(token) = self.tokens.get(start) {
+            if matches!(
+                token.token_type,
+                TokenType::RightBrace | TokenType::RightBracket
+            ) {
+                break;
+            }
+
+            let value = match self.tokens[open].token_type {
+                TokenType::LeftBrace => start + 2,
+                _ => start,
+            };
+
+            let end = self.end(value);
+            members.push(Member { start, end });
+
+            match self.tokens.get(end) {
+                Some(token) if token.token_type == TokenType::Comma => start = end + 1,
+                _ => return (members, end),
+            }
+        }
+
+        (members, start)
+    }
+
+    /// Index just after the value starting at token `start`
+    fn end(&self, start: usize) -> usize {
+        let mut depth = 0_usize;
+
+        for (index, token) in self.tokens.iter().enumerate().skip(start) {
+            match token.token_type {
+                TokenType::LeftBrace | TokenType::LeftBracket => depth += 1,
+                TokenType::RightBrace | TokenType::RightBracket => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+
+            if depth == 0 {
+                return index + 1;
+            }
+        }
+
+        self.tokens.len()
+    }
+
+    fn text(&self, index: usize) -> &str {
+        self.tokens
+            .get(index)
+            .map_or("", |token| self.slice(token.indices.0, token.indices.1))
+    }
+
+    /// Source between two tokens, such as whitespace, comments and a comma
+    fn between(&self, before: usize, after: usize) -> &str {
+        match (self.tokens.get(before), self.tokens.get(after)) {
+            (Some(before), Some(after)) => self.slice(before.indices.1, after.indices.0),
+            _ => "",
+        }
+    }
+
+    fn slice(&self, start: usize, end: usize) -> &str {
+        self.source.get(start..end).unwrap_or_default()
+    }
+}
+
+fn write_minified(ast: &Node, json: &mut String) {
+    // Writing to a String cannot fail
+    let _ = ast.write_minified(json, &|out, literal| out.write_str(literal));
+}
+
+#[cfg(test)]
+mod preserving_tests {
+    use scanner::scanner::Scanner;
+    use token::parse_options::ParseOptions;
+
+    use super::*;
+
+    fn tokens(source: &str) -> Vec<Token> {
+        let options = ParseOptions {
+            allow_comments: true,
+            preserve_comments: true,
+            ..Default::default()
+        };
+
+        Scanner::new(source)
+            .with_options(options)
+            .scan()
+            .unwrap()
+            .into_inner()
+    }
+
+    fn property<'a>(key: &'a str, value: Node<'a>) -> Node<'a> {
+        Node::Property(Box::new(Node::Literal(key)), Box::new(value))
+    }
+
+    #[test]
+    fn reproduce_unchanged_source() {
+        let source = "\n{ \"a\" :1,\n  \"b\": [ 2 ,3 ],\"c\":{ } , \"d\" :[]}\n";
+        let ast = Node::Object(vec![
+            property("\"a\"", Node::Literal("1")),
+            property(
+                "\"b\"",
+                Node::Array(vec![Node::Literal("2"), Node::Literal("3")]),
+            ),
+            property("\"c\"", Node::Object(vec![])),
+            property("\"d\"", Node::Array(vec![])),
+        ]);
+
+        assert_eq!(source, format_preserving(source, &tokens(source), &ast));
+    }
+
+    #[test]
+    fn keep_comments_around_edits() {
+        let source = "// limits\n{\n  \"max\": -1, /* none */\n  \"on\": true // default\n}\n";
+        let tokens = tokens(source);
+
+        let ast = Node::Object(vec![
+            property("\"max\"", Node::Literal("10")),
+            property("\"on\"", Node::Literal("null")),
+        ]);
+
+        assert_eq!(
+            "// limits\n{\n  \"max\": 10, /* none */\n  \"on\": null // default\n}\n",
+            format_preserving(source, &tokens, &ast)
+        );
+    }
+
+    #[test]
+    fn keep_layout_around_edits() {
+        let source = "{\n  \"a\" : [1],\n  \"b\":  2\n}";
+        let tokens = tokens(source);
+
+        let ast = Node::Object(vec![
+            property("\"b\"", Node::Literal("3")),
+            property(
+                "\"a\"",
+                Node::Array(vec![Node::Literal("1"), Node::Literal("4")]),
+            ),
+            property("\"c\"", Node::Array(vec![Node::Literal("5")])),
+        ]);
+
+        assert_eq!(
+            "{\n  \"b\":  3,\n  \"a\" : [1,4],\n  \"c\" : [5]\n}",
+            format_preserving(source, &tokens, &ast)
+        );
+
+        assert_eq!(
+            "{\n  \"a\" : [1]\n}",
+            format_preserving(
+                source,
+                &tokens,
+                &Node::Object(vec![property(
+                    "\"a\"",
+                    Node::Array(vec![Node::Literal("1")])
+                )])
+            )
+        );
+
+        assert_eq!(
+            "[]",
+            format_preserving(source, &tokens, &Node::Array(vec![]))
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use ast::node::Node;
+
+use crate::format_error::FormatError;
+
+/// Check that `ast` can be written as valid JSON
+///
+/// Objects may only contain properties, properties may only appear in objects and every key must
+/// be a string literal. Literals themselves are written as they are and not checked.
+pub(crate) fn validate(ast: &Node) -> Result<(), FormatError> {
+    validate_node(ast, "$")
+}
+
+fn validate_node(ast: &Node, path: &str) -> Result<(), FormatError> {
+    match ast {
+        Node::Object(children) => {
+            for child in children {
+                let Node::Property(key, value) = child else {
+                    return Err(FormatError::NonPropertyChild {
+                        path: path.to_string(),
+                        found: child.type_name().to_string(),
+                    });
+                };
+
+                let key = match key.as_ref() {
+                    Node::Literal(key)
+                        if key.len() >= 2 && key.starts_with('"') && key.ends_with('"') =>
+                    {
+                        &key[1..key.len() - 1]
+                    }
+                    key => {
+                        return Err(FormatError::InvalidKey {
+                            path: path.to_string(),
+                            key: key.to_string(),
+                        })
+                    }
+                };
+
+                validate_node(value, &format!("{}.{}", path, key))?;
+            }
+
+            Ok(())
+        }
+        Node::Array(children) => {
+            for (i, child) in children.iter().enumerate() {
+                validate_node(child, &format!("{}[{}]", path, i))?;
+            }
+
+            Ok(())
+        }
+        Node::Property(..) => Err(FormatError::MisplacedProperty {
+            path: path.to_string(),
+        }),
+        Node::Literal(_) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod validate_tests {
+    use super::*;
+
+    #[test]
+    fn accept_well_formed_ast() {
+        let ast = Node::Object(vec![Node::Property(
+            Box::new(Node::Literal("\"a\"")),
+            Box::new(Node::Array(vec![Node::Object(vec![]), Node::Literal("1")])),
+        )]);
+
+        assert_eq!(Ok(()), validate(&ast));
+    }
+
+    #[test]
+    fn reject_malformed_ast() {
+        let property = || {
+            Node::Property(
+                Box::new(Node::Literal("\"b\"")),
+                Box::new(Node::Literal("1")),
+            )
+        };
+
+        assert_eq!(
+            Err(FormatError::NonPropertyChild {
+                path: "$.a".to_string(),
+                found: "boolean".to_string(),
+            }),
+            validate(&Node::Object(vec![Node::Property(
+                Box::new(Node::Literal("\"a\"")),
+                Box::new(Node::Object(vec![property(), Node::Literal("true")])),
+            )]))
+        );
+        assert_eq!(
+            Err(FormatError::MisplacedProperty {
+                path: "$[1]".to_string(),
+            }),
+            validate(&Node::Array(vec![Node::Literal("1"), property()]))
+        );
+        assert_eq!(
+            Err(FormatError::MisplacedProperty {
+                path: "$".to_string(),
+            }),
+            validate(&property())
+        );
+        assert_eq!(
+            Err(FormatError::InvalidKey {
+                path: "$".to_string(),
+                key: "name".to_string(),
+            }),
+            validate(&Node::Object(vec![Node::Property(
+                Box::new(Node::Literal("name")),
+                Box::new(Node::Literal("1")),
+            )]))
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::{error::Error, fmt::Display};
+
+use parser::parser_error::ParserError;
+use scanner::scanner_error::ScannerError;
+
+/// Error from any stage of the `jtools::parse` pipeline
+#[derive(Debug, PartialEq)]
+pub enum JtoolsError {
+    Scanner(ScannerError),
+    Parser(ParserError),
+}
+
+impl JtoolsError {
+    /// Stable identifier of the underlying error used by machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Scanner(error) => error.kind(),
+            Self::Parser(error) => error.kind(),
+        }
+    }
+
+    /// Line number, display column and offending slice of the source
+    pub fn position(&self) -> (usize, usize, &str) {
+        match self {
+            Self::Scanner(error) => error.position(),
+            Self::Parser(error) => error.position(),
+        }
+    }
+}
+
+impl Error for JtoolsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Scanner(error) => Some(error),
+            Self::Parser(error) => Some(error),
+        }
+    }
+}
+
+impl Display for JtoolsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Scanner(error) => write!(f, "{}", error),
+            Self::Parser(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ScannerError> for JtoolsError {
+    fn from(error: ScannerError) -> Self {
+        Self::Scanner(error)
+    }
+}
+
+impl From<ParserError> for JtoolsError {
+    fn from(error: ParserError) -> Self {
+        Self::Parser(error)
+    }
+}
@@ -0,0 +1,112 @@
+//! Parse, format and minify JSON in a single call
+//!
+//! Each function runs the whole `Scanner -> Parser` pipeline with the default options and reports
+//! a failure from either stage as a `JtoolsError`. Use the `scanner`, `parser` and `format` crates
+//! directly for anything more configurable.
+//!
+//! ```
+//! use ast::node::Node;
+//!
+//! assert_eq!(Ok(Node::Array(vec![])), jtools::parse("[]"));
+//! assert_eq!(Ok("[1,2]".to_string()), jtools::minify("[ 1, 2 ]"));
+//! assert_eq!(Ok(3), jtools::count_array("[1, {\"a\": [2]}, null]"));
+//! assert_eq!("unknown_literal", jtools::format("nul").unwrap_err().kind());
+//! ```
+
+use ast::node::Node;
+use format::{formatter::Formatter, minifier::Minifier};
+use parser::parser::Parser;
+use scanner::scanner::Scanner;
+
+pub mod jtools_error;
+
+pub use jtools_error::JtoolsError;
+
+pub fn parse(source: &str) -> Result<Node<'_>, JtoolsError> {
+    let tokens = Scanner::new(source).scan()?;
+    let parser = Parser::new(source, tokens);
+
+    Ok(parser.parse()?)
+}
+
+pub fn format(source: &str) -> Result<String, JtoolsError> {
+    Ok(Formatter::default().format(&parse(source)?))
+}
+
+pub fn minify(source: &str) -> Result<String, JtoolsError> {
+    Ok(Minifier::default().minify(&parse(source)?))
+}
+
+/// Number of elements in a top-level array, scanning lazily and without building the AST
+pub fn count_array(source: &str) -> Result<usize, JtoolsError> {
+    let mut scanner = Scanner::new(source);
+    let mut scanner_error = None;
+
+    let tokens = scanner
+        .tokens()
+        .map_while(|token| token.map_err(|error| scanner_error = Some(error)).ok());
+
+    let count = Parser::count_top_level_array(source, tokens);
+
+    // The scanner stops at its first error, which the parser then sees as the end of input
+    if let Some(error) = scanner_error {
+        return Err(error.into());
+    }
+
+    Ok(count?)
+}
+
+#[cfg(test)]
+mod jtools_tests {
+    use parser::parser_error::ParserError;
+
+    use super::*;
+
+    #[test]
+    fn run_whole_pipeline() {
+        assert_eq!(
+            Ok(Node::Object(vec![Node::Property(
+                Box::new(Node::Literal("\"a\"")),
+                Box::new(Node::Literal("1")),
+            )])),
+            parse("{ \"a\": 1 }")
+        );
+        assert_eq!(Ok("{\n    \"a\": 1\n}".to_string()), format("{\"a\":1}"));
+        assert_eq!(Ok("{\"a\":1}".to_string()), minify("{ \"a\": 1 }"));
+    }
+
+    #[test]
+    fn count_array_elements() {
+        assert_eq!(Ok(0), count_array(" [ ] "));
+        assert_eq!(Ok(4), count_array("[1, [2, 3], {\"a\": {}}, \"]\"]"));
+
+        assert_eq!("invalid_root", count_array("{}").unwrap_err().kind());
+
+        for source in [
+            "",
+            "[1,]",
+            "[1 2]",
+            "[[1, 2]",
+            "[{\"a\": 1, \"a\": 2}]",
+            "[1] 2",
+            "[1, tru]",
+        ] {
+            assert_eq!(
+                parse(source).unwrap_err(),
+                count_array(source).unwrap_err(),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn unify_scanner_and_parser_errors() {
+        assert!(matches!(parse(""), Err(JtoolsError::Scanner(_))));
+        assert!(matches!(
+            parse("[1 2]"),
+            Err(JtoolsError::Parser(ParserError::UnexpectedToken(_)))
+        ));
+        assert_eq!((1, 4, "2"), parse("[1 2]").unwrap_err().position());
+    }
+}
@@ -1,6 +1,6 @@
 use cli::Cli;
-use std::io::Error;
+use std::{io::Error, process::ExitCode};
 
-fn main() -> Result<(), Error> {
+fn main() -> Result<ExitCode, Error> {
     Cli.run()
 }
@@ -1,3 +1,4 @@
 pub mod parser;
 pub mod parser_error;
 pub mod property_map;
+pub mod warning;
@@ -1,10 +1,89 @@
-use std::cell::Cell;
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashSet,
+};
 
-use ast::node::Node;
+use ast::{node::Node, typed_node::TypedNode};
 use error_preview::error_preview::ErrorPreview;
-use token::{token::Token, token_type::TokenType};
+use token::{
+    parse_options::{ParseOptions, RootKind},
+    token::Token,
+    token_type::TokenType,
+    tokens::Tokens,
+};
+
+pub use token::parse_options::DuplicateKeys;
+
+use crate::{
+    parser_error::{Hint, ParserError, UnexpectedToken},
+    property_map::PropertyMap,
+    warning::Warning,
+};
+
+/// Token types that can start a literal
+const LITERALS: [TokenType; 7] = [
+    TokenType::String,
+    TokenType::Number,
+    TokenType::True,
+    TokenType::False,
+    TokenType::Null,
+    TokenType::LeftBrace,
+    TokenType::LeftBracket,
+];
+
+/// Tree the parser builds, `Node` or the typed `TypedNode`
+///
+/// Literals are given the type of the token they are parsed from, so `Parser::parse_typed`
+/// follows the same grammar, options and errors as `Parser::parse`.
+trait Tree<'source>: Sized {
+    fn literal(token_type: &TokenType, literal: &'source str) -> Self;
+    fn property(key: Self, value: Self) -> Self;
+    fn object(properties: Vec<Self>) -> Self;
+    fn array(elements: Vec<Self>) -> Self;
+}
+
+impl<'source> Tree<'source> for Node<'source> {
+    fn literal(_: &TokenType, literal: &'source str) -> Self {
+        Node::Literal(literal)
+    }
+
+    fn property(key: Self, value: Self) -> Self {
+        Node::Property(Box::new(key), Box::new(value))
+    }
+
+    fn object(properties: Vec<Self>) -> Self {
+        Node::Object(properties)
+    }
+
+    fn array(elements: Vec<Self>) -> Self {
+        Node::Array(elements)
+    }
+}
+
+impl<'source> Tree<'source> for TypedNode<'source> {
+    fn literal(token_type: &TokenType, literal: &'source str) -> Self {
+        match token_type {
+            TokenType::Number => TypedNode::Number(literal.parse().unwrap_or(f64::NAN)),
+            TokenType::True => TypedNode::Bool(true),
+            TokenType::False => TypedNode::Bool(false),
+            TokenType::Null => TypedNode::Null,
+            _ => TypedNode::String(literal),
+        }
+    }
+
+    fn property(key: Self, value: Self) -> Self {
+        TypedNode::Property(Box::new(key), Box::new(value))
+    }
 
-use crate::{parser_error::ParserError, property_map::PropertyMap};
+    fn object(properties: Vec<Self>) -> Self {
+        TypedNode::Object(properties)
+    }
+
+    fn array(elements: Vec<Self>) -> Self {
+        TypedNode::Array(elements)
+    }
+}
 
 /// Recursive descent parser
 ///
@@ -56,165 +135,1011 @@ use crate::{parser_error::ParserError, property_map::PropertyMap};
 ///     p.parse()
 /// );
 /// ```
+///
+/// ## Nesting depth
+///
+/// Objects and arrays can be nested up to a maximum depth, 128 by default, so deeply nested input
+/// returns `ParserError::MaxDepthExceeded` instead of overflowing the stack. The limit can be
+/// changed with `Parser::with_max_depth`.
+///
+/// ## Duplicate keys
+///
+/// Duplicate object keys return `ParserError::DuplicateProperty` by default. With
+/// `Parser::with_duplicate_keys` they can instead overwrite the earlier property in place,
+/// `DuplicateKeys::LastWins`, or all be kept in insertion order, `DuplicateKeys::KeepAll`.
+/// Keys are compared case sensitively, as required by the JSON specification, unless
+/// `Parser::with_case_insensitive_keys` is used so that `"Name"` and `"name"` collide.
+///
+/// `DuplicateKeys::Warn` keeps the last value like `LastWins` but records a
+/// `Warning::DuplicateProperty` for each duplicate, which `Parser::parse_with_warnings` returns
+/// with the AST so tools can report them without failing.
+///
+/// ```
+/// use ast::node::Node;
+/// use parser::parser::{DuplicateKeys, Parser};
+/// use token::{token::Token, token_type::TokenType};
+///
+/// let p = Parser::new(
+///     "{\"a\":1,\"a\":2}",
+///     vec![
+///         Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+///         Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+///         Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+///         Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+///         Token::new(TokenType::Comma, 1, (6, 7), (7, 8)),
+///         Token::new(TokenType::String, 1, (7, 10), (8, 11)),
+///         Token::new(TokenType::Colon, 1, (10, 11), (11, 12)),
+///         Token::new(TokenType::Number, 1, (11, 12), (12, 13)),
+///         Token::new(TokenType::RightBrace, 1, (12, 13), (13, 14)),
+///         Token::new(TokenType::Eof, 1, (13, 13), (14, 14)),
+///     ],
+/// )
+/// .with_duplicate_keys(DuplicateKeys::Warn);
+///
+/// let (ast, warnings) = p.parse_with_warnings().unwrap();
+///
+/// assert_eq!("{\"a\":2}", ast.to_string());
+/// assert_eq!((1, 8, "\"a\""), warnings[0].position());
+/// ```
+///
+/// ## Options
+///
+/// `Parser::with_options` applies the nesting limit and duplicate key settings of a
+/// `ParseOptions` in one call, usually the same options given to `Scanner::with_options`.
+///
+/// With `allow_unquoted_keys` an `Identifier` token is accepted as a property key and stored as
+/// written, so `{name: 1}` has the key literal `name`. Quoted keys are then compared without
+/// their quotes so that `name` and `"name"` are duplicates. Without it an `Identifier` key is an
+/// unexpected token.
+///
+/// ## Comments
+///
+/// `TokenType::Comment` tokens, scanned with `ParseOptions::preserve_comments`, are dropped when
+/// the parser is created so they never appear in the AST. Pass them to
+/// `Formatter::format_with_comments` to keep them when reformatting.
+///
+/// ## Root value
+///
+/// `ParseOptions::require_root` restricts the top-level value to an object or an array. Any other
+/// value returns `ParserError::InvalidRoot` pointing at its first token, before it is parsed.
+/// Tokens with no value at all, scanned from input that is only whitespace or comments, return
+/// `ParserError::NoValue` whatever the root.
+///
+/// ## Expected tokens
+///
+/// `ParserError::UnexpectedToken` carries the `expected_set` of every token type the grammar
+/// allows at the error, for example a literal or `]` after `[`, and `,` or `]` after an element.
+/// Editors can use it to suggest completions. The message itself still only names the token the
+/// parser was looking for.
+///
+/// ```
+/// use parser::{parser::Parser, parser_error::ParserError};
+/// use token::{token::Token, token_type::TokenType};
+///
+/// let p = Parser::new(
+///     "[1:",
+///     vec![
+///         Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+///         Token::new(TokenType::Number, 1, (1, 2), (2, 3)),
+///         Token::new(TokenType::Colon, 1, (2, 3), (3, 4)),
+///         Token::new(TokenType::Eof, 1, (3, 3), (4, 4)),
+///     ],
+/// );
+///
+/// match p.parse() {
+///     Err(ParserError::UnexpectedToken(error)) => {
+///         assert_eq!(vec![TokenType::Comma, TokenType::RightBracket], error.expected_set);
+///     }
+///     result => panic!("expected unexpected token error, got {:?}", result),
+/// }
+/// ```
+///
+/// ## Error recovery
+///
+/// `Parser::parse` stops at the first error. `Parser::parse_all` instead records each error and
+/// skips ahead to the next comma or closing delimiter so that independent errors can be reported
+/// in a single pass. The AST is only returned when no errors were found.
 #[derive(Debug, PartialEq)]
 pub struct Parser<'source> {
     source: &'source str,
     current: Cell<usize>,
     tokens: Vec<Token>,
+    depth: Cell<usize>,
+    max_depth: usize,
+    duplicate_keys: DuplicateKeys,
+    case_insensitive_keys: bool,
+    allow_unquoted_keys: bool,
+    require_root: RootKind,
+    colored_errors: bool,
+    errors: RefCell<Option<Vec<ParserError>>>,
+    /// Warnings of the latest parse, cleared as each public parse method starts
+    warnings: RefCell<Vec<Warning>>,
 }
 
 impl<'source> Parser<'source> {
-    pub fn new(source: &'source str, tokens: Vec<Token>) -> Self {
+    pub fn new(source: &'source str, tokens: impl Into<Tokens>) -> Self {
+        let mut tokens = tokens.into().into_inner();
+        tokens.retain(|token| token.token_type != TokenType::Comment);
+
         Self {
             source,
             current: Cell::new(0),
             tokens,
+            depth: Cell::new(0),
+            max_depth: 128,
+            duplicate_keys: DuplicateKeys::default(),
+            case_insensitive_keys: false,
+            allow_unquoted_keys: false,
+            require_root: RootKind::Any,
+            colored_errors: false,
+            errors: RefCell::new(None),
+            warnings: RefCell::new(vec![]),
+        }
+    }
+
+    /// Apply `max_depth`, `duplicate_keys`, `case_insensitive_keys`, `allow_unquoted_keys`,
+    /// `require_root` and `colored_errors`
+    pub fn with_options(self, options: ParseOptions) -> Self {
+        Self {
+            require_root: options.require_root,
+            max_depth: options.max_depth,
+            duplicate_keys: options.duplicate_keys,
+            case_insensitive_keys: options.case_insensitive_keys,
+            allow_unquoted_keys: options.allow_unquoted_keys,
+            colored_errors: options.colored_errors,
+            ..self
+        }
+    }
+
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    pub fn with_duplicate_keys(self, duplicate_keys: DuplicateKeys) -> Self {
+        Self {
+            duplicate_keys,
+            ..self
+        }
+    }
+
+    pub fn with_case_insensitive_keys(self, case_insensitive_keys: bool) -> Self {
+        Self {
+            case_insensitive_keys,
+            ..self
         }
     }
 
-    pub fn parse(&self) -> Result<Node, ParserError> {
+    pub fn parse(&self) -> Result<Node<'source>, ParserError> {
+        self.warnings.replace(vec![]);
+        self.root()?;
+
         let ast = self.parse_literal()?;
 
-        self.next_or_error(TokenType::Eof)?;
+        self.end_of_input()?;
 
         Ok(ast)
     }
 
+    /// Parse and return the AST with the warnings found, such as duplicates with
+    /// `DuplicateKeys::Warn`
+    pub fn parse_with_warnings(&self) -> Result<(Node<'source>, Vec<Warning>), ParserError> {
+        let ast = self.parse()?;
+
+        Ok((ast, self.warnings.take()))
+    }
+
+    /// Parse a single value and return it with the index of the next unconsumed token
+    ///
+    /// Unlike `Parser::parse` the value does not have to be followed by `Eof`, so calling it
+    /// until `Parser::is_at_end` splits a stream of concatenated values such as `{}[]"x"`.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use parser::parser::Parser;
+    /// use token::{token::Token, token_type::TokenType};
+    ///
+    /// let p = Parser::new(
+    ///     "{}1",
+    ///     vec![
+    ///         Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+    ///         Token::new(TokenType::RightBrace, 1, (1, 2), (2, 3)),
+    ///         Token::new(TokenType::Number, 1, (2, 3), (3, 4)),
+    ///         Token::new(TokenType::Eof, 1, (3, 3), (4, 4)),
+    ///     ],
+    /// );
+    ///
+    /// let mut values = vec![];
+    ///
+    /// while !p.is_at_end() {
+    ///     values.push(p.parse_partial().unwrap());
+    /// }
+    ///
+    /// assert_eq!(vec![(Node::Object(vec![]), 2), (Node::Literal("1"), 3)], values);
+    /// ```
+    pub fn parse_partial(&self) -> Result<(Node<'source>, usize), ParserError> {
+        self.warnings.replace(vec![]);
+
+        let ast = self.parse_literal()?;
+
+        Ok((ast, self.current.get()))
+    }
+
+    /// Whether every token before `Eof` has been consumed
+    pub fn is_at_end(&self) -> bool {
+        matches!(
+            self.peek(),
+            None | Some(Token {
+                token_type: TokenType::Eof,
+                ..
+            })
+        )
+    }
+
+    /// Parse a stream of concatenated values, such as `{"a":1} {"b":2}`, one document at a time
+    ///
+    /// ## Description
+    ///
+    /// Values may be separated by any whitespace or by nothing at all, unlike NDJSON where each
+    /// document is on its own line. Whitespace is never tokenized, so trailing whitespace after the
+    /// last value ends the stream without an empty document. Each document must match
+    /// `require_root` and the stream stops after the first error.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use parser::parser::Parser;
+    /// use token::{token::Token, token_type::TokenType};
+    ///
+    /// let p = Parser::new(
+    ///     "{} 1\n",
+    ///     vec![
+    ///         Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+    ///         Token::new(TokenType::RightBrace, 1, (1, 2), (2, 3)),
+    ///         Token::new(TokenType::Number, 1, (3, 4), (4, 5)),
+    ///         Token::new(TokenType::Eof, 2, (5, 5), (1, 1)),
+    ///     ],
+    /// );
+    ///
+    /// let documents: Vec<_> = p.parse_stream().collect();
+    ///
+    /// assert_eq!(vec![Ok(Node::Object(vec![])), Ok(Node::Literal("1"))], documents);
+    /// ```
+    pub fn parse_stream(&self) -> impl Iterator<Item = Result<Node<'source>, ParserError>> + '_ {
+        let mut failed = false;
+
+        std::iter::from_fn(move || {
+            if failed || self.is_at_end() {
+                return None;
+            }
+
+            let document = self
+                .root()
+                .and_then(|_| self.parse_partial())
+                .map(|(ast, _)| ast);
+
+            failed = document.is_err();
+
+            Some(document)
+        })
+    }
+
+    pub fn parse_all(&self) -> Result<Node<'source>, Vec<ParserError>> {
+        match self.parse_recovering() {
+            (Some(ast), errors) if errors.is_empty() => Ok(ast),
+            (_, errors) => Err(errors),
+        }
+    }
+
+    /// Parse as much as possible of a document that may be cut off, such as a truncated read
+    ///
+    /// Objects and arrays still open at the end of the tokens are closed, properties missing a
+    /// value are dropped and the partial tree is returned with the first error, which for a cut
+    /// off document describes where it ends. Like `Parser::parse_all`, tokens are skipped after
+    /// other errors so the tree may also be missing values from the middle of the document.
+    ///
+    /// Tokens scanned before a `ScannerError`, such as a string cut off by the truncation, can be
+    /// parsed by collecting them from `Scanner::tokens` until the first error.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use parser::{parser::Parser, parser_error::ParserError};
+    /// use token::{token::Token, token_type::TokenType};
+    ///
+    /// let p = Parser::new(
+    ///     "[1, [2",
+    ///     vec![
+    ///         Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+    ///         Token::new(TokenType::Number, 1, (1, 2), (2, 3)),
+    ///         Token::new(TokenType::Comma, 1, (2, 3), (3, 4)),
+    ///         Token::new(TokenType::LeftBracket, 1, (4, 5), (5, 6)),
+    ///         Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+    ///         Token::new(TokenType::Eof, 1, (6, 6), (7, 7)),
+    ///     ],
+    /// );
+    ///
+    /// let (ast, error) = p.parse_best_effort();
+    ///
+    /// assert_eq!(
+    ///     Some(Node::Array(vec![Node::Literal("1"), Node::Array(vec![Node::Literal("2")])])),
+    ///     ast
+    /// );
+    /// assert!(matches!(error, Some(ParserError::UnexpectedToken(error)) if error.column == (7, 7)));
+    /// ```
+    pub fn parse_best_effort(&self) -> (Option<Node<'source>>, Option<ParserError>) {
+        let (ast, errors) = self.parse_recovering();
+
+        (ast, errors.into_iter().next())
+    }
+
+    /// Parse recording each error and synchronising after it, returning the AST built from the
+    /// tokens that could be parsed with every error found
+    fn parse_recovering(&self) -> (Option<Node<'source>>, Vec<ParserError>) {
+        self.errors.replace(Some(vec![]));
+        self.warnings.replace(vec![]);
+
+        if let Err(error) = self.root() {
+            self.errors.replace(None);
+            return (None, vec![error]);
+        }
+
+        let ast = self.recover(self.parse_literal(), None).ok().flatten();
+
+        if ast.is_some() {
+            let _ = self.recover(self.end_of_input(), None);
+        }
+
+        (ast, self.errors.take().unwrap_or_default())
+    }
+
+    /// Every repeated object key with the line and column of each repeat, in source order
+    ///
+    /// A lint that never fails: the tokens are walked without building an AST, so duplicates are
+    /// still found in objects that contain syntax errors. The first occurrence of a key is not
+    /// reported, only the ones after it. Keys are compared as they would be by `Parser::parse`,
+    /// including case insensitivity and unquoted keys, regardless of the duplicate key policy.
+    ///
+    /// ```
+    /// use parser::parser::Parser;
+    /// use token::{token::Token, token_type::TokenType};
+    ///
+    /// let p = Parser::new(
+    ///     "{\"a\":1,\"a\":2}",
+    ///     vec![
+    ///         Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+    ///         Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+    ///         Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+    ///         Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+    ///         Token::new(TokenType::Comma, 1, (6, 7), (7, 8)),
+    ///         Token::new(TokenType::String, 1, (7, 10), (8, 11)),
+    ///         Token::new(TokenType::Colon, 1, (10, 11), (11, 12)),
+    ///         Token::new(TokenType::Number, 1, (11, 12), (12, 13)),
+    ///         Token::new(TokenType::RightBrace, 1, (12, 13), (13, 14)),
+    ///         Token::new(TokenType::Eof, 1, (13, 13), (14, 14)),
+    ///     ],
+    /// );
+    ///
+    /// assert_eq!(vec![("\"a\"".to_string(), 1, 8)], p.find_duplicate_keys());
+    /// ```
+    pub fn find_duplicate_keys(&self) -> Vec<(String, usize, usize)> {
+        let mut duplicates = vec![];
+        // Keys seen by each enclosing object, `None` for arrays
+        let mut scopes: Vec<Option<HashSet<Cow<str>>>> = vec![];
+
+        for (index, token) in self.tokens.iter().enumerate() {
+            match token.token_type {
+                TokenType::LeftBrace => scopes.push(Some(HashSet::new())),
+                TokenType::LeftBracket => scopes.push(None),
+                TokenType::RightBrace | TokenType::RightBracket => {
+                    scopes.pop();
+                }
+                TokenType::String | TokenType::Identifier => {
+                    let is_key = matches!(
+                        self.tokens.get(index + 1),
+                        Some(Token {
+                            token_type: TokenType::Colon,
+                            ..
+                        })
+                    );
+
+                    let Some(Some(keys)) = scopes.last_mut().filter(|_| is_key) else {
+                        continue;
+                    };
+
+                    let key = self.property_key(self.snippet(token));
+
+                    let seen_key = if self.case_insensitive_keys {
+                        Cow::Owned(key.to_lowercase())
+                    } else {
+                        Cow::Borrowed(key)
+                    };
+
+                    if !keys.insert(seen_key) {
+                        duplicates.push((
+                            self.snippet(token).to_string(),
+                            token.line_number,
+                            token.column_indices.0,
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        duplicates
+    }
+
+    /// Parse into a `TypedNode` tree where literals are typed by the token they were scanned as
+    ///
+    /// ```
+    /// use ast::typed_node::TypedNode;
+    /// use parser::parser::Parser;
+    /// use token::{token::Token, token_type::TokenType};
+    ///
+    /// let p = Parser::new(
+    ///     "[1e2,\"1\"]",
+    ///     vec![
+    ///         Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+    ///         Token::new(TokenType::Number, 1, (1, 4), (2, 5)),
+    ///         Token::new(TokenType::Comma, 1, (4, 5), (5, 6)),
+    ///         Token::new(TokenType::String, 1, (5, 8), (6, 9)),
+    ///         Token::new(TokenType::RightBracket, 1, (8, 9), (9, 10)),
+    ///         Token::new(TokenType::Eof, 1, (9, 9), (10, 10)),
+    ///     ],
+    /// );
+    ///
+    /// assert_eq!(
+    ///     Ok(TypedNode::Array(vec![TypedNode::Number(100.0), TypedNode::String("\"1\"")])),
+    ///     p.parse_typed()
+    /// );
+    /// ```
+    pub fn parse_typed(&self) -> Result<TypedNode<'source>, ParserError> {
+        self.warnings.replace(vec![]);
+        self.root()?;
+
+        let ast = self.parse_literal()?;
+
+        self.end_of_input()?;
+
+        Ok(ast)
+    }
+
+    /// Parse a top-level object into the `PropertyMap` built while parsing it
+    ///
+    /// The map keeps the index used to detect duplicates, so `PropertyMap::get` finds a property in
+    /// O(1) by its unquoted key. Keys follow the same options as `Parser::parse`, so a case
+    /// insensitive parser gives a case insensitive map, and with `DuplicateKeys::LastWins` or
+    /// `DuplicateKeys::Warn` the last property with a key is found. With `DuplicateKeys::KeepAll`
+    /// the later duplicates are kept in order but not indexed.
+    ///
+    /// ```
+    /// use ast::node::Node;
+    /// use parser::parser::Parser;
+    /// use token::{token::Token, token_type::TokenType};
+    ///
+    /// let p = Parser::new(
+    ///     "{\"a\":1}",
+    ///     vec![
+    ///         Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+    ///         Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+    ///         Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+    ///         Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+    ///         Token::new(TokenType::RightBrace, 1, (6, 7), (7, 8)),
+    ///         Token::new(TokenType::Eof, 1, (7, 7), (8, 8)),
+    ///     ],
+    /// );
+    ///
+    /// let pm = p.parse_property_map().unwrap();
+    ///
+    /// assert_eq!(
+    ///     Some(&Node::Property(
+    ///         Box::new(Node::Literal("\"a\"")),
+    ///         Box::new(Node::Literal("1"))
+    ///     )),
+    ///     pm.get("a")
+    /// );
+    /// ```
+    pub fn parse_property_map(&self) -> Result<PropertyMap<'source>, ParserError> {
+        self.warnings.replace(vec![]);
+        self.root()?;
+
+        let property_map = match self.peek() {
+            Some(token) if token.token_type == TokenType::LeftBrace => {
+                self.nested(token, || self.parse_properties())?
+            }
+            token => return Err(self.unexpected_token(&[TokenType::LeftBrace], token)),
+        };
+
+        self.end_of_input()?;
+
+        Ok(property_map)
+    }
+
+    /// Check the tokens against the grammar without building an AST
+    ///
+    /// Follows the same rules as `Parser::parse`, including the max depth and duplicate key
+    /// options, and returns the same first error. No nodes are allocated, only the keys of the
+    /// object being validated are tracked to detect duplicates.
+    ///
+    /// ```
+    /// use parser::parser::Parser;
+    /// use token::{token::Token, token_type::TokenType};
+    ///
+    /// let p = Parser::new(
+    ///     "[]",
+    ///     vec![
+    ///         Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+    ///         Token::new(TokenType::RightBracket, 1, (1, 2), (2, 3)),
+    ///         Token::new(TokenType::Eof, 1, (2, 2), (3, 3)),
+    ///     ],
+    /// );
+    ///
+    /// assert_eq!(Ok(()), p.validate());
+    /// ```
+    pub fn validate(&self) -> Result<(), ParserError> {
+        self.root()?;
+
+        self.validate_literal()?;
+
+        self.end_of_input()?;
+
+        Ok(())
+    }
+
     pub fn is_valid(&self) -> bool {
-        self.parse().is_ok()
+        self.validate().is_ok()
     }
 
-    fn parse_object(&self) -> Result<Node, ParserError> {
-        let mut property_map = PropertyMap::new();
+    /// Count the elements of a top-level array without building the AST
+    ///
+    /// ## Description
+    ///
+    /// `tokens` are consumed one at a time, so they can come straight from `Scanner::tokens`
+    /// without collecting a whole document. Each element is buffered on its own and checked with
+    /// the same rules as `Parser::validate` with the default options, so only the tokens of the
+    /// largest element are held in memory and no `Node` is allocated. The top-level value must be
+    /// an array followed by `Eof` and errors are the same as `Parser::parse` would return.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use parser::parser::Parser;
+    /// use token::{token::Token, token_type::TokenType};
+    ///
+    /// let tokens = vec![
+    ///     Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+    ///     Token::new(TokenType::Number, 1, (1, 2), (2, 3)),
+    ///     Token::new(TokenType::Comma, 1, (2, 3), (3, 4)),
+    ///     Token::new(TokenType::LeftBracket, 1, (3, 4), (4, 5)),
+    ///     Token::new(TokenType::RightBracket, 1, (4, 5), (5, 6)),
+    ///     Token::new(TokenType::RightBracket, 1, (5, 6), (6, 7)),
+    ///     Token::new(TokenType::Eof, 1, (6, 6), (7, 7)),
+    /// ];
+    ///
+    /// assert_eq!(Ok(2), Parser::count_top_level_array("[1,[]]", tokens));
+    /// ```
+    pub fn count_top_level_array(
+        source: &'source str,
+        tokens: impl IntoIterator<Item = Token>,
+    ) -> Result<usize, ParserError> {
+        let mut tokens = tokens
+            .into_iter()
+            .filter(|token| token.token_type != TokenType::Comment);
+
+        let first = tokens.next();
+        let root = Self::window(source, None, first.iter().cloned().collect()).with_options(
+            ParseOptions {
+                require_root: RootKind::Array,
+                ..ParseOptions::default()
+            },
+        );
+
+        root.root()?;
+
+        let Some(mut previous) = first.filter(|token| token.token_type == TokenType::LeftBracket)
+        else {
+            return Err(root.unexpected_token(&[TokenType::LeftBracket], root.peek()));
+        };
+
+        let mut count = 0;
+        let mut next = tokens.next();
+
+        if !matches!(&next, Some(token) if token.token_type == TokenType::RightBracket) {
+            loop {
+                let element =
+                    Self::window(source, Some(&previous), Self::element(&mut tokens, next));
+                element.validate_literal()?;
+
+                if let Some(last) = element.tokens.last() {
+                    previous = last.clone();
+                }
+
+                count += 1;
+                next = tokens.next();
+
+                match next {
+                    Some(comma) if comma.token_type == TokenType::Comma => {
+                        next = tokens.next();
+                        previous = comma;
+                    }
+                    Some(ref token) if token.token_type == TokenType::RightBracket => break,
+                    token => {
+                        let after =
+                            Self::window(source, Some(&previous), token.into_iter().collect());
+
+                        return Err(
+                            after.unexpected_token(&[TokenType::RightBracket], after.peek())
+                        );
+                    }
+                }
+            }
+        }
+
+        Self::window(source, None, tokens.next().into_iter().collect()).end_of_input()?;
+
+        Ok(count)
+    }
+
+    fn parse_object<T: Tree<'source>>(&self) -> Result<T, ParserError> {
+        Ok(T::object(self.parse_properties()?.ordered_properties))
+    }
+
+    fn parse_properties<T: Tree<'source>>(&self) -> Result<PropertyMap<'source, T>, ParserError> {
+        let mut property_map = if self.case_insensitive_keys {
+            PropertyMap::new_case_insensitive()
+        } else {
+            PropertyMap::new()
+        };
 
         if matches!(self.peek(), Some(Token { token_type, .. }) if *token_type != TokenType::RightBrace)
         {
-            let (key, property, token) = self.parse_property()?;
-
-            property_map
-                .insert(key, property)
-                .ok_or_else(|| ParserError::DuplicateProperty {
-                    property: key.to_string(),
-                    error_preview: self.error_preview(token),
-                })?;
+            self.parse_object_property(&mut property_map)?;
 
             while matches!(self.peek(), Some(Token { token_type, .. }) if *token_type == TokenType::Comma)
             {
                 self.next();
-                let (key, property, token) = self.parse_property()?;
+                self.parse_object_property(&mut property_map)?;
+            }
+        }
+
+        self.recover(
+            self.next_or_error(TokenType::RightBrace),
+            Some(TokenType::RightBrace),
+        )?;
+
+        Ok(property_map)
+    }
 
-                property_map.insert(key, property).ok_or_else(|| {
-                    ParserError::DuplicateProperty {
-                        property: key.to_string(),
+    fn parse_object_property<T: Tree<'source>>(
+        &self,
+        property_map: &mut PropertyMap<'source, T>,
+    ) -> Result<(), ParserError> {
+        let Some((key, property, token)) = self.recover(self.parse_property(), None)? else {
+            return Ok(());
+        };
+
+        match self.duplicate_keys {
+            DuplicateKeys::Error => {}
+            DuplicateKeys::LastWins => {
+                property_map.replace(key, property);
+                return Ok(());
+            }
+            DuplicateKeys::KeepAll => {
+                property_map.ordered_properties.push(property);
+                return Ok(());
+            }
+            DuplicateKeys::Warn => {
+                if property_map.get(key).is_some() {
+                    self.warnings.borrow_mut().push(Warning::DuplicateProperty {
+                        property: self.snippet(token).to_string(),
                         error_preview: self.error_preview(token),
-                    }
-                })?;
+                        line: token.line_number,
+                        column: token.column_indices.0,
+                    });
+                }
+
+                property_map.replace(key, property);
+                return Ok(());
             }
         }
 
-        self.next_or_error(TokenType::RightBrace)?;
+        let inserted = property_map
+            .insert(key, property)
+            .ok_or_else(|| self.duplicate_property(token));
+
+        self.recover(inserted, None)?;
 
-        Ok(Node::Object(property_map.ordered_properties))
+        Ok(())
     }
 
-    fn parse_property(&self) -> Result<(&str, Node, &Token), ParserError> {
-        let token = self.next_or_error(TokenType::String)?;
+    fn duplicate_property(&self, token: &Token) -> ParserError {
+        ParserError::DuplicateProperty {
+            property: self.snippet(token).to_string(),
+            error_preview: self.error_preview(token),
+            line: token.line_number,
+            column: token.column_indices.0,
+        }
+    }
+
+    fn parse_property<T: Tree<'source>>(&self) -> Result<(&'source str, T, &Token), ParserError> {
+        let token = self.next_key()?;
 
         let (start, end) = token.indices;
-        let key = Node::Literal(&self.source[start..end]);
+        let key = T::literal(&TokenType::String, &self.source[start..end]);
 
         let _colon = self.next_or_error(TokenType::Colon)?;
 
         let value = self.parse_literal()?;
 
         Ok((
-            &self.source[start..end],
-            Node::Property(Box::new(key), Box::new(value)),
+            self.property_key(&self.source[start..end]),
+            T::property(key, value),
             token,
         ))
     }
 
-    fn parse_array(&self) -> Result<Node, ParserError> {
+    fn parse_array<T: Tree<'source>>(&self) -> Result<T, ParserError> {
         let mut values = vec![];
 
         if matches!(self.peek(), Some(Token { token_type, .. }) if *token_type != TokenType::RightBracket)
         {
-            values.push(self.parse_literal()?);
+            values.extend(self.recover(self.parse_literal(), None)?);
 
             while matches!(self.peek(), Some(Token { token_type, .. }) if *token_type == TokenType::Comma)
             {
                 self.next();
-                values.push(self.parse_literal()?);
+                values.extend(self.recover(self.parse_literal(), None)?);
             }
         }
 
-        self.next_or_error(TokenType::RightBracket)?;
+        self.recover(
+            self.next_or_error(TokenType::RightBracket),
+            Some(TokenType::RightBracket),
+        )?;
 
-        Ok(Node::Array(values))
+        Ok(T::array(values))
     }
 
-    fn parse_literal(&self) -> Result<Node, ParserError> {
+    fn parse_literal<T: Tree<'source>>(&self) -> Result<T, ParserError> {
         match self.peek() {
             Some(Token {
                 indices: (start, end),
                 token_type:
-                    TokenType::Null
+                    token_type @ (TokenType::Null
                     | TokenType::String
                     | TokenType::Number
                     | TokenType::True
-                    | TokenType::False,
+                    | TokenType::False),
                 ..
             }) => {
-                let node = Ok(Node::Literal(&self.source[*start..*end]));
+                let node = Ok(T::literal(token_type, &self.source[*start..*end]));
                 self.next();
-                return node;
+                node
             }
-            Some(Token {
-                token_type: TokenType::LeftBrace,
-                ..
-            }) => {
+            Some(
+                token @ Token {
+                    token_type: TokenType::LeftBrace,
+                    ..
+                },
+            ) => self.nested(token, || self.parse_object()),
+            Some(
+                token @ Token {
+                    token_type: TokenType::LeftBracket,
+                    ..
+                },
+            ) => self.nested(token, || self.parse_array()),
+            token => Err(self.unexpected_token(&LITERALS, token)),
+        }
+    }
+
+    fn validate_object(&self) -> Result<(), ParserError> {
+        let mut keys = HashSet::new();
+
+        if matches!(self.peek(), Some(Token { token_type, .. }) if *token_type != TokenType::RightBrace)
+        {
+            self.validate_object_property(&mut keys)?;
+
+            while matches!(self.peek(), Some(Token { token_type, .. }) if *token_type == TokenType::Comma)
+            {
                 self.next();
-                return self.parse_object();
+                self.validate_object_property(&mut keys)?;
             }
+        }
+
+        self.next_or_error(TokenType::RightBrace)?;
+
+        Ok(())
+    }
+
+    fn validate_object_property<'a>(
+        &'a self,
+        keys: &mut HashSet<Cow<'a, str>>,
+    ) -> Result<(), ParserError> {
+        let token = self.next_key()?;
+        let key = self.property_key(self.snippet(token));
+
+        let _colon = self.next_or_error(TokenType::Colon)?;
+
+        self.validate_literal()?;
+
+        if self.duplicate_keys != DuplicateKeys::Error {
+            return Ok(());
+        }
+
+        let seen_key = if self.case_insensitive_keys {
+            Cow::Owned(key.to_lowercase())
+        } else {
+            Cow::Borrowed(key)
+        };
+
+        if !keys.insert(seen_key) {
+            return Err(self.duplicate_property(token));
+        }
+
+        Ok(())
+    }
+
+    fn validate_array(&self) -> Result<(), ParserError> {
+        if matches!(self.peek(), Some(Token { token_type, .. }) if *token_type != TokenType::RightBracket)
+        {
+            self.validate_literal()?;
+
+            while matches!(self.peek(), Some(Token { token_type, .. }) if *token_type == TokenType::Comma)
+            {
+                self.next();
+                self.validate_literal()?;
+            }
+        }
+
+        self.next_or_error(TokenType::RightBracket)?;
+
+        Ok(())
+    }
+
+    fn validate_literal(&self) -> Result<(), ParserError> {
+        match self.peek() {
             Some(Token {
-                token_type: TokenType::LeftBracket,
+                token_type:
+                    TokenType::Null
+                    | TokenType::String
+                    | TokenType::Number
+                    | TokenType::True
+                    | TokenType::False,
                 ..
             }) => {
                 self.next();
-                return self.parse_array();
-            }
-            Some(token) => {
-                return Err(ParserError::UnexpectedToken {
-                    expected: format!(
-                        "{}",
-                        self.token_types_to_string(&[
-                            TokenType::String,
-                            TokenType::Number,
-                            TokenType::True,
-                            TokenType::False,
-                            TokenType::Null,
-                            TokenType::LeftBrace,
-                            TokenType::LeftBracket,
-                        ])
-                    ),
-                    found: token.token_type.to_string(),
-                    error_preview: self.error_preview(token),
-                })
+                Ok(())
             }
-            _ => {
-                // This will never be run
-                return Err(ParserError::UnexpectedToken {
-                    expected: "".to_string(),
-                    found: "".to_string(),
-                    error_preview: "".to_string(),
-                });
+            Some(
+                token @ Token {
+                    token_type: TokenType::LeftBrace,
+                    ..
+                },
+            ) => self.nested(token, || self.validate_object()),
+            Some(
+                token @ Token {
+                    token_type: TokenType::LeftBracket,
+                    ..
+                },
+            ) => self.nested(token, || self.validate_array()),
+            token => Err(self.unexpected_token(&LITERALS, token)),
+        }
+    }
+
+    /// Error for the token found instead of one of the expected token types
+    ///
+    /// Scanned tokens always end with `Eof` but running out of tokens, as can happen with tokens
+    /// built by hand, is also reported as finding `eof` at the last token.
+    fn unexpected_token(&self, expected_types: &[TokenType], token: Option<&Token>) -> ParserError {
+        let expected = self.token_types_to_string(expected_types);
+        let found = token.map_or(TokenType::Eof.to_string(), |token| {
+            token.token_type.to_string()
+        });
+
+        let hint = token.and_then(|token| self.hint(expected_types, token));
+        let expected_set = self.expected_set(expected_types);
+
+        let error = match token.or(self.tokens.last()) {
+            Some(token) => UnexpectedToken {
+                expected,
+                found,
+                hint,
+                expected_set,
+                error_preview: self.error_preview(token),
+                line: token.line_number,
+                column: token.column_indices,
+                snippet: self.snippet(token).to_string(),
+            },
+            None => UnexpectedToken {
+                expected,
+                found,
+                hint,
+                expected_set,
+                error_preview: "".to_string(),
+                line: 0,
+                column: (0, 0),
+                snippet: "".to_string(),
+            },
+        };
+
+        ParserError::UnexpectedToken(Box::new(error))
+    }
+
+    fn nested<T, F>(&self, token: &Token, parse: F) -> Result<T, ParserError>
+    where
+        F: FnOnce() -> Result<T, ParserError>,
+    {
+        let depth = self.depth.get() + 1;
+
+        if depth > self.max_depth {
+            return Err(ParserError::MaxDepthExceeded {
+                max_depth: self.max_depth,
+                error_preview: self.error_preview(token),
+                line: token.line_number,
+                column: token.column_indices.0,
+                snippet: self.snippet(token).to_string(),
+            });
+        }
+
+        self.next();
+        self.depth.set(depth);
+        let node = parse();
+        self.depth.set(depth - 1);
+
+        node
+    }
+
+    /// Record the error and synchronise when collecting errors, otherwise return it
+    fn recover<T>(
+        &self,
+        result: Result<T, ParserError>,
+        closing: Option<TokenType>,
+    ) -> Result<Option<T>, ParserError> {
+        let error = match result {
+            Ok(value) => return Ok(Some(value)),
+            Err(error) => error,
+        };
+
+        match self.errors.borrow_mut().as_mut() {
+            Some(errors) => errors.push(error),
+            None => return Err(error),
+        }
+
+        self.synchronise(closing);
+
+        Ok(None)
+    }
+
+    /// Skip tokens up to the next comma or closing delimiter at the current nesting level
+    ///
+    /// When a closing delimiter is given commas are skipped as well and the delimiter is consumed
+    /// if found.
+    fn synchronise(&self, closing: Option<TokenType>) {
+        let mut level = 0;
+
+        while let Some(token) = self.peek() {
+            match token.token_type {
+                TokenType::Eof => break,
+                TokenType::LeftBrace | TokenType::LeftBracket => level += 1,
+                TokenType::RightBrace | TokenType::RightBracket if level == 0 => {
+                    if closing.as_ref() == Some(&token.token_type) {
+                        self.next();
+                    }
+
+                    break;
+                }
+                TokenType::RightBrace | TokenType::RightBracket => level -= 1,
+                TokenType::Comma if level == 0 && closing.is_none() => break,
+                _ => {}
             }
+
+            self.next();
         }
     }
 
     fn token_types_to_string(&self, token_types: &[TokenType]) -> String {
         token_types
-            .into_iter()
+            .iter()
             .map(|token_type| token_type.to_string())
             .collect::<Vec<String>>()
             .join(" | ")
@@ -228,31 +1153,116 @@ impl<'source> Parser<'source> {
             }
         }
 
-        if let Some(token) = self.peek() {
-            return Err(ParserError::UnexpectedToken {
-                expected: self.token_types_to_string(&[expected_token_type]),
-                found: token.token_type.to_string(),
-                error_preview: self.error_preview(token),
+        Err(self.unexpected_token(&[expected_token_type], self.peek()))
+    }
+
+    /// Check there is a top-level value and its first token matches `require_root`
+    ///
+    /// Tokens that cannot start a value are left for `parse_literal` to report.
+    fn root(&self) -> Result<(), ParserError> {
+        if let Some(token) = self
+            .peek()
+            .filter(|token| token.token_type == TokenType::Eof)
+        {
+            return Err(ParserError::NoValue {
+                line: token.line_number,
+                column: token.column_indices.0,
             });
         }
 
-        // This will never be run
-        Err(ParserError::UnexpectedToken {
-            expected: "".to_string(),
-            found: "".to_string(),
-            error_preview: "".to_string(),
+        let expected = match self.require_root {
+            RootKind::Any => return Ok(()),
+            RootKind::Object => (TokenType::LeftBrace, "object"),
+            RootKind::Array => (TokenType::LeftBracket, "array"),
+        };
+
+        let Some(token) = self.peek() else {
+            return Ok(());
+        };
+
+        let found = match token.token_type {
+            ref token_type if *token_type == expected.0 => return Ok(()),
+            TokenType::LeftBrace => "object",
+            TokenType::LeftBracket => "array",
+            TokenType::String => "string",
+            TokenType::Number => "number",
+            TokenType::True | TokenType::False => "boolean",
+            TokenType::Null => "null",
+            _ => return Ok(()),
+        };
+
+        Err(ParserError::InvalidRoot {
+            expected: expected.1.to_string(),
+            found: found.to_string(),
+            error_preview: self.error_preview(token),
+            line: token.line_number,
+            column: token.column_indices.0,
+            snippet: self.snippet(token).to_string(),
         })
     }
 
+    /// Consume the `Eof` token, any other token is content after a complete top-level value
+    fn end_of_input(&self) -> Result<&Token, ParserError> {
+        match self.peek() {
+            Some(token) if token.token_type == TokenType::Eof => {
+                self.next();
+                Ok(token)
+            }
+            Some(token) => Err(ParserError::TrailingContent {
+                error_preview: self.error_preview(token),
+                line: token.line_number,
+                column: token.column_indices.0,
+                snippet: self.snippet(token).to_string(),
+            }),
+            None => Err(self.unexpected_token(&[TokenType::Eof], None)),
+        }
+    }
+
+    /// Consume a property key, a `String` or, when allowed, an `Identifier`
+    fn next_key(&self) -> Result<&Token, ParserError> {
+        if !self.allow_unquoted_keys {
+            return self.next_or_error(TokenType::String);
+        }
+
+        match self.peek() {
+            Some(token)
+                if matches!(token.token_type, TokenType::String | TokenType::Identifier) =>
+            {
+                self.next();
+                Ok(token)
+            }
+            token => Err(self.unexpected_token(&[TokenType::String, TokenType::Identifier], token)),
+        }
+    }
+
+    /// Key used to detect duplicates and look up properties, without its quotes
+    fn property_key<'a>(&self, key: &'a str) -> &'a str {
+        key.strip_prefix(['"', '\''])
+            .and_then(|key| key.strip_suffix(['"', '\'']))
+            .unwrap_or(key)
+    }
+
     fn error_preview(&self, token: &Token) -> String {
         let Token {
-            indices: (start, _),
+            indices: (start, end),
             column_indices: (column_start, _),
             line_number,
             ..
         } = token;
 
-        ErrorPreview.preview(self.source, *start, *column_start, *line_number)
+        ErrorPreview.preview_span(
+            self.source,
+            *start,
+            *end,
+            *column_start,
+            *line_number,
+            self.colored_errors,
+        )
+    }
+
+    fn snippet(&self, token: &Token) -> &str {
+        let (start, end) = token.indices;
+        &self.source[start..end]
     }
 
     fn next(&self) -> Option<&Token> {
@@ -265,104 +1275,834 @@ impl<'source> Parser<'source> {
         current
     }
 
-    fn peek(&self) -> Option<&Token> {
-        self.tokens.get(self.current.get())
+    /// Guess the mistake behind an unexpected `token` from the tokens around it
+    ///
+    /// Only the current token is considered, looking one token ahead or behind, so hints are
+    /// given for the most common hand-editing mistakes: missing commas and colons and trailing
+    /// commas.
+    fn hint(&self, expected: &[TokenType], token: &Token) -> Option<Hint> {
+        if !self.peek().is_some_and(|peek| std::ptr::eq(peek, token)) {
+            return None;
+        }
+
+        let previous = self
+            .current
+            .get()
+            .checked_sub(1)
+            .and_then(|previous| self.tokens.get(previous));
+        let next = self.peek_nth(1);
+
+        let hint = match &token.token_type {
+            TokenType::RightBrace | TokenType::RightBracket
+                if previous.is_some_and(|previous| previous.token_type == TokenType::Comma) =>
+            {
+                Hint::TrailingComma
+            }
+            TokenType::String | TokenType::Identifier
+                if expected.contains(&TokenType::RightBrace)
+                    && next.is_some_and(|next| next.token_type == TokenType::Colon) =>
+            {
+                Hint::MissingPropertyComma
+            }
+            token_type
+                if expected.contains(&TokenType::RightBracket) && LITERALS.contains(token_type) =>
+            {
+                Hint::MissingElementComma
+            }
+            token_type if expected == [TokenType::Colon] && LITERALS.contains(token_type) => {
+                Hint::MissingColon
+            }
+            _ => return None,
+        };
+
+        Some(hint)
+    }
+
+    /// Every token type allowed where one of `expected` was required
+    ///
+    /// Callers only check for the token they need next, which depends on where they are in the
+    /// grammar. The other tokens allowed there follow from the token before the current one, so
+    /// `]` is added after `[` and `,` after a complete element or property.
+    fn expected_set(&self, expected: &[TokenType]) -> Vec<TokenType> {
+        let previous = self
+            .current
+            .get()
+            .checked_sub(1)
+            .and_then(|previous| self.tokens.get(previous))
+            .map(|previous| &previous.token_type);
+
+        let key = match self.allow_unquoted_keys {
+            true => &[TokenType::String, TokenType::Identifier][..],
+            false => &[TokenType::String],
+        };
+
+        let mut expected_set = match (previous, expected) {
+            (Some(TokenType::LeftBracket), [TokenType::RightBracket]) => LITERALS.to_vec(),
+            (Some(TokenType::LeftBrace), [TokenType::RightBrace]) => key.to_vec(),
+            (_, [TokenType::RightBracket | TokenType::RightBrace]) => vec![TokenType::Comma],
+            _ => vec![],
+        };
+
+        expected_set.extend_from_slice(expected);
+
+        match (previous, expected) {
+            (Some(TokenType::LeftBracket), expected) if expected == LITERALS => {
+                expected_set.push(TokenType::RightBracket)
+            }
+            (Some(TokenType::LeftBrace), expected) if expected == key => {
+                expected_set.push(TokenType::RightBrace)
+            }
+            _ => {}
+        }
+
+        expected_set
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.peek_nth(0)
+    }
+
+    /// Token `n` places after the current one without consuming anything
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.current.get() + n)
+    }
+
+    /// Parser over a few tokens of a streamed top-level array, positioned after `previous` and
+    /// one level deep so errors, hints and depth limits match parsing the whole array
+    fn window(source: &'source str, previous: Option<&Token>, tokens: Vec<Token>) -> Self {
+        let parser = Self::new(
+            source,
+            previous
+                .into_iter()
+                .cloned()
+                .chain(tokens)
+                .collect::<Vec<_>>(),
+        );
+
+        parser.current.set(usize::from(previous.is_some()));
+        parser.depth.set(1);
+
+        parser
+    }
+
+    /// Tokens of the value starting with `first`, pulled from `tokens` up to its closing bracket
+    fn element(tokens: &mut impl Iterator<Item = Token>, first: Option<Token>) -> Vec<Token> {
+        let Some(first) = first else {
+            return vec![];
+        };
+
+        let mut depth = usize::from(matches!(
+            first.token_type,
+            TokenType::LeftBrace | TokenType::LeftBracket
+        ));
+        let mut element = vec![first];
+
+        while depth > 0 {
+            let Some(token) = tokens.next() else {
+                break;
+            };
+
+            match token.token_type {
+                TokenType::LeftBrace | TokenType::LeftBracket => depth += 1,
+                TokenType::RightBrace | TokenType::RightBracket => depth -= 1,
+                _ => {}
+            }
+
+            element.push(token);
+        }
+
+        element
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod parser_tests {
+    use token::token_type::TokenType;
+
+    use super::*;
+
+    #[test]
+    fn parse_valid_tokens() {
+        let p = Parser::new(
+            "{}",
+            vec![
+                Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::RightBrace, 1, (1, 2), (2, 3)),
+                Token::new(TokenType::Eof, 1, (2, 2), (3, 3)),
+            ],
+        );
+
+        assert_eq!(true, p.is_valid());
+    }
+
+    #[test]
+    fn parse_empty_object() {
+        let p = Parser::new(
+            "{}",
+            vec![
+                Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::RightBrace, 1, (1, 2), (2, 3)),
+                Token::new(TokenType::Eof, 1, (2, 2), (3, 3)),
+            ],
+        );
+
+        assert_eq!(Ok(Node::Object(vec![])), p.parse());
+    }
+
+    #[test]
+    fn parse_valid_object() {
+        let p = Parser::new(
+            "{\"animal\":\"dog\"}",
+            vec![
+                Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::String, 1, (1, 9), (2, 10)),
+                Token::new(TokenType::Colon, 1, (9, 10), (10, 11)),
+                Token::new(TokenType::String, 1, (10, 15), (11, 16)),
+                Token::new(TokenType::RightBrace, 1, (15, 16), (16, 17)),
+                Token::new(TokenType::Eof, 1, (15, 15), (16, 16)),
+            ],
+        );
+
+        assert_eq!(
+            Ok(Node::Object(vec![Node::Property(
+                Box::new(Node::Literal("\"animal\"",)),
+                Box::new(Node::Literal("\"dog\"",)),
+            ),])),
+            p.parse()
+        );
+    }
+
+    #[test]
+    fn parse_valid_property() {
+        let p = Parser::new(
+            "\"animal\":\"dog\"",
+            vec![
+                Token::new(TokenType::String, 1, (0, 8), (1, 9)),
+                Token::new(TokenType::Colon, 1, (8, 9), (9, 10)),
+                Token::new(TokenType::String, 1, (9, 14), (10, 15)),
+            ],
+        );
+
+        assert_eq!(
+            Ok((
+                "animal",
+                Node::Property(
+                    Box::new(Node::Literal("\"animal\""),),
+                    Box::new(Node::Literal("\"dog\""))
+                ),
+                &Token::new(TokenType::String, 1, (0, 8), (1, 9)),
+            )),
+            p.parse_property()
+        );
+    }
+
+    #[test]
+    fn error_invalid_array() {
+        let p = Parser::new(
+            "[,]",
+            vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::Comma, 1, (1, 2), (2, 3)),
+                Token::new(TokenType::RightBracket, 1, (2, 3), (3, 4)),
+            ],
+        );
+
+        assert_eq!(true, p.parse().is_err());
+    }
+
+    #[test]
+    fn parse_up_to_max_depth() {
+        let p = Parser::new(
+            "[[]]",
+            vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::LeftBracket, 1, (1, 2), (2, 3)),
+                Token::new(TokenType::RightBracket, 1, (2, 3), (3, 4)),
+                Token::new(TokenType::RightBracket, 1, (3, 4), (4, 5)),
+                Token::new(TokenType::Eof, 1, (4, 4), (5, 5)),
+            ],
+        )
+        .with_max_depth(2);
+
+        assert_eq!(Ok(Node::Array(vec![Node::Array(vec![])])), p.parse());
+    }
+
+    #[test]
+    fn error_when_max_depth_exceeded() {
+        let p = Parser::new(
+            "[{}]",
+            vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::LeftBrace, 1, (1, 2), (2, 3)),
+                Token::new(TokenType::RightBrace, 1, (2, 3), (3, 4)),
+                Token::new(TokenType::RightBracket, 1, (3, 4), (4, 5)),
+                Token::new(TokenType::Eof, 1, (4, 4), (5, 5)),
+            ],
+        )
+        .with_max_depth(1);
+
+        assert!(matches!(
+            p.parse(),
+            Err(ParserError::MaxDepthExceeded {
+                max_depth: 1,
+                line: 1,
+                column: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn count_top_level_array_within_max_depth() {
+        let nested = |depth: usize| {
+            let source = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+            let mut tokens: Vec<_> = source
+                .char_indices()
+                .map(|(i, char)| {
+                    let token_type = match char {
+                        '[' => TokenType::LeftBracket,
+                        _ => TokenType::RightBracket,
+                    };
+
+                    Token::new(token_type, 1, (i, i + 1), (i + 1, i + 2))
+                })
+                .collect();
+            tokens.push(Token::new(
+                TokenType::Eof,
+                1,
+                (source.len(), source.len()),
+                (source.len() + 1, source.len() + 1),
+            ));
+
+            (source, tokens)
+        };
+
+        let (source, tokens) = nested(128);
+        assert_eq!(Ok(1), Parser::count_top_level_array(&source, tokens));
+
+        let (source, tokens) = nested(129);
+        assert_eq!(
+            Parser::new(&source, tokens.clone()).parse().map(|_| 1),
+            Parser::count_top_level_array(&source, tokens)
+        );
+        assert!(matches!(
+            Parser::count_top_level_array(&source, nested(129).1),
+            Err(ParserError::MaxDepthExceeded { max_depth: 128, .. })
+        ));
+    }
+
+    #[test]
+    fn depth_is_restored_between_siblings() {
+        let p = Parser::new(
+            "[[],[]]",
+            vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::LeftBracket, 1, (1, 2), (2, 3)),
+                Token::new(TokenType::RightBracket, 1, (2, 3), (3, 4)),
+                Token::new(TokenType::Comma, 1, (3, 4), (4, 5)),
+                Token::new(TokenType::LeftBracket, 1, (4, 5), (5, 6)),
+                Token::new(TokenType::RightBracket, 1, (5, 6), (6, 7)),
+                Token::new(TokenType::RightBracket, 1, (6, 7), (7, 8)),
+                Token::new(TokenType::Eof, 1, (7, 7), (8, 8)),
+            ],
+        )
+        .with_max_depth(2);
+
+        assert!(p.parse().is_ok());
+    }
+
+    #[test]
+    fn parse_all_collects_errors() {
+        let p = Parser::new(
+            "{\"a\": , \"b\": 1, \"b\": 2}",
+            vec![
+                Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+                Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+                Token::new(TokenType::Comma, 1, (6, 7), (7, 8)),
+                Token::new(TokenType::String, 1, (8, 11), (9, 12)),
+                Token::new(TokenType::Colon, 1, (11, 12), (12, 13)),
+                Token::new(TokenType::Number, 1, (13, 14), (14, 15)),
+                Token::new(TokenType::Comma, 1, (14, 15), (15, 16)),
+                Token::new(TokenType::String, 1, (16, 19), (17, 20)),
+                Token::new(TokenType::Colon, 1, (19, 20), (20, 21)),
+                Token::new(TokenType::Number, 1, (21, 22), (22, 23)),
+                Token::new(TokenType::RightBrace, 1, (22, 23), (23, 24)),
+                Token::new(TokenType::Eof, 1, (23, 23), (24, 24)),
+            ],
+        );
+
+        let errors = p.parse_all().unwrap_err();
+
+        assert_eq!(
+            vec!["unexpected_token", "duplicate_property"],
+            errors.iter().map(|e| e.kind()).collect::<Vec<_>>()
+        );
+        assert_eq!((1, 7, ","), errors[0].position());
+        assert_eq!((1, 17, "\"b\""), errors[1].position());
+    }
+
+    #[test]
+    fn parse_all_recovers_inside_arrays() {
+        let p = Parser::new(
+            "[:,2",
+            vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::Colon, 1, (1, 2), (2, 3)),
+                Token::new(TokenType::Comma, 1, (2, 3), (3, 4)),
+                Token::new(TokenType::Number, 1, (3, 4), (4, 5)),
+                Token::new(TokenType::Eof, 1, (4, 4), (5, 5)),
+            ],
+        );
+
+        let errors = p.parse_all().unwrap_err();
+
+        assert_eq!(2, errors.len());
+        assert_eq!((1, 2, ":"), errors[0].position());
+        assert_eq!((1, 5, ""), errors[1].position());
+    }
+
+    #[test]
+    fn parse_all_valid_tokens() {
+        let p = Parser::new(
+            "[1]",
+            vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::Number, 1, (1, 2), (2, 3)),
+                Token::new(TokenType::RightBracket, 1, (2, 3), (3, 4)),
+                Token::new(TokenType::Eof, 1, (3, 3), (4, 4)),
+            ],
+        );
+
+        assert_eq!(Ok(Node::Array(vec![Node::Literal("1")])), p.parse_all());
+    }
+
+    #[test]
+    fn unexpected_token_carries_span() {
+        let p = Parser::new(
+            "[1 true]",
+            vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::Number, 1, (1, 2), (2, 3)),
+                Token::new(TokenType::True, 1, (3, 7), (4, 8)),
+                Token::new(TokenType::RightBracket, 1, (7, 8), (8, 9)),
+                Token::new(TokenType::Eof, 1, (8, 8), (9, 9)),
+            ],
+        );
+
+        assert!(matches!(
+            p.parse(),
+            Err(ParserError::UnexpectedToken(error)) if error.line == 1 && error.column == (4, 8)
+        ));
+    }
+
+    #[test]
+    fn parse_typed_literals() {
+        let p = Parser::new(
+            "{\"a\":[true,null,\"null\",-1.5]}",
+            vec![
+                Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+                Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+                Token::new(TokenType::LeftBracket, 1, (5, 6), (6, 7)),
+                Token::new(TokenType::True, 1, (6, 10), (7, 11)),
+                Token::new(TokenType::Comma, 1, (10, 11), (11, 12)),
+                Token::new(TokenType::Null, 1, (11, 15), (12, 16)),
+                Token::new(TokenType::Comma, 1, (15, 16), (16, 17)),
+                Token::new(TokenType::String, 1, (16, 22), (17, 23)),
+                Token::new(TokenType::Comma, 1, (22, 23), (23, 24)),
+                Token::new(TokenType::Number, 1, (23, 27), (24, 28)),
+                Token::new(TokenType::RightBracket, 1, (27, 28), (28, 29)),
+                Token::new(TokenType::RightBrace, 1, (28, 29), (29, 30)),
+                Token::new(TokenType::Eof, 1, (29, 29), (30, 30)),
+            ],
+        );
+
+        assert_eq!(
+            Ok(TypedNode::Object(vec![TypedNode::Property(
+                Box::new(TypedNode::String("\"a\"")),
+                Box::new(TypedNode::Array(vec![
+                    TypedNode::Bool(true),
+                    TypedNode::Null,
+                    TypedNode::String("\"null\""),
+                    TypedNode::Number(-1.5),
+                ])),
+            )])),
+            p.parse_typed()
+        );
+    }
+
+    #[test]
+    fn apply_options() {
+        let options = ParseOptions {
+            max_depth: 1,
+            duplicate_keys: DuplicateKeys::KeepAll,
+            case_insensitive_keys: true,
+            ..ParseOptions::default()
+        };
+
+        let parser = Parser::new("", vec![]).with_options(options);
+
+        assert_eq!(1, parser.max_depth);
+        assert_eq!(DuplicateKeys::KeepAll, parser.duplicate_keys);
+        assert!(parser.case_insensitive_keys);
+    }
+
+    fn unquoted_keys_parser(allow_unquoted_keys: bool) -> Parser<'static> {
+        Parser::new(
+            "{a:1,\"a\":2}",
+            vec![
+                Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::Identifier, 1, (1, 2), (2, 3)),
+                Token::new(TokenType::Colon, 1, (2, 3), (3, 4)),
+                Token::new(TokenType::Number, 1, (3, 4), (4, 5)),
+                Token::new(TokenType::Comma, 1, (4, 5), (5, 6)),
+                Token::new(TokenType::String, 1, (5, 8), (6, 9)),
+                Token::new(TokenType::Colon, 1, (8, 9), (9, 10)),
+                Token::new(TokenType::Number, 1, (9, 10), (10, 11)),
+                Token::new(TokenType::RightBrace, 1, (10, 11), (11, 12)),
+                Token::new(TokenType::Eof, 1, (11, 11), (12, 12)),
+            ],
+        )
+        .with_options(ParseOptions {
+            allow_unquoted_keys,
+            duplicate_keys: DuplicateKeys::KeepAll,
+            ..ParseOptions::default()
+        })
+    }
+
+    #[test]
+    fn parse_unquoted_keys() {
+        assert_eq!(
+            Ok(Node::Object(vec![
+                Node::Property(Box::new(Node::Literal("a")), Box::new(Node::Literal("1"))),
+                Node::Property(
+                    Box::new(Node::Literal("\"a\"")),
+                    Box::new(Node::Literal("2"))
+                ),
+            ])),
+            unquoted_keys_parser(true).parse()
+        );
+
+        let error = unquoted_keys_parser(false).parse().unwrap_err();
+
+        assert_eq!("unexpected_token", error.kind());
+        assert_eq!((1, 2, "a"), error.position());
     }
-}
 
-#[cfg(test)]
-mod parser_tests {
-    use token::token_type::TokenType;
+    #[test]
+    fn unquoted_and_quoted_keys_are_duplicates() {
+        let parser = unquoted_keys_parser(true).with_duplicate_keys(DuplicateKeys::Error);
 
-    use super::*;
+        assert!(matches!(
+            parser.validate(),
+            Err(ParserError::DuplicateProperty { property, .. }) if property == "\"a\""
+        ));
+
+        let parser = unquoted_keys_parser(true).with_duplicate_keys(DuplicateKeys::Error);
+
+        assert!(matches!(
+            parser.parse(),
+            Err(ParserError::DuplicateProperty { property, .. }) if property == "\"a\""
+        ));
+    }
 
     #[test]
-    fn parse_valid_tokens() {
-        let p = Parser::new(
-            "{}",
+    fn find_all_duplicate_keys() {
+        let source = "{\"a\":{\"b\":1,\"b\":[\"b\",{\"b\":2}]},\"a\":1,\"a\" 3}";
+
+        let parser = Parser::new(
+            source,
             vec![
                 Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
-                Token::new(TokenType::RightBrace, 1, (1, 2), (2, 3)),
-                Token::new(TokenType::Eof, 1, (2, 2), (3, 3)),
+                Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+                Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+                Token::new(TokenType::LeftBrace, 1, (5, 6), (6, 7)),
+                Token::new(TokenType::String, 1, (6, 9), (7, 10)),
+                Token::new(TokenType::Colon, 1, (9, 10), (10, 11)),
+                Token::new(TokenType::Number, 1, (10, 11), (11, 12)),
+                Token::new(TokenType::Comma, 1, (11, 12), (12, 13)),
+                Token::new(TokenType::String, 1, (12, 15), (13, 16)),
+                Token::new(TokenType::Colon, 1, (15, 16), (16, 17)),
+                Token::new(TokenType::LeftBracket, 1, (16, 17), (17, 18)),
+                Token::new(TokenType::String, 1, (17, 20), (18, 21)),
+                Token::new(TokenType::Comma, 1, (20, 21), (21, 22)),
+                Token::new(TokenType::LeftBrace, 1, (21, 22), (22, 23)),
+                Token::new(TokenType::String, 1, (22, 25), (23, 26)),
+                Token::new(TokenType::Colon, 1, (25, 26), (26, 27)),
+                Token::new(TokenType::Number, 1, (26, 27), (27, 28)),
+                Token::new(TokenType::RightBrace, 1, (27, 28), (28, 29)),
+                Token::new(TokenType::RightBracket, 1, (28, 29), (29, 30)),
+                Token::new(TokenType::RightBrace, 1, (29, 30), (30, 31)),
+                Token::new(TokenType::Comma, 1, (30, 31), (31, 32)),
+                Token::new(TokenType::String, 1, (31, 34), (32, 35)),
+                Token::new(TokenType::Colon, 1, (34, 35), (35, 36)),
+                Token::new(TokenType::Number, 1, (35, 36), (36, 37)),
+                Token::new(TokenType::Comma, 1, (36, 37), (37, 38)),
+                Token::new(TokenType::String, 1, (37, 40), (38, 41)),
+                Token::new(TokenType::Number, 1, (41, 42), (42, 43)),
+                Token::new(TokenType::RightBrace, 1, (42, 43), (43, 44)),
+                Token::new(TokenType::Eof, 1, (43, 43), (44, 44)),
             ],
         );
 
-        assert_eq!(true, p.is_valid());
+        assert!(parser.parse().is_err());
+        assert_eq!(
+            vec![("\"b\"".to_string(), 1, 13), ("\"a\"".to_string(), 1, 32)],
+            parser.find_duplicate_keys()
+        );
     }
 
-    #[test]
-    fn parse_empty_object() {
-        let p = Parser::new(
-            "{}",
+    fn duplicate_keys_parser(duplicate_keys: DuplicateKeys) -> Parser<'static> {
+        Parser::new(
+            "{\"a\":1,\"b\":2,\"a\":3}",
             vec![
                 Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
-                Token::new(TokenType::RightBrace, 1, (1, 2), (2, 3)),
-                Token::new(TokenType::Eof, 1, (2, 2), (3, 3)),
+                Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+                Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+                Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+                Token::new(TokenType::Comma, 1, (6, 7), (7, 8)),
+                Token::new(TokenType::String, 1, (7, 10), (8, 11)),
+                Token::new(TokenType::Colon, 1, (10, 11), (11, 12)),
+                Token::new(TokenType::Number, 1, (11, 12), (12, 13)),
+                Token::new(TokenType::Comma, 1, (12, 13), (13, 14)),
+                Token::new(TokenType::String, 1, (13, 16), (14, 17)),
+                Token::new(TokenType::Colon, 1, (16, 17), (17, 18)),
+                Token::new(TokenType::Number, 1, (17, 18), (18, 19)),
+                Token::new(TokenType::RightBrace, 1, (18, 19), (19, 20)),
+                Token::new(TokenType::Eof, 1, (19, 19), (20, 20)),
             ],
+        )
+        .with_duplicate_keys(duplicate_keys)
+    }
+
+    #[test]
+    fn parse_typed_with_duplicate_keys() {
+        let property = |key, value| {
+            TypedNode::Property(
+                Box::new(TypedNode::String(key)),
+                Box::new(TypedNode::Number(value)),
+            )
+        };
+
+        assert_eq!(
+            Ok(TypedNode::Object(vec![
+                property("\"a\"", 3.0),
+                property("\"b\"", 2.0)
+            ])),
+            duplicate_keys_parser(DuplicateKeys::LastWins).parse_typed()
         );
 
-        assert_eq!(Ok(Node::Object(vec![])), p.parse());
+        assert!(matches!(
+            duplicate_keys_parser(DuplicateKeys::Error).parse_typed(),
+            Err(ParserError::DuplicateProperty { .. })
+        ));
     }
 
     #[test]
-    fn parse_valid_object() {
-        let p = Parser::new(
-            "{\"animal\":\"dog\"}",
-            vec![
-                Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
-                Token::new(TokenType::String, 1, (1, 9), (2, 10)),
-                Token::new(TokenType::Colon, 1, (9, 10), (10, 11)),
-                Token::new(TokenType::String, 1, (10, 15), (11, 16)),
-                Token::new(TokenType::RightBrace, 1, (15, 16), (16, 17)),
-                Token::new(TokenType::Eof, 1, (15, 15), (16, 16)),
-            ],
-        );
+    fn error_on_duplicate_keys_by_default() {
+        assert!(matches!(
+            duplicate_keys_parser(DuplicateKeys::Error).parse(),
+            Err(ParserError::DuplicateProperty { .. })
+        ));
+    }
+
+    #[test]
+    fn warn_on_duplicate_keys() {
+        let p = duplicate_keys_parser(DuplicateKeys::Warn);
+        let (ast, warnings) = p.parse_with_warnings().unwrap();
 
         assert_eq!(
-            Ok(Node::Object(vec![Node::Property(
-                Box::new(Node::Literal("\"animal\"",)),
-                Box::new(Node::Literal("\"dog\"",)),
-            ),])),
-            p.parse()
+            duplicate_keys_parser(DuplicateKeys::LastWins).parse(),
+            Ok(ast)
         );
+        assert!(matches!(
+            warnings.as_slice(),
+            [Warning::DuplicateProperty { property, line: 1, column: 14, .. }] if property == "\"a\""
+        ));
+        assert!(duplicate_keys_parser(DuplicateKeys::Warn).is_valid());
+
+        let (_, warnings) = duplicate_keys_parser(DuplicateKeys::LastWins)
+            .parse_with_warnings()
+            .unwrap();
+
+        assert!(warnings.is_empty());
     }
 
     #[test]
-    fn parse_valid_property() {
-        let p = Parser::new(
-            "\"animal\":\"dog\"",
+    fn clear_warnings_between_parses() {
+        let p = duplicate_keys_parser(DuplicateKeys::Warn);
+
+        for _ in 0..3 {
+            p.current.set(0);
+            assert!(p.parse().is_ok());
+        }
+
+        assert_eq!(1, p.warnings.borrow().len());
+
+        p.current.set(0);
+        let (_, warnings) = p.parse_with_warnings().unwrap();
+
+        assert_eq!(1, warnings.len());
+    }
+
+    #[test]
+    fn error_on_case_insensitive_duplicate_keys() {
+        let source = "{\"name\":1,\"Name\":2}";
+        let tokens = || {
             vec![
-                Token::new(TokenType::String, 1, (0, 8), (1, 9)),
-                Token::new(TokenType::Colon, 1, (8, 9), (9, 10)),
-                Token::new(TokenType::String, 1, (9, 14), (10, 15)),
-            ],
+                Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::String, 1, (1, 7), (2, 8)),
+                Token::new(TokenType::Colon, 1, (7, 8), (8, 9)),
+                Token::new(TokenType::Number, 1, (8, 9), (9, 10)),
+                Token::new(TokenType::Comma, 1, (9, 10), (10, 11)),
+                Token::new(TokenType::String, 1, (10, 16), (11, 17)),
+                Token::new(TokenType::Colon, 1, (16, 17), (17, 18)),
+                Token::new(TokenType::Number, 1, (17, 18), (18, 19)),
+                Token::new(TokenType::RightBrace, 1, (18, 19), (19, 20)),
+                Token::new(TokenType::Eof, 1, (19, 19), (20, 20)),
+            ]
+        };
+
+        assert!(Parser::new(source, tokens()).parse().is_ok());
+        assert!(matches!(
+            Parser::new(source, tokens())
+                .with_case_insensitive_keys(true)
+                .validate(),
+            Err(ParserError::DuplicateProperty { property, .. }) if property == "\"Name\""
+        ));
+        assert!(matches!(
+            Parser::new(source, tokens())
+                .with_case_insensitive_keys(true)
+                .parse(),
+            Err(ParserError::DuplicateProperty { property, .. }) if property == "\"Name\""
+        ));
+    }
+
+    #[test]
+    fn validate_duplicate_keys() {
+        assert_eq!(
+            duplicate_keys_parser(DuplicateKeys::Error)
+                .parse()
+                .map(|_| ()),
+            duplicate_keys_parser(DuplicateKeys::Error).validate()
         );
+        assert_eq!(
+            Ok(()),
+            duplicate_keys_parser(DuplicateKeys::LastWins).validate()
+        );
+    }
+
+    #[test]
+    fn validate_with_the_same_errors_as_parse() {
+        let parser = |max_depth| {
+            Parser::new(
+                "[[1] 2]",
+                vec![
+                    Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                    Token::new(TokenType::LeftBracket, 1, (1, 2), (2, 3)),
+                    Token::new(TokenType::Number, 1, (2, 3), (3, 4)),
+                    Token::new(TokenType::RightBracket, 1, (3, 4), (4, 5)),
+                    Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+                    Token::new(TokenType::RightBracket, 1, (6, 7), (7, 8)),
+                    Token::new(TokenType::Eof, 1, (7, 7), (8, 8)),
+                ],
+            )
+            .with_max_depth(max_depth)
+        };
+
+        for max_depth in [1, 2] {
+            let error = parser(max_depth).validate().unwrap_err();
+
+            assert_eq!(parser(max_depth).parse().unwrap_err(), error);
+        }
+
+        assert!(matches!(
+            parser(1).validate(),
+            Err(ParserError::MaxDepthExceeded { .. })
+        ));
+        assert!(!parser(2).is_valid());
+    }
 
+    #[test]
+    fn keep_last_duplicate_key() {
         assert_eq!(
-            Ok((
-                "\"animal\"",
+            Ok(Node::Object(vec![
                 Node::Property(
-                    Box::new(Node::Literal("\"animal\""),),
-                    Box::new(Node::Literal("\"dog\""))
+                    Box::new(Node::Literal("\"a\"")),
+                    Box::new(Node::Literal("3"))
                 ),
-                &Token::new(TokenType::String, 1, (0, 8), (1, 9)),
-            )),
-            p.parse_property()
+                Node::Property(
+                    Box::new(Node::Literal("\"b\"")),
+                    Box::new(Node::Literal("2"))
+                ),
+            ])),
+            duplicate_keys_parser(DuplicateKeys::LastWins).parse()
         );
     }
 
     #[test]
-    fn error_invalid_array() {
-        let p = Parser::new(
-            "[,]",
-            vec![
-                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
-                Token::new(TokenType::Comma, 1, (1, 2), (2, 3)),
-                Token::new(TokenType::RightBracket, 1, (2, 3), (3, 4)),
-            ],
+    fn keep_all_duplicate_keys() {
+        assert_eq!(
+            Ok(Node::Object(vec![
+                Node::Property(
+                    Box::new(Node::Literal("\"a\"")),
+                    Box::new(Node::Literal("1"))
+                ),
+                Node::Property(
+                    Box::new(Node::Literal("\"b\"")),
+                    Box::new(Node::Literal("2"))
+                ),
+                Node::Property(
+                    Box::new(Node::Literal("\"a\"")),
+                    Box::new(Node::Literal("3"))
+                ),
+            ])),
+            duplicate_keys_parser(DuplicateKeys::KeepAll).parse()
         );
+    }
 
-        assert_eq!(true, p.parse().is_err());
+    #[test]
+    fn parse_property_map_with_duplicate_keys() {
+        let property = |key, value| {
+            Node::Property(Box::new(Node::Literal(key)), Box::new(Node::Literal(value)))
+        };
+
+        let pm = duplicate_keys_parser(DuplicateKeys::LastWins)
+            .with_case_insensitive_keys(true)
+            .parse_property_map()
+            .unwrap();
+
+        assert_eq!(Some(&property("\"a\"", "3")), pm.get("A"));
+        assert_eq!(Some(&property("\"b\"", "2")), pm.get("b"));
+        assert_eq!(None, pm.get("\"b\""));
+        assert_eq!(2, pm.ordered_properties.len());
+
+        let p = duplicate_keys_parser(DuplicateKeys::Warn);
+
+        assert_eq!(
+            Some(&property("\"a\"", "3")),
+            p.parse_property_map().unwrap().get("a")
+        );
+        assert_eq!(1, p.warnings.take().len());
+
+        assert!(matches!(
+            duplicate_keys_parser(DuplicateKeys::Error).parse_property_map(),
+            Err(ParserError::DuplicateProperty { .. })
+        ));
+        assert!(matches!(
+            Parser::new(
+                "[]",
+                vec![
+                    Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                    Token::new(TokenType::RightBracket, 1, (1, 2), (2, 3)),
+                    Token::new(TokenType::Eof, 1, (2, 2), (3, 3)),
+                ],
+            )
+            .parse_property_map(),
+            Err(ParserError::UnexpectedToken(_))
+        ));
     }
 
     #[test]
@@ -464,6 +2204,411 @@ mod parser_tests {
         assert_eq!(true, p.next_or_error(TokenType::LeftBrace).is_err());
     }
 
+    #[test]
+    fn parse_concatenated_values() {
+        let p = Parser::new(
+            "{}[]\"x\"",
+            vec![
+                Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::RightBrace, 1, (1, 2), (2, 3)),
+                Token::new(TokenType::LeftBracket, 1, (2, 3), (3, 4)),
+                Token::new(TokenType::RightBracket, 1, (3, 4), (4, 5)),
+                Token::new(TokenType::String, 1, (4, 7), (5, 8)),
+                Token::new(TokenType::Eof, 1, (7, 7), (8, 8)),
+            ],
+        );
+
+        assert_eq!(Ok((Node::Object(vec![]), 2)), p.parse_partial());
+        assert_eq!(Ok((Node::Array(vec![]), 4)), p.parse_partial());
+        assert!(!p.is_at_end());
+        assert_eq!(Ok((Node::Literal("\"x\""), 5)), p.parse_partial());
+        assert!(p.is_at_end());
+        assert!(p.parse_partial().is_err());
+    }
+
+    #[test]
+    fn parse_whitespace_separated_stream() {
+        let p = Parser::new(
+            "{\"a\":1} {\"b\":2}\n\t ",
+            vec![
+                Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+                Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+                Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+                Token::new(TokenType::RightBrace, 1, (6, 7), (7, 8)),
+                Token::new(TokenType::LeftBrace, 1, (8, 9), (9, 10)),
+                Token::new(TokenType::String, 1, (9, 12), (10, 13)),
+                Token::new(TokenType::Colon, 1, (12, 13), (13, 14)),
+                Token::new(TokenType::Number, 1, (13, 14), (14, 15)),
+                Token::new(TokenType::RightBrace, 1, (14, 15), (15, 16)),
+                Token::new(TokenType::Eof, 2, (18, 18), (3, 3)),
+            ],
+        );
+
+        let documents: Vec<_> = p.parse_stream().collect();
+
+        assert_eq!(
+            vec![
+                Ok(Node::Object(vec![Node::Property(
+                    Box::new(Node::Literal("\"a\"")),
+                    Box::new(Node::Literal("1"))
+                )])),
+                Ok(Node::Object(vec![Node::Property(
+                    Box::new(Node::Literal("\"b\"")),
+                    Box::new(Node::Literal("2"))
+                )])),
+            ],
+            documents
+        );
+
+        let p = Parser::new(" ", vec![Token::new(TokenType::Eof, 1, (1, 1), (2, 2))]);
+        assert_eq!(0, p.parse_stream().count());
+    }
+
+    #[test]
+    fn stop_stream_after_first_error() {
+        let p = Parser::new(
+            "1 ] 2",
+            vec![
+                Token::new(TokenType::Number, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::RightBracket, 1, (2, 3), (3, 4)),
+                Token::new(TokenType::Number, 1, (4, 5), (5, 6)),
+                Token::new(TokenType::Eof, 1, (5, 5), (6, 6)),
+            ],
+        );
+
+        let documents: Vec<_> = p.parse_stream().collect();
+
+        assert_eq!(2, documents.len());
+        assert_eq!(Ok(Node::Literal("1")), documents[0]);
+        assert!(documents[1].is_err());
+    }
+
+    #[test]
+    fn ignore_comment_tokens() {
+        let parser = Parser::new(
+            "[/**/1]",
+            vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::Comment, 1, (1, 5), (2, 6)),
+                Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+                Token::new(TokenType::RightBracket, 1, (6, 7), (7, 8)),
+                Token::new(TokenType::Eof, 1, (7, 7), (8, 8)),
+            ],
+        );
+
+        assert_eq!(Ok(Node::Array(vec![Node::Literal("1")])), parser.parse());
+    }
+
+    #[test]
+    fn restrict_root_value() {
+        let parser = |require_root| {
+            Parser::new(
+                "42",
+                vec![
+                    Token::new(TokenType::Number, 1, (0, 2), (1, 3)),
+                    Token::new(TokenType::Eof, 1, (2, 2), (3, 3)),
+                ],
+            )
+            .with_options(ParseOptions {
+                require_root,
+                ..ParseOptions::default()
+            })
+        };
+
+        assert_eq!(Ok(Node::Literal("42")), parser(RootKind::Any).parse());
+
+        let error = parser(RootKind::Object).parse().unwrap_err();
+
+        assert!(matches!(
+            &error,
+            ParserError::InvalidRoot { expected, found, .. } if expected == "object" && found == "number"
+        ));
+        assert_eq!((1, 1, "42"), error.position());
+        assert_eq!(Err(error), parser(RootKind::Object).validate());
+        assert_eq!(
+            "invalid_root",
+            parser(RootKind::Array).parse_all().unwrap_err()[0].kind()
+        );
+    }
+
+    #[test]
+    fn parse_truncated_document_best_effort() {
+        let tokens = vec![
+            Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+            Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+            Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+            Token::new(TokenType::True, 1, (5, 9), (6, 10)),
+            Token::new(TokenType::Comma, 1, (9, 10), (10, 11)),
+            Token::new(TokenType::String, 1, (10, 13), (11, 14)),
+            Token::new(TokenType::Colon, 1, (13, 14), (14, 15)),
+            Token::new(TokenType::Eof, 1, (14, 14), (15, 15)),
+        ];
+
+        let (ast, error) = Parser::new("{\"a\":true,\"b\":", tokens.clone()).parse_best_effort();
+
+        assert_eq!(
+            Some(Node::Object(vec![Node::Property(
+                Box::new(Node::Literal("\"a\"")),
+                Box::new(Node::Literal("true")),
+            )])),
+            ast
+        );
+        assert!(matches!(
+            error,
+            Some(ParserError::UnexpectedToken(error))
+                if error.found == "eof" && error.column == (15, 15)
+        ));
+
+        assert_eq!(
+            (Some(Node::Literal("true")), None),
+            Parser::new(
+                "true",
+                vec![
+                    Token::new(TokenType::True, 1, (0, 4), (1, 5)),
+                    Token::new(TokenType::Eof, 1, (4, 4), (5, 5)),
+                ]
+            )
+            .parse_best_effort()
+        );
+        assert!(matches!(
+            Parser::new("{", vec![Token::new(TokenType::Eof, 1, (0, 0), (1, 1))])
+                .parse_best_effort(),
+            (None, Some(ParserError::NoValue { .. }))
+        ));
+    }
+
+    #[test]
+    fn error_without_value() {
+        let parser = || Parser::new("  \n", vec![Token::new(TokenType::Eof, 1, (0, 0), (1, 1))]);
+        let error = || ParserError::NoValue { line: 1, column: 1 };
+
+        assert_eq!(Err(error()), parser().parse());
+        assert_eq!(Err(error()), parser().validate());
+        assert_eq!(Err(vec![error()]), parser().parse_all());
+    }
+
+    #[test]
+    fn error_on_trailing_content() {
+        let parser = || {
+            Parser::new(
+                "{} []",
+                vec![
+                    Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                    Token::new(TokenType::RightBrace, 1, (1, 2), (2, 3)),
+                    Token::new(TokenType::LeftBracket, 1, (3, 4), (4, 5)),
+                    Token::new(TokenType::RightBracket, 1, (4, 5), (5, 6)),
+                    Token::new(TokenType::Eof, 1, (5, 5), (6, 6)),
+                ],
+            )
+        };
+
+        let error = parser().parse().unwrap_err();
+
+        assert!(matches!(error, ParserError::TrailingContent { .. }));
+        assert_eq!((1, 4, "["), error.position());
+        assert!(error
+            .to_string()
+            .starts_with("Unexpected content after top-level value"));
+        assert_eq!(Err(error), parser().validate());
+        assert!(matches!(
+            parser().parse_all().unwrap_err().as_slice(),
+            [ParserError::TrailingContent { .. }]
+        ));
+    }
+
+    #[test]
+    fn report_found_token_type() {
+        let p = Parser::new(
+            "{\"a\":1,}",
+            vec![
+                Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+                Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+                Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+                Token::new(TokenType::Comma, 1, (6, 7), (7, 8)),
+                Token::new(TokenType::RightBrace, 1, (7, 8), (8, 9)),
+                Token::new(TokenType::Eof, 1, (8, 8), (9, 9)),
+            ],
+        );
+
+        assert!(matches!(
+            p.parse(),
+            Err(ParserError::UnexpectedToken(error))
+                if error.expected == "string" && error.found == "}"
+        ));
+
+        let p = Parser::new(
+            "[1",
+            vec![Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2))],
+        );
+
+        assert!(matches!(
+            p.parse(),
+            Err(ParserError::UnexpectedToken(error)) if error.found == "eof" && error.line == 1
+        ));
+
+        let p = Parser::new("true", vec![Token::new(TokenType::True, 1, (0, 4), (1, 5))]);
+
+        assert!(p
+            .next_or_error(TokenType::LeftBrace)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Expected { found true"));
+    }
+
+    #[test]
+    fn expected_set_at_each_position() {
+        use TokenType::*;
+
+        let expected_set = |token_types: &[TokenType], allow_unquoted_keys| {
+            let source = "x".repeat(token_types.len());
+            let mut tokens: Vec<Token> = token_types
+                .iter()
+                .enumerate()
+                .map(|(index, token_type)| {
+                    Token::new(
+                        token_type.clone(),
+                        1,
+                        (index, index + 1),
+                        (index + 1, index + 2),
+                    )
+                })
+                .collect();
+
+            let end = token_types.len();
+            tokens.push(Token::new(Eof, 1, (end, end), (end + 1, end + 1)));
+
+            let parser = Parser::new(&source, tokens).with_options(ParseOptions {
+                allow_unquoted_keys,
+                ..ParseOptions::default()
+            });
+
+            match parser.parse() {
+                Err(ParserError::UnexpectedToken(error)) => error.expected_set,
+                result => panic!("expected unexpected token error, got {:?}", result),
+            }
+        };
+
+        let value = |extra: &[TokenType]| [&LITERALS[..], extra].concat();
+
+        assert_eq!(value(&[]), expected_set(&[RightBracket], false));
+        assert_eq!(
+            value(&[RightBracket]),
+            expected_set(&[LeftBracket, RightBrace], false)
+        );
+        assert_eq!(value(&[RightBracket]), expected_set(&[LeftBracket], false));
+        assert_eq!(
+            vec![Comma, RightBracket],
+            expected_set(&[LeftBracket, Number, Colon], false)
+        );
+        assert_eq!(
+            value(&[]),
+            expected_set(&[LeftBracket, Number, Comma, RightBracket], false)
+        );
+        assert_eq!(
+            vec![String, RightBrace],
+            expected_set(&[LeftBrace, RightBracket], false)
+        );
+        assert_eq!(
+            vec![String, Identifier, RightBrace],
+            expected_set(&[LeftBrace, Number], true)
+        );
+        assert_eq!(
+            vec![Colon],
+            expected_set(&[LeftBrace, String, Number], false)
+        );
+        assert_eq!(
+            value(&[]),
+            expected_set(&[LeftBrace, String, Colon, RightBrace], false)
+        );
+        assert_eq!(
+            vec![Comma, RightBrace],
+            expected_set(&[LeftBrace, String, Colon, Null, RightBracket], false)
+        );
+        assert_eq!(
+            vec![String],
+            expected_set(&[LeftBrace, String, Colon, Null, Comma, RightBrace], false)
+        );
+    }
+
+    #[test]
+    fn hint_at_likely_mistakes() {
+        let hint = |source, tokens| match Parser::new(source, tokens).parse() {
+            Err(ParserError::UnexpectedToken(error)) => error.hint,
+            result => panic!("expected unexpected token error, got {:?}", result),
+        };
+
+        assert_eq!(
+            Some(Hint::MissingPropertyComma),
+            hint(
+                "{\"a\":1 \"b\":2}",
+                vec![
+                    Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                    Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+                    Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+                    Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+                    Token::new(TokenType::String, 1, (7, 10), (8, 11)),
+                    Token::new(TokenType::Colon, 1, (10, 11), (11, 12)),
+                    Token::new(TokenType::Number, 1, (11, 12), (12, 13)),
+                    Token::new(TokenType::RightBrace, 1, (12, 13), (13, 14)),
+                    Token::new(TokenType::Eof, 1, (13, 13), (14, 14)),
+                ],
+            )
+        );
+        assert_eq!(
+            Some(Hint::MissingElementComma),
+            hint(
+                "[1 2]",
+                vec![
+                    Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                    Token::new(TokenType::Number, 1, (1, 2), (2, 3)),
+                    Token::new(TokenType::Number, 1, (3, 4), (4, 5)),
+                    Token::new(TokenType::RightBracket, 1, (4, 5), (5, 6)),
+                    Token::new(TokenType::Eof, 1, (5, 5), (6, 6)),
+                ],
+            )
+        );
+        assert_eq!(
+            Some(Hint::MissingColon),
+            hint(
+                "{\"a\" 1}",
+                vec![
+                    Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                    Token::new(TokenType::String, 1, (1, 4), (2, 5)),
+                    Token::new(TokenType::Number, 1, (5, 6), (6, 7)),
+                    Token::new(TokenType::RightBrace, 1, (6, 7), (7, 8)),
+                    Token::new(TokenType::Eof, 1, (7, 7), (8, 8)),
+                ],
+            )
+        );
+        assert_eq!(
+            Some(Hint::TrailingComma),
+            hint(
+                "[1,]",
+                vec![
+                    Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                    Token::new(TokenType::Number, 1, (1, 2), (2, 3)),
+                    Token::new(TokenType::Comma, 1, (2, 3), (3, 4)),
+                    Token::new(TokenType::RightBracket, 1, (3, 4), (4, 5)),
+                    Token::new(TokenType::Eof, 1, (4, 4), (5, 5)),
+                ],
+            )
+        );
+        assert_eq!(
+            None,
+            hint(
+                "{:}",
+                vec![
+                    Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                    Token::new(TokenType::Colon, 1, (1, 2), (2, 3)),
+                    Token::new(TokenType::RightBrace, 1, (2, 3), (3, 4)),
+                    Token::new(TokenType::Eof, 1, (3, 3), (4, 4)),
+                ],
+            )
+        );
+    }
+
     #[test]
     fn consume_next_until_end() {
         let p = Parser::new(
@@ -522,6 +2667,24 @@ mod parser_tests {
         );
     }
 
+    #[test]
+    fn peek_nth_without_consuming() {
+        let p = Parser::new(
+            "[]",
+            vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::RightBracket, 1, (1, 2), (2, 3)),
+            ],
+        );
+
+        assert_eq!(
+            Some(&Token::new(TokenType::RightBracket, 1, (1, 2), (2, 3))),
+            p.peek_nth(1)
+        );
+        assert_eq!(None, p.peek_nth(2));
+        assert_eq!(0, p.current.get());
+    }
+
     #[test]
     fn create_new_parser() {
         let p = Parser::new("true", vec![Token::new(TokenType::True, 1, (0, 4), (1, 5))]);
@@ -531,6 +2694,15 @@ mod parser_tests {
                 source: "true",
                 current: Cell::new(0),
                 tokens: vec![Token::new(TokenType::True, 1, (0, 4), (1, 5))],
+                depth: Cell::new(0),
+                max_depth: 128,
+                duplicate_keys: DuplicateKeys::Error,
+                case_insensitive_keys: false,
+                allow_unquoted_keys: false,
+                require_root: RootKind::Any,
+                colored_errors: false,
+                errors: RefCell::new(None),
+                warnings: RefCell::new(vec![]),
             },
             p
         );
@@ -1,35 +1,190 @@
 use std::fmt::Display;
 
+use token::token_type::TokenType;
+
+/// Likely cause of an unexpected token, guessed from the tokens around it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hint {
+    MissingPropertyComma,
+    MissingElementComma,
+    MissingColon,
+    TrailingComma,
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Hint::MissingPropertyComma => {
+                write!(f, "did you forget a comma after the previous property?")
+            }
+            Hint::MissingElementComma => {
+                write!(f, "did you forget a comma after the previous element?")
+            }
+            Hint::MissingColon => write!(f, "did you forget a colon after the key?"),
+            Hint::TrailingComma => write!(f, "trailing commas are not allowed"),
+        }
+    }
+}
+
+/// Token the grammar does not allow where it was found
+///
+/// Boxed in `ParserError::UnexpectedToken` so results carrying a `ParserError` stay small.
+#[derive(Debug, PartialEq)]
+pub struct UnexpectedToken {
+    pub expected: String,
+    pub found: String,
+    /// Likely cause of a common hand-editing mistake, such as a missing comma
+    pub hint: Option<Hint>,
+    /// Every token type the grammar allows here, for example `,` or `]` after an element
+    pub expected_set: Vec<TokenType>,
+    pub error_preview: String,
+    pub line: usize,
+    pub column: (usize, usize),
+    pub snippet: String,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ParserError {
     DuplicateProperty {
         property: String,
         error_preview: String,
+        line: usize,
+        column: usize,
     },
-    UnexpectedToken {
+    UnexpectedToken(Box<UnexpectedToken>),
+    MaxDepthExceeded {
+        max_depth: usize,
+        error_preview: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    InvalidRoot {
         expected: String,
         found: String,
         error_preview: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    TrailingContent {
+        error_preview: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    /// Tokens without a value, from input that is only whitespace or comments
+    NoValue {
+        line: usize,
+        column: usize,
     },
 }
 
 impl std::error::Error for ParserError {}
 
+impl ParserError {
+    /// Stable identifier for each error used by machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ParserError::DuplicateProperty { .. } => "duplicate_property",
+            ParserError::UnexpectedToken(_) => "unexpected_token",
+            ParserError::MaxDepthExceeded { .. } => "max_depth_exceeded",
+            ParserError::InvalidRoot { .. } => "invalid_root",
+            ParserError::TrailingContent { .. } => "trailing_content",
+            ParserError::NoValue { .. } => "no_value",
+        }
+    }
+
+    /// Line number, display column and offending slice of the source
+    pub fn position(&self) -> (usize, usize, &str) {
+        match self {
+            ParserError::DuplicateProperty {
+                property,
+                line,
+                column,
+                ..
+            } => (*line, *column, property),
+            ParserError::UnexpectedToken(error) => (error.line, error.column.0, &error.snippet),
+            ParserError::MaxDepthExceeded {
+                snippet,
+                line,
+                column,
+                ..
+            }
+            | ParserError::InvalidRoot {
+                snippet,
+                line,
+                column,
+                ..
+            }
+            | ParserError::TrailingContent {
+                snippet,
+                line,
+                column,
+                ..
+            } => (*line, *column, snippet),
+            ParserError::NoValue { line, column } => (*line, *column, ""),
+        }
+    }
+}
+
 impl Display for ParserError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ParserError::DuplicateProperty {
                 property,
                 error_preview,
+                ..
             } => {
                 write!(f, "Duplicate property {} {}", property, error_preview)
             }
-            ParserError::UnexpectedToken {
+            ParserError::UnexpectedToken(error) => match &error.hint {
+                None => write!(
+                    f,
+                    "Expected {} found {} {}",
+                    error.expected, error.found, error.error_preview
+                ),
+                Some(hint) => write!(
+                    f,
+                    "Expected {} found {}, {} {}",
+                    error.expected, error.found, hint, error.error_preview
+                ),
+            },
+            ParserError::MaxDepthExceeded {
+                max_depth,
+                error_preview,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Maximum depth of {} exceeded {}",
+                    max_depth, error_preview
+                )
+            }
+            ParserError::InvalidRoot {
                 expected,
                 found,
                 error_preview,
+                ..
             } => {
-                write!(f, "Expected {} found {} {}", expected, found, error_preview)
+                write!(
+                    f,
+                    "Expected top-level value to be an {} found {} {}",
+                    expected, found, error_preview
+                )
+            }
+            ParserError::TrailingContent { error_preview, .. } => {
+                write!(
+                    f,
+                    "Unexpected content after top-level value {}",
+                    error_preview
+                )
+            }
+            ParserError::NoValue { .. } => {
+                write!(
+                    f,
+                    "No JSON value found, the input is only whitespace or comments"
+                )
             }
         }
     }
@@ -37,7 +192,9 @@ impl Display for ParserError {
 
 #[cfg(test)]
 mod parser_error_tests {
-    use crate::parser_error::ParserError;
+    use token::token_type::TokenType;
+
+    use crate::parser_error::{Hint, ParserError, UnexpectedToken};
 
     #[test]
     fn duplicate_property_message() {
@@ -45,21 +202,116 @@ mod parser_error_tests {
             "Duplicate property \"hello\" error preview",
             ParserError::DuplicateProperty {
                 property: "\"hello\"".to_string(),
-                error_preview: "error preview".to_string()
+                error_preview: "error preview".to_string(),
+                line: 1,
+                column: 1,
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn expose_kind_and_position() {
+        let error = ParserError::UnexpectedToken(Box::new(UnexpectedToken {
+            expected: "string".to_string(),
+            found: ",".to_string(),
+            hint: None,
+            expected_set: vec![TokenType::String, TokenType::RightBrace],
+            error_preview: "error preview".to_string(),
+            line: 3,
+            column: (7, 8),
+            snippet: ",".to_string(),
+        }));
+
+        assert_eq!("unexpected_token", error.kind());
+        assert_eq!((3, 7, ","), error.position());
+    }
+
+    #[test]
+    fn invalid_root_message() {
+        assert_eq!(
+            "Expected top-level value to be an object found number error preview",
+            ParserError::InvalidRoot {
+                expected: "object".to_string(),
+                found: "number".to_string(),
+                error_preview: "error preview".to_string(),
+                line: 1,
+                column: 1,
+                snippet: "42".to_string(),
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn trailing_content_message() {
+        assert_eq!(
+            "Unexpected content after top-level value error preview",
+            ParserError::TrailingContent {
+                error_preview: "error preview".to_string(),
+                line: 1,
+                column: 4,
+                snippet: "[".to_string(),
             }
             .to_string()
         );
     }
 
+    #[test]
+    fn max_depth_exceeded_message() {
+        assert_eq!(
+            "Maximum depth of 128 exceeded error preview",
+            ParserError::MaxDepthExceeded {
+                max_depth: 128,
+                error_preview: "error preview".to_string(),
+                line: 1,
+                column: 129,
+                snippet: "[".to_string(),
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn no_value_message() {
+        let error = ParserError::NoValue { line: 3, column: 1 };
+
+        assert_eq!("no_value", error.kind());
+        assert_eq!((3, 1, ""), error.position());
+        assert_eq!(
+            "No JSON value found, the input is only whitespace or comments",
+            error.to_string()
+        );
+    }
+
     #[test]
     fn unexpected_token_message() {
         assert_eq!(
             "Expected string found , error preview",
-            ParserError::UnexpectedToken {
+            ParserError::UnexpectedToken(Box::new(UnexpectedToken {
                 expected: "string".to_string(),
                 found: ",".to_string(),
-                error_preview: "error preview".to_string()
-            }
+                hint: None,
+                expected_set: vec![TokenType::String],
+                error_preview: "error preview".to_string(),
+                line: 1,
+                column: (1, 2),
+                snippet: ",".to_string(),
+            }))
+            .to_string()
+        );
+        assert_eq!(
+            "Expected : found number, did you forget a colon after the key? error preview",
+            ParserError::UnexpectedToken(Box::new(UnexpectedToken {
+                expected: ":".to_string(),
+                found: "number".to_string(),
+                hint: Some(Hint::MissingColon),
+                expected_set: vec![TokenType::Colon],
+                error_preview: "error preview".to_string(),
+                line: 1,
+                column: (5, 6),
+                snippet: "1".to_string(),
+            }))
             .to_string()
         );
     }
@@ -1,4 +1,7 @@
-use std::collections::{hash_map::Entry, HashMap};
+use std::{
+    borrow::Cow,
+    collections::{hash_map::Entry, HashMap},
+};
 
 use ast::node::Node;
 
@@ -20,6 +23,22 @@ use ast::node::Node;
 /// used. If a properties key does not exist in the `HashMap` then it is added to the vector,
 /// otherwise it returns an error.
 ///
+/// Properties are `Node`s unless another type is given, such as the `TypedNode` properties stored
+/// by `Parser::parse_typed`.
+///
+/// ## Case insensitive keys
+///
+/// Some systems treat `"Name"` and `"name"` as the same key. A map created with
+/// `PropertyMap::new_case_insensitive` lowercases each key before the lookup so such collisions
+/// are reported as duplicates, while the ordered vector keeps the original properties.
+///
+/// ## Lookup
+///
+/// `get` uses the same index to find a property in O(1) instead of scanning the properties. Keys
+/// are given without their quotes, as `Node::get` expects. `Parser::parse` hands over only the
+/// ordered vector in `Node::Object`, so for repeated lookups on a parsed object use
+/// `Parser::parse_property_map`, which returns the map built while parsing.
+///
 /// ## Examples
 ///
 /// ```
@@ -40,21 +59,36 @@ use ast::node::Node;
 ///     Node::Property(Box::new(Node::Literal("one")), Box::new(Node::Literal("1"))),
 /// );
 /// ```
-pub struct PropertyMap<'source> {
-    pub ordered_properties: Vec<Node<'source>>,
-    map: HashMap<&'source str, usize>,
+pub struct PropertyMap<'source, T = Node<'source>> {
+    pub ordered_properties: Vec<T>,
+    map: HashMap<Cow<'source, str>, usize>,
+    case_insensitive: bool,
 }
 
-impl<'source> PropertyMap<'source> {
+impl<'source, T> Default for PropertyMap<'source, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'source, T> PropertyMap<'source, T> {
     pub fn new() -> Self {
         Self {
             ordered_properties: vec![],
             map: HashMap::new(),
+            case_insensitive: false,
+        }
+    }
+
+    pub fn new_case_insensitive() -> Self {
+        Self {
+            case_insensitive: true,
+            ..Self::new()
         }
     }
 
-    pub fn insert(&mut self, key: &'source str, ast: Node<'source>) -> Option<usize> {
-        match self.map.entry(key) {
+    pub fn insert(&mut self, key: &'source str, ast: T) -> Option<usize> {
+        match self.map.entry(self.key(key)) {
             Entry::Occupied(_) => None,
             Entry::Vacant(vacant_entry) => {
                 let property_position = self.ordered_properties.len();
@@ -63,6 +97,41 @@ impl<'source> PropertyMap<'source> {
             }
         }
     }
+
+    /// Insert a property, or overwrite an existing property with the same key in place
+    pub fn replace(&mut self, key: &'source str, ast: T) -> usize {
+        match self.map.entry(self.key(key)) {
+            Entry::Occupied(occupied_entry) => {
+                let property_position = *occupied_entry.get();
+                self.ordered_properties[property_position] = ast;
+                property_position
+            }
+            Entry::Vacant(vacant_entry) => {
+                let property_position = self.ordered_properties.len();
+                self.ordered_properties.push(ast);
+                *vacant_entry.insert(property_position)
+            }
+        }
+    }
+
+    /// Property stored under `key`, given in the same form as to `insert`
+    pub fn get(&self, key: &str) -> Option<&T> {
+        let index = if self.case_insensitive {
+            self.map.get(key.to_lowercase().as_str())
+        } else {
+            self.map.get(key)
+        };
+
+        index.and_then(|&index| self.ordered_properties.get(index))
+    }
+
+    fn key(&self, key: &'source str) -> Cow<'source, str> {
+        if self.case_insensitive {
+            Cow::Owned(key.to_lowercase())
+        } else {
+            Cow::Borrowed(key)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -90,6 +159,88 @@ mod property_map_tests {
         );
     }
 
+    #[test]
+    fn error_given_case_insensitive_duplicates() {
+        let mut pm = PropertyMap::new_case_insensitive();
+
+        assert_eq!(
+            Some(0),
+            pm.insert(
+                "name",
+                Node::Property(
+                    Box::new(Node::Literal("name")),
+                    Box::new(Node::Literal("1"))
+                ),
+            )
+        );
+
+        assert_eq!(
+            None,
+            pm.insert(
+                "Name",
+                Node::Property(
+                    Box::new(Node::Literal("Name")),
+                    Box::new(Node::Literal("2"))
+                ),
+            )
+        );
+
+        assert_eq!(
+            Some(0),
+            PropertyMap::new().insert(
+                "Name",
+                Node::Property(
+                    Box::new(Node::Literal("Name")),
+                    Box::new(Node::Literal("2"))
+                ),
+            )
+        );
+    }
+
+    #[test]
+    fn replace_duplicates_in_place() {
+        let mut pm = PropertyMap::new();
+
+        pm.replace(
+            "one",
+            Node::Property(Box::new(Node::Literal("one")), Box::new(Node::Literal("1"))),
+        );
+        pm.replace(
+            "two",
+            Node::Property(Box::new(Node::Literal("two")), Box::new(Node::Literal("2"))),
+        );
+
+        assert_eq!(
+            0,
+            pm.replace(
+                "one",
+                Node::Property(Box::new(Node::Literal("one")), Box::new(Node::Literal("3"))),
+            )
+        );
+        assert_eq!(
+            vec![
+                Node::Property(Box::new(Node::Literal("one")), Box::new(Node::Literal("3"))),
+                Node::Property(Box::new(Node::Literal("two")), Box::new(Node::Literal("2"))),
+            ],
+            pm.ordered_properties
+        );
+    }
+
+    #[test]
+    fn get_properties_by_key() {
+        let property = |key, value| {
+            Node::Property(Box::new(Node::Literal(key)), Box::new(Node::Literal(value)))
+        };
+
+        let mut pm = PropertyMap::new_case_insensitive();
+        pm.insert("Name", property("Name", "1"));
+        pm.insert("age", property("age", "2"));
+
+        assert_eq!(Some(&property("Name", "1")), pm.get("name"));
+        assert_eq!(Some(&property("age", "2")), pm.get("AGE"));
+        assert_eq!(None, pm.get("id"));
+    }
+
     #[test]
     fn maintain_insertion_order() {
         let mut pm = PropertyMap::new();
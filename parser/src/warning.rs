@@ -0,0 +1,72 @@
+use std::fmt::Display;
+
+/// Problem found by `Parser::parse_with_warnings` that did not stop parsing
+#[derive(Debug, PartialEq)]
+pub enum Warning {
+    /// Key already used in the same object with `DuplicateKeys::Warn`, the last value is kept
+    DuplicateProperty {
+        property: String,
+        error_preview: String,
+        line: usize,
+        column: usize,
+    },
+}
+
+impl Warning {
+    /// Stable identifier for each warning used by machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Warning::DuplicateProperty { .. } => "duplicate_property",
+        }
+    }
+
+    /// Line number, display column and offending slice of the source
+    pub fn position(&self) -> (usize, usize, &str) {
+        match self {
+            Warning::DuplicateProperty {
+                property,
+                line,
+                column,
+                ..
+            } => (*line, *column, property),
+        }
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::DuplicateProperty {
+                property,
+                error_preview,
+                ..
+            } => write!(
+                f,
+                "Duplicate property {}, keeping the last value {}",
+                property, error_preview
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod warning_tests {
+    use super::*;
+
+    #[test]
+    fn expose_kind_and_position() {
+        let warning = Warning::DuplicateProperty {
+            property: "\"a\"".to_string(),
+            error_preview: "preview".to_string(),
+            line: 3,
+            column: 5,
+        };
+
+        assert_eq!("duplicate_property", warning.kind());
+        assert_eq!((3, 5, "\"a\""), warning.position());
+        assert_eq!(
+            "Duplicate property \"a\", keeping the last value preview",
+            warning.to_string()
+        );
+    }
+}
@@ -1,2 +1,6 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod scanner;
 pub mod scanner_error;
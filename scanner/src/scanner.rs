@@ -1,7 +1,15 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::f64;
+use core::{iter::Peekable, ops::Range, str::CharIndices};
 use error_preview::error_preview::ErrorPreview;
-use std::{iter::Peekable, str::CharIndices};
-use token::{token::Token, token_type::TokenType};
+use token::{
+    number::round_trips, parse_options::ParseOptions, token::Token, token_type::TokenType,
+    tokens::Tokens,
+};
 
 use crate::scanner_error::ScannerError;
 
@@ -10,18 +18,19 @@ use crate::scanner_error::ScannerError;
 /// ## Description
 ///
 /// The scanner iterates over every character in a given source, O(n), and groups those characters
-/// into tokens which are fed to the parser if scanning is successful.
+/// into tokens which are fed to the parser if scanning is successful. Tokens are returned as
+/// `Tokens`, always sorted by their indices and ending with `Eof`.
 ///
 /// ## Examples
 /// ```
 /// use scanner::scanner::Scanner;
-/// use token::{token_type::TokenType, token::Token};
+/// use token::{token::Token, token_type::TokenType, tokens::Tokens};
 ///
 /// let mut scanner = Scanner::new("{ \"data\": [1] }");
 /// let tokens = scanner.scan();
 ///
 /// assert_eq!(
-///     Ok(vec![
+///     Ok(Tokens::from(vec![
 ///         Token {
 ///             token_type: TokenType::LeftBrace,
 ///             line_number: 1,
@@ -70,98 +79,649 @@ use crate::scanner_error::ScannerError;
 ///             indices: (15, 15,),
 ///             column_indices: (16, 16,),
 ///         },
-///     ],),
+///     ])),
 ///     tokens
 /// )
 /// ```
+///
+/// ## JSONC
+///
+/// A scanner created with `Scanner::new_jsonc` skips `//` line comments and `/* */` block
+/// comments in the same way whitespace is skipped so no tokens are emitted for them.
+///
+/// ```
+/// use scanner::scanner::Scanner;
+///
+/// let source = "// settings\n{ /* empty */ }";
+///
+/// assert!(Scanner::new(source).scan().is_err());
+/// assert!(Scanner::new_jsonc(source).scan().is_ok());
+/// ```
+///
+/// With `ParseOptions::preserve_comments` each comment is instead emitted as a
+/// `TokenType::Comment` token spanning the whole comment, so a formatter can write it back. The
+/// line and start column are where the comment starts. The parser ignores comment tokens.
+///
+/// ```
+/// use scanner::scanner::Scanner;
+/// use token::{parse_options::ParseOptions, token_type::TokenType};
+///
+/// let options = ParseOptions {
+///     allow_comments: true,
+///     preserve_comments: true,
+///     ..ParseOptions::default()
+/// };
+///
+/// let tokens = Scanner::new("// settings\n{}").with_options(options).scan().unwrap();
+///
+/// assert_eq!(TokenType::Comment, tokens[0].token_type);
+/// assert_eq!((0, 11), tokens[0].indices);
+/// ```
+///
+/// ## Single quotes
+///
+/// A scanner created with `Scanner::new_lenient` also accepts strings wrapped in single quotes,
+/// as emitted by some Python tools, with the same escape sequences plus `\'`. The token still
+/// spans the quotes so the literal is `'name'` rather than `"name"`.
+///
+/// ```
+/// use scanner::scanner::Scanner;
+///
+/// let source = "{'name': 'x'}";
+///
+/// assert!(Scanner::new(source).scan().is_err());
+/// assert!(Scanner::new_lenient(source).scan().is_ok());
+/// ```
+///
+/// ## Non-finite numbers
+///
+/// `Scanner::new_lenient`, or `ParseOptions::allow_non_finite_numbers`, also accepts `NaN`,
+/// `Infinity` and `-Infinity`, as written by Python's `json` module, and numbers such as `1e400`
+/// that overflow an `f64`. They are scanned as `TokenType::Number` tokens and kept as written.
+///
+/// ```
+/// use scanner::scanner::Scanner;
+///
+/// let source = "[NaN, -Infinity, 1e400]";
+///
+/// assert!(Scanner::new(source).scan().is_err());
+/// assert!(Scanner::new_lenient(source).scan().is_ok());
+/// ```
+///
+/// ## Precision loss
+///
+/// Number tokens keep the literal as written, but consumers reading it as an `f64` lose digits an
+/// `f64` cannot hold, such as IDs above 2^53. With `ParseOptions::reject_precision_loss` a finite
+/// number that does not keep its exact value when read as an `f64` and written back returns
+/// `ScannerError::PrecisionLoss`. Spellings such as `1.0e2` or `0.1` that read back to the same
+/// value are accepted.
+///
+/// ```
+/// use scanner::{scanner::Scanner, scanner_error::ScannerError};
+/// use token::parse_options::ParseOptions;
+///
+/// let options = ParseOptions {
+///     reject_precision_loss: true,
+///     ..ParseOptions::default()
+/// };
+///
+/// assert!(Scanner::new("[0.1, 1.0e2]").with_options(options).scan().is_ok());
+/// assert!(matches!(
+///     Scanner::new("{\"id\": 9007199254740993}").with_options(options).scan(),
+///     Err(ScannerError::PrecisionLoss { column: 8, .. })
+/// ));
+/// ```
+///
+/// ## Unquoted keys
+///
+/// With `ParseOptions::allow_unquoted_keys` a bare word made of ASCII letters, digits, `_` and
+/// `$`, not starting with a digit, is scanned as a `TokenType::Identifier` instead of returning
+/// `ScannerError::UnknownLiteral`. `true`, `false` and `null` are still keywords.
+///
+/// ```
+/// use scanner::scanner::Scanner;
+/// use token::{parse_options::ParseOptions, token_type::TokenType};
+///
+/// let options = ParseOptions {
+///     allow_unquoted_keys: true,
+///     ..ParseOptions::default()
+/// };
+///
+/// let tokens = Scanner::new("{$name_1: null}").with_options(options).scan().unwrap();
+///
+/// assert_eq!(TokenType::Identifier, tokens[1].token_type);
+/// assert!(Scanner::new("{$name_1: null}").scan().is_err());
+/// ```
+///
+/// ## Braced unicode escapes
+///
+/// Some producers write a character outside the Basic Multilingual Plane as a single escape of
+/// its code point, such as `\u{1F600}`, instead of a surrogate pair. With
+/// `ParseOptions::allow_braced_unicode_escapes` one to six hex digits between braces are accepted
+/// if they are a Unicode scalar value, otherwise `ScannerError::InvalidUnicodeSequence` is
+/// returned, including for surrogates. The literal is kept as written, so
+/// `token::escape::unescape_json_string` does not decode it.
+///
+/// ```
+/// use scanner::scanner::Scanner;
+/// use token::parse_options::ParseOptions;
+///
+/// let options = ParseOptions {
+///     allow_braced_unicode_escapes: true,
+///     ..ParseOptions::default()
+/// };
+///
+/// assert!(Scanner::new(r#""\u{1F600}""#).with_options(options).scan().is_ok());
+/// assert!(Scanner::new(r#""\u{D83D}""#).with_options(options).scan().is_err());
+/// assert!(Scanner::new(r#""\u{1F600}""#).scan().is_err());
+/// ```
+///
+/// ## Options
+///
+/// `Scanner::with_options` applies the comment, quote and tab settings of a `ParseOptions`
+/// together with its limits for untrusted input. A source larger than `max_input_size` returns
+/// `ScannerError::InputTooLarge` before any token is scanned and a string longer than
+/// `max_string_length` returns `ScannerError::StringTooLong` as soon as the limit is passed.
+///
+/// ```
+/// use scanner::scanner::Scanner;
+/// use token::parse_options::ParseOptions;
+///
+/// let options = ParseOptions {
+///     max_string_length: Some(3),
+///     ..ParseOptions::default()
+/// };
+///
+/// assert!(Scanner::new("\"abc\"").with_options(options).scan().is_ok());
+/// assert!(Scanner::new("\"abcd\"").with_options(options).scan().is_err());
+/// ```
+///
+/// ## Byte order mark
+///
+/// A leading UTF-8 byte order mark is skipped. Token indices remain byte offsets into the
+/// original source, so they start after the mark, while columns ignore it.
+///
+/// ## Bytes
+///
+/// `Scanner::from_bytes` checks raw input is UTF-8 before scanning and returns
+/// `ScannerError::InvalidUtf8` with the offset, line and column of the first invalid byte.
+///
+/// ```
+/// use scanner::{scanner::Scanner, scanner_error::ScannerError};
+///
+/// assert!(Scanner::from_bytes(b"[1, 2]").unwrap().scan().is_ok());
+///
+/// match Scanner::from_bytes(b"[\n  \"\xff\"]") {
+///     Err(ScannerError::InvalidUtf8 { offset, line, column, .. }) => {
+///         assert_eq!((5, 2, 4), (offset, line, column));
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
 #[derive(Debug)]
 pub struct Scanner<'source> {
     pub source: &'source str,
     chars: Peekable<CharIndices<'source>>,
+    /// Byte index of the source where `chars` starts, non-zero after resuming a rescan
+    chars_offset: usize,
     pub start: usize,
     pub current: usize,
     pub line: usize,
     pub column_start: usize,
     pub column_end: usize,
+    allow_comments: bool,
+    preserve_comments: bool,
+    allow_single_quotes: bool,
+    allow_unquoted_keys: bool,
+    allow_braced_unicode_escapes: bool,
+    allow_non_finite_numbers: bool,
+    reject_precision_loss: bool,
+    tab_width: usize,
+    max_input_size: Option<usize>,
+    max_string_length: Option<usize>,
+    colored_errors: bool,
+    /// Byte index, line and column just after the last token or comment, where `Eof` is placed
+    content_end: (usize, usize, usize),
+    finished: bool,
 }
 
 impl<'source> Scanner<'source> {
     pub fn new(source: &'source str) -> Self {
+        let mut chars = source.char_indices().peekable();
+        let bom = chars.next_if(|&(_, char)| char == '\u{FEFF}');
+        let current = bom.map_or(0, |(_, char)| char.len_utf8());
+
         Self {
             source,
-            chars: source.char_indices().peekable(),
-            start: 0,
-            current: 0,
+            chars,
+            chars_offset: 0,
+            start: current,
+            current,
             line: 1,
             column_start: 0,
             column_end: 1,
+            allow_comments: false,
+            preserve_comments: false,
+            allow_single_quotes: false,
+            allow_unquoted_keys: false,
+            allow_braced_unicode_escapes: false,
+            allow_non_finite_numbers: false,
+            reject_precision_loss: false,
+            tab_width: 1,
+            max_input_size: None,
+            max_string_length: None,
+            colored_errors: false,
+            content_end: (current, 1, 1),
+            finished: false,
+        }
+    }
+
+    /// Scanner over raw bytes, failing on the first byte that is not valid UTF-8
+    pub fn from_bytes(bytes: &'source [u8]) -> Result<Self, ScannerError> {
+        match core::str::from_utf8(bytes) {
+            Ok(source) => Ok(Self::new(source)),
+            Err(error) => {
+                let offset = error.valid_up_to();
+                let valid = &bytes[..offset];
+                let line_start = valid
+                    .iter()
+                    .rposition(|&byte| byte == b'\n')
+                    .map_or(0, |index| index + 1);
+
+                // The prefix up to `offset` is known to be valid so it can be counted in chars
+                let column = core::str::from_utf8(&valid[line_start..]).map_or(1, |line| {
+                    line.trim_start_matches('\u{FEFF}').chars().count() + 1
+                });
+
+                Err(ScannerError::InvalidUtf8 {
+                    offset,
+                    error: format!("byte {:#04x} at offset {}", bytes[offset], offset),
+                    line: valid.iter().filter(|&&byte| byte == b'\n').count() + 1,
+                    column,
+                    snippet: String::new(),
+                })
+            }
+        }
+    }
+
+    pub fn new_jsonc(source: &'source str) -> Self {
+        Self::new(source).with_options(ParseOptions {
+            allow_comments: true,
+            ..ParseOptions::default()
+        })
+    }
+
+    pub fn new_lenient(source: &'source str) -> Self {
+        Self::new(source).with_options(ParseOptions {
+            allow_single_quotes: true,
+            allow_non_finite_numbers: true,
+            ..ParseOptions::default()
+        })
+    }
+
+    /// Apply `allow_comments`, `preserve_comments`, `allow_single_quotes`, `allow_unquoted_keys`,
+    /// `allow_braced_unicode_escapes`, `allow_non_finite_numbers`, `reject_precision_loss`,
+    /// `tab_width`, `max_input_size`, `max_string_length` and `colored_errors`
+    pub fn with_options(self, options: ParseOptions) -> Self {
+        Self {
+            allow_comments: options.allow_comments,
+            preserve_comments: options.preserve_comments,
+            allow_single_quotes: options.allow_single_quotes,
+            allow_unquoted_keys: options.allow_unquoted_keys,
+            allow_braced_unicode_escapes: options.allow_braced_unicode_escapes,
+            allow_non_finite_numbers: options.allow_non_finite_numbers,
+            reject_precision_loss: options.reject_precision_loss,
+            max_input_size: options.max_input_size,
+            max_string_length: options.max_string_length,
+            colored_errors: options.colored_errors,
+            ..self.with_tab_width(options.tab_width)
+        }
+    }
+
+    /// Count a tab as moving to the next multiple of `tab_width` columns, 1 by default, so error
+    /// columns match how an editor displays the line
+    pub fn with_tab_width(self, tab_width: usize) -> Self {
+        Self {
+            tab_width: tab_width.max(1),
+            ..self
         }
     }
 
     fn error_preview(&self, start: Option<usize>, column_start: Option<usize>) -> String {
-        ErrorPreview.preview(
+        ErrorPreview.preview_span(
             self.source,
             start.unwrap_or(self.start),
+            self.current,
             column_start.unwrap_or(self.column_start),
             self.line,
+            self.colored_errors,
         )
     }
 
-    pub fn scan(&mut self) -> Result<Vec<Token>, ScannerError> {
-        let mut tokens = vec![];
+    fn snippet(&self, start: Option<usize>) -> String {
+        self.source[start.unwrap_or(self.start)..self.current].to_string()
+    }
 
-        if self.source.is_empty() {
-            Err(ScannerError::EmptySource {
-                error: self.error_preview(None, Some(1)),
-            })?
+    pub fn scan(&mut self) -> Result<Tokens, ScannerError> {
+        self.tokens().collect()
+    }
+
+    /// Lazily scan tokens one at a time
+    ///
+    /// The iterator yields each token as it is found followed by a final `Eof` token. Scanning
+    /// stops after the first error so at most one `Err` is ever returned.
+    ///
+    /// `Eof` is placed directly after the last token or comment, ignoring trailing whitespace and
+    /// newlines, so errors at the end of input point at the end of the last non-empty line.
+    ///
+    /// ```
+    /// use scanner::scanner::Scanner;
+    /// use token::token_type::TokenType;
+    ///
+    /// let mut scanner = Scanner::new("[true]");
+    /// let mut tokens = scanner.tokens();
+    ///
+    /// assert_eq!(
+    ///     Some(TokenType::LeftBracket),
+    ///     tokens.next().map(|token| token.unwrap().token_type)
+    /// );
+    /// ```
+    pub fn tokens(&mut self) -> TokenStream<'_, 'source> {
+        TokenStream { scanner: self }
+    }
+
+    /// Re-scan only the part of a document affected by an edit
+    ///
+    /// The scanner is created over the edited source, `tokens` are the tokens of the source before
+    /// the edit and `edit` is the byte range of the original source that was replaced by `new_len`
+    /// bytes. Scanning restarts at the token before the edit and stops as soon as a token after
+    /// the edit lines up with one of the original tokens, of the same type and length at the
+    /// shifted offset. The remaining original tokens are reused with their offsets, line numbers
+    /// and, on the same line, columns shifted, so an edit typed into a large file only scans the
+    /// few tokens around it. The result is the same as scanning the edited source from scratch.
+    ///
+    /// ```
+    /// use scanner::scanner::Scanner;
+    ///
+    /// let tokens = Scanner::new("[1, 2, 3]").scan().unwrap();
+    ///
+    /// // Replace the `2` at bytes 4..5 with `true`
+    /// let edited = "[1, true, 3]";
+    /// let rescanned = Scanner::new(edited).rescan_range(&tokens, 4..5, 4);
+    ///
+    /// assert_eq!(Scanner::new(edited).scan(), rescanned);
+    /// ```
+    pub fn rescan_range(
+        &mut self,
+        tokens: &[Token],
+        edit: Range<usize>,
+        new_len: usize,
+    ) -> Result<Tokens, ScannerError> {
+        let restart = tokens
+            .iter()
+            .position(|token| token.indices.1 >= edit.start)
+            .unwrap_or(tokens.len())
+            .saturating_sub(1);
+
+        // `Eof` follows the last content, which may be a comment that is not a token, so an edit
+        // reaching it is scanned from the start
+        let Some(resume) = tokens
+            .get(restart)
+            .filter(|token| token.indices.0 <= edit.start && token.token_type != TokenType::Eof)
+        else {
+            return self.scan();
+        };
+
+        let delta = new_len as isize - edit.len() as isize;
+        let edit_end = edit.start + new_len;
+
+        let mut rescanned = tokens[..restart].to_vec();
+        self.resume_at(resume);
+
+        while let Some(token) = self.next_token() {
+            let token = token?;
+
+            // Nothing is left after the resumed token so `Eof` belongs after the content before it
+            if token.token_type == TokenType::Eof && rescanned.len() == restart {
+                self.rewind();
+                return self.scan();
+            }
+
+            if token.indices.0 >= edit_end {
+                if let Some(tail) = self.unchanged_tail(tokens, &token, edit.end, delta) {
+                    rescanned.extend(tail);
+                    break;
+                }
+            }
+
+            rescanned.push(token);
+        }
+
+        Ok(rescanned.into())
+    }
+
+    /// Go back to the start of the source, keeping the options
+    fn rewind(&mut self) {
+        let Scanner {
+            chars,
+            chars_offset,
+            start,
+            current,
+            line,
+            column_start,
+            column_end,
+            content_end,
+            finished,
+            ..
+        } = Scanner::new(self.source);
+
+        self.chars = chars;
+        self.chars_offset = chars_offset;
+        self.start = start;
+        self.current = current;
+        self.line = line;
+        self.column_start = column_start;
+        self.column_end = column_end;
+        self.content_end = content_end;
+        self.finished = finished;
+    }
+
+    /// Continue scanning from the start of a previously scanned token
+    fn resume_at(&mut self, token: &Token) {
+        let mut start = token.indices.0;
+
+        self.chars = self
+            .source
+            .get(start..)
+            .unwrap_or_default()
+            .char_indices()
+            .peekable();
+        self.chars_offset = start;
+
+        // An edit may have added a byte order mark, which is only skipped at the start
+        if start == 0 {
+            if let Some((_, bom)) = self.chars.next_if(|&(_, char)| char == '\u{FEFF}') {
+                start = bom.len_utf8();
+            }
+        }
+
+        self.start = start;
+        self.current = start;
+        self.line = token.line_number;
+        self.column_start = token.column_indices.0.saturating_sub(1);
+        self.column_end = token.column_indices.0;
+        self.content_end = (start, self.line, self.column_end);
+    }
+
+    /// Original tokens from the one matching `token` onward, shifted to their edited positions, or
+    /// `None` if no original token after the edit lines up with `token`
+    fn unchanged_tail(
+        &self,
+        tokens: &[Token],
+        token: &Token,
+        edit_end: usize,
+        delta: isize,
+    ) -> Option<Vec<Token>> {
+        let original_start = token.indices.0.checked_add_signed(-delta)?;
+        let index = tokens
+            .binary_search_by_key(&original_start, |original| original.indices.0)
+            .ok()?;
+        let original = &tokens[index];
+
+        let line_delta = token.line_number as isize - original.line_number as isize;
+        let column_delta = token.column_indices.0 as isize - original.column_indices.0 as isize;
+
+        // Tabs later on the line move to the next tab stop so columns only shift uniformly by
+        // whole tab widths
+        let aligned = column_delta.unsigned_abs().is_multiple_of(self.tab_width);
+
+        if original.indices.0 < edit_end
+            || original.token_type != token.token_type
+            || original.indices.1 - original.indices.0 != token.indices.1 - token.indices.0
+            || !aligned
+        {
+            return None;
+        }
+
+        let shift = |index: usize, delta: isize| index.wrapping_add_signed(delta);
+
+        Some(
+            tokens[index..]
+                .iter()
+                .map(|tail| {
+                    let column_delta = if tail.line_number == original.line_number {
+                        column_delta
+                    } else {
+                        0
+                    };
+
+                    Token::new(
+                        tail.token_type.clone(),
+                        shift(tail.line_number, line_delta),
+                        (shift(tail.indices.0, delta), shift(tail.indices.1, delta)),
+                        (
+                            shift(tail.column_indices.0, column_delta),
+                            shift(tail.column_indices.1, column_delta),
+                        ),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn next_token(&mut self) -> Option<Result<Token, ScannerError>> {
+        if self.finished {
+            return None;
+        }
+
+        if self.source.trim_start_matches('\u{FEFF}').is_empty() {
+            self.finished = true;
+
+            // There is nothing to preview in an empty source
+            return Some(Err(ScannerError::EmptySource {
+                error: String::new(),
+                line: self.line,
+                column: 1,
+                snippet: self.snippet(None),
+            }));
+        }
+
+        if let Some(max_input_size) = self.max_input_size {
+            if self.source.len() > max_input_size {
+                self.finished = true;
+
+                return Some(Err(ScannerError::InputTooLarge {
+                    error: format!(
+                        "{} bytes exceeds the limit of {} bytes",
+                        self.source.len(),
+                        max_input_size
+                    ),
+                    line: self.line,
+                    column: 1,
+                    snippet: String::new(),
+                }));
+            }
         }
 
         while self.chars.peek().is_some() {
             self.start = self.current;
 
-            if let Some(token) = self.evaluate()? {
-                tokens.push(token);
+            let result = self.evaluate();
+
+            if !self.source[self.start..self.current].trim().is_empty() {
+                self.content_end = (self.current, self.line, self.column_end);
+            }
+
+            match result {
+                Ok(Some(token)) => return Some(Ok(token)),
+                Ok(None) => {}
+                Err(error) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
             }
         }
 
-        tokens.push(Token::new(
-            TokenType::Eof,
-            self.line,
-            (self.current, self.current),
-            (self.column_end, self.column_end),
-        ));
+        self.finished = true;
 
-        Ok(tokens)
+        let (end, line, column) = self.content_end;
+
+        Some(Ok(Token::new(
+            TokenType::Eof,
+            line,
+            (end, end),
+            (column, column),
+        )))
     }
 
     fn evaluate(&mut self) -> Result<Option<Token>, ScannerError> {
         let char = self.advance().unwrap();
 
         match char {
-            ' ' | '\t' | '\r' => Ok(None),
+            ' ' | '\t' => Ok(None),
+            '\r' => {
+                // \r\n is a single line terminator whereas a lone \r is whitespace
+                if self.advance_if(|&(_, char)| char == '\n').is_some() {
+                    self.new_line();
+                }
+
+                Ok(None)
+            }
             '\n' => {
-                self.line += 1;
-                self.column_start = 0;
-                self.column_end = 1;
+                self.new_line();
                 Ok(None)
             }
+            '/' if self.allow_comments => self.skip_comment(),
             '{' => Ok(Some(self.create_token(TokenType::LeftBrace, None))),
             '}' => Ok(Some(self.create_token(TokenType::RightBrace, None))),
             '[' => Ok(Some(self.create_token(TokenType::LeftBracket, None))),
             ']' => Ok(Some(self.create_token(TokenType::RightBracket, None))),
             ':' => Ok(Some(self.create_token(TokenType::Colon, None))),
             ',' => Ok(Some(self.create_token(TokenType::Comma, None))),
-            '\"' => self.scan_string(),
+            '\"' => self.scan_string('\"'),
+            '\'' if self.allow_single_quotes => self.scan_string('\''),
             '0' => {
                 if matches!(self.chars.peek(), Some(&(_, char)) if char.is_ascii_digit()) {
                     Err(ScannerError::LeadingZeros {
                         error: self.error_preview(None, None),
+                        line: self.line,
+                        column: self.column_start,
+                        snippet: self.snippet(None),
                     })?
                 }
 
                 self.scan_number()
             }
             '-' => self.scan_number(),
+            '+' if matches!(self.chars.peek(), Some(&(_, char)) if char.is_ascii_digit() || char == '.') => {
+                Err(ScannerError::InvalidNumberSign {
+                    error: self.error_preview(None, None),
+                    line: self.line,
+                    column: self.column_start,
+                    snippet: self.snippet(None),
+                })?
+            }
+            '_' | '$' if self.allow_unquoted_keys => self.scan_keyword(),
             _ => {
                 if char.is_ascii_alphabetic() {
                     self.scan_keyword()
@@ -170,6 +730,9 @@ impl<'source> Scanner<'source> {
                 } else {
                     Err(ScannerError::UnknownCharacter {
                         error: self.error_preview(None, None),
+                        line: self.line,
+                        column: self.column_start,
+                        snippet: self.snippet(None),
                     })?
                 }
             }
@@ -179,22 +742,57 @@ impl<'source> Scanner<'source> {
     fn scan_number(&mut self) -> Result<Option<Token>, ScannerError> {
         let number_column_start = self.column_start;
 
-        while let Some(_) = self.advance_if(|&(_, char)| char.is_ascii_digit()) {}
+        if self.allow_non_finite_numbers
+            && self.source[self.start..].starts_with('-')
+            && self.advance_if(|&(_, char)| char == 'I').is_some()
+        {
+            while self
+                .advance_if(|&(_, char)| char.is_ascii_alphabetic())
+                .is_some()
+            {}
+
+            if &self.source[self.start..self.current] != "-Infinity" {
+                Err(ScannerError::InvalidNumber {
+                    error: self.error_preview(None, Some(number_column_start)),
+                    line: self.line,
+                    column: number_column_start,
+                    snippet: self.snippet(None),
+                })?
+            }
+
+            return Ok(Some(
+                self.create_token(TokenType::Number, Some(number_column_start)),
+            ));
+        }
+
+        while self
+            .advance_if(|&(_, char)| char.is_ascii_digit())
+            .is_some()
+        {}
 
         if self.advance_if(|&(_, char)| char == '.').is_some() {
             match self.chars.peek() {
                 Some(&(_, char)) if !char.is_ascii_digit() => {
                     Err(ScannerError::UnterminatedFractionalNumber {
                         error: self.error_preview(None, Some(number_column_start)),
+                        line: self.line,
+                        column: number_column_start,
+                        snippet: self.snippet(None),
                     })?
                 }
                 None => Err(ScannerError::UnterminatedFractionalNumber {
                     error: self.error_preview(None, Some(number_column_start)),
+                    line: self.line,
+                    column: number_column_start,
+                    snippet: self.snippet(None),
                 })?,
                 _ => {}
             }
 
-            while let Some(_) = self.advance_if(|&(_, char)| char.is_ascii_digit()) {}
+            while self
+                .advance_if(|&(_, char)| char.is_ascii_digit())
+                .is_some()
+            {}
         }
 
         if self
@@ -204,41 +802,86 @@ impl<'source> Scanner<'source> {
             let exponent_start = self.current - 1;
             let exponent_column_start = self.column_start;
 
-            if self
-                .advance_if(|&(_, char)| char == '+' || char == '-')
-                .is_some()
-            {}
+            self.advance_if(|&(_, char)| char == '+' || char == '-');
 
             match self.chars.peek() {
                 Some(&(_, char)) if !char.is_ascii_digit() => Err(ScannerError::InvalidExponent {
                     error: self.error_preview(Some(exponent_start), Some(exponent_column_start)),
+                    line: self.line,
+                    column: exponent_column_start,
+                    snippet: self.snippet(Some(exponent_start)),
                 })?,
                 None => Err(ScannerError::InvalidExponent {
                     error: self.error_preview(Some(exponent_start), Some(exponent_column_start)),
+                    line: self.line,
+                    column: exponent_column_start,
+                    snippet: self.snippet(Some(exponent_start)),
                 })?,
                 _ => {}
             }
 
-            while let Some(_) = self.advance_if(|&(_, char)| char.is_ascii_digit()) {}
+            while self
+                .advance_if(|&(_, char)| char.is_ascii_digit())
+                .is_some()
+            {}
         }
 
-        match &self.source[self.start..self.current].parse::<f64>() {
-            Ok(number) if number.is_finite() => Ok(Some(
+        let literal = &self.source[self.start..self.current];
+
+        match literal.parse::<f64>() {
+            Ok(number)
+                if number.is_finite() && self.reject_precision_loss && !round_trips(literal) =>
+            {
+                Err(ScannerError::PrecisionLoss {
+                    error: self.error_preview(None, Some(number_column_start)),
+                    line: self.line,
+                    column: number_column_start,
+                    snippet: self.snippet(None),
+                })?
+            }
+            Ok(number) if number.is_finite() || self.allow_non_finite_numbers => Ok(Some(
                 self.create_token(TokenType::Number, Some(number_column_start)),
             )),
             _ => Err(ScannerError::InvalidNumber {
                 error: self.error_preview(None, Some(number_column_start)),
+                line: self.line,
+                column: number_column_start,
+                snippet: self.snippet(None),
             })?,
         }
     }
 
-    fn scan_string(&mut self) -> Result<Option<Token>, ScannerError> {
+    fn scan_string(&mut self, quote: char) -> Result<Option<Token>, ScannerError> {
         let string_column_start = self.column_start;
 
-        while let Some(char) = self.advance_if(|&(_, char)| char != '\"') {
-            if char == '\n' {
+        while let Some(char) = self.advance_if(|&(_, char)| char != quote) {
+            if matches!(self.max_string_length, Some(max) if self.current - self.start - 1 > max) {
+                Err(ScannerError::StringTooLong {
+                    error: self.error_preview(None, Some(string_column_start)),
+                    line: self.line,
+                    column: string_column_start,
+                    snippet: self.snippet(None),
+                })?
+            }
+
+            if char == '\n' || char == '\r' {
                 Err(ScannerError::UnterminatedString {
                     error: self.error_preview(None, Some(string_column_start)),
+                    line: self.line,
+                    column: string_column_start,
+                    snippet: self.snippet(None),
+                })?
+            }
+
+            if char < '\u{20}' {
+                let char_start = self.current - 1;
+
+                Err(ScannerError::UnescapedControlCharacter {
+                    code_point: char as u32,
+                    error: self.error_preview(Some(char_start), None),
+                    line: self.line,
+                    column: self.column_start,
+                    snippet: self.snippet(Some(char_start)),
                 })?
             }
 
@@ -247,30 +890,54 @@ impl<'source> Scanner<'source> {
                 let escape_column_start = self.column_start;
 
                 match self.chars.peek() {
-                    Some(&(_, char)) if char == 'u' => {
+                    Some(&(_, 'u')) => {
                         self.advance();
 
-                        for _ in 0..4 {
-                            if self
-                                .advance_if(|&(_, char)| char.is_ascii_hexdigit())
-                                .is_none()
-                            {
-                                Err(ScannerError::InvalidUnicodeSequence {
-                                    error: self.error_preview(
-                                        Some(escape_start),
-                                        Some(escape_column_start),
-                                    ),
-                                })?
+                        if self.allow_braced_unicode_escapes
+                            && self.advance_if(|&(_, char)| char == '{').is_some()
+                        {
+                            self.scan_code_point(escape_start, escape_column_start)?;
+                            continue;
+                        }
+
+                        let code_unit = self.scan_code_unit(escape_start, escape_column_start)?;
+
+                        // A high surrogate must be immediately followed by a low surrogate escape
+                        // to form a valid Unicode scalar value and a low surrogate must never
+                        // appear on its own
+                        let is_valid_pair = match code_unit {
+                            0xD800..=0xDBFF => {
+                                self.advance_if(|&(_, char)| char == '\\').is_some()
+                                    && self.advance_if(|&(_, char)| char == 'u').is_some()
+                                    && (0xDC00..=0xDFFF).contains(
+                                        &self.scan_code_unit(escape_start, escape_column_start)?,
+                                    )
                             }
+                            0xDC00..=0xDFFF => false,
+                            _ => true,
+                        };
+
+                        if !is_valid_pair {
+                            Err(ScannerError::InvalidSurrogatePair {
+                                error: self
+                                    .error_preview(Some(escape_start), Some(escape_column_start)),
+                                line: self.line,
+                                column: escape_column_start,
+                                snippet: self.snippet(Some(escape_start)),
+                            })?
                         }
                     }
-                    Some(&(_, char))
-                        if matches!(char, '\"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't') =>
-                    {
+                    Some(&(_, '\"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't')) => {
+                        self.advance();
+                    }
+                    Some(&(_, '\'')) if quote == '\'' => {
                         self.advance();
                     }
                     _ => Err(ScannerError::InvalidEscapeSequence {
                         error: self.error_preview(Some(escape_start), Some(escape_column_start)),
+                        line: self.line,
+                        column: escape_column_start,
+                        snippet: self.snippet(Some(escape_start)),
                     })?,
                 };
             }
@@ -279,6 +946,9 @@ impl<'source> Scanner<'source> {
         if self.chars.peek().is_none() {
             Err(ScannerError::UnterminatedString {
                 error: self.error_preview(None, Some(string_column_start)),
+                line: self.line,
+                column: string_column_start,
+                snippet: self.snippet(None),
             })?
         }
 
@@ -290,17 +960,145 @@ impl<'source> Scanner<'source> {
         )))
     }
 
+    /// Scan the four hex digits following `\u` into a UTF-16 code unit
+    fn scan_code_unit(
+        &mut self,
+        escape_start: usize,
+        escape_column_start: usize,
+    ) -> Result<u32, ScannerError> {
+        let mut code_unit = 0;
+
+        for _ in 0..4 {
+            match self.advance_if(|&(_, char)| char.is_ascii_hexdigit()) {
+                Some(char) => code_unit = code_unit * 16 + char.to_digit(16).unwrap_or_default(),
+                None => Err(ScannerError::InvalidUnicodeSequence {
+                    error: self.error_preview(Some(escape_start), Some(escape_column_start)),
+                    line: self.line,
+                    column: escape_column_start,
+                    snippet: self.snippet(Some(escape_start)),
+                })?,
+            }
+        }
+
+        Ok(code_unit)
+    }
+
+    /// Scan the hex digits and closing brace of `\u{...}` into a Unicode scalar value
+    ///
+    /// At most six digits are read, enough for U+10FFFF. Surrogates are not scalar values and,
+    /// unlike in `\uXXXX` escapes, cannot be combined into a pair.
+    fn scan_code_point(
+        &mut self,
+        escape_start: usize,
+        escape_column_start: usize,
+    ) -> Result<char, ScannerError> {
+        let mut code_point = 0;
+        let mut digits = 0;
+
+        while let Some(char) = self.advance_if(|&(_, char)| char.is_ascii_hexdigit()) {
+            code_point = code_point * 16 + char.to_digit(16).unwrap_or_default();
+            digits += 1;
+
+            if digits > 6 {
+                break;
+            }
+        }
+
+        let closed = self.advance_if(|&(_, char)| char == '}').is_some();
+
+        match char::from_u32(code_point) {
+            Some(char) if closed && (1..=6).contains(&digits) => Ok(char),
+            _ => Err(ScannerError::InvalidUnicodeSequence {
+                error: self.error_preview(Some(escape_start), Some(escape_column_start)),
+                line: self.line,
+                column: escape_column_start,
+                snippet: self.snippet(Some(escape_start)),
+            })?,
+        }
+    }
+
+    fn skip_comment(&mut self) -> Result<Option<Token>, ScannerError> {
+        let comment_line = self.line;
+        let comment_column_start = self.column_start;
+
+        match self.advance() {
+            Some('/') => {
+                while self
+                    .advance_if(|&(_, char)| char != '\n' && char != '\r')
+                    .is_some()
+                {}
+            }
+            Some('*') => loop {
+                match self.advance() {
+                    Some('*') if self.advance_if(|&(_, char)| char == '/').is_some() => break,
+                    Some('\r') if self.advance_if(|&(_, char)| char == '\n').is_some() => {
+                        self.new_line()
+                    }
+                    Some('\n') => self.new_line(),
+                    Some(_) => {}
+                    None => Err(ScannerError::UnterminatedComment {
+                        error: ErrorPreview.preview_span(
+                            self.source,
+                            self.start,
+                            self.current,
+                            comment_column_start,
+                            comment_line,
+                            self.colored_errors,
+                        ),
+                        line: comment_line,
+                        column: comment_column_start,
+                        snippet: self.snippet(None),
+                    })?,
+                }
+            },
+            _ => Err(ScannerError::UnknownCharacter {
+                error: self.error_preview(None, Some(comment_column_start)),
+                line: self.line,
+                column: comment_column_start,
+                snippet: self.snippet(None),
+            })?,
+        }
+
+        if !self.preserve_comments {
+            return Ok(None);
+        }
+
+        Ok(Some(Token::new(
+            TokenType::Comment,
+            comment_line,
+            (self.start, self.current),
+            (comment_column_start, self.column_end),
+        )))
+    }
+
     fn scan_keyword(&mut self) -> Result<Option<Token>, ScannerError> {
         let keyword_column_start = self.column_start;
 
-        while let Some(_) = self.advance_if(|&(_, char)| char.is_ascii_alphabetic()) {}
+        let allow_unquoted_keys = self.allow_unquoted_keys;
+
+        while self
+            .advance_if(|&(_, char)| {
+                char.is_ascii_alphabetic()
+                    || allow_unquoted_keys && (char.is_ascii_digit() || char == '_' || char == '$')
+            })
+            .is_some()
+        {}
 
         let result = match &self.source[self.start..self.current] {
             "true" => self.create_token(TokenType::True, Some(keyword_column_start)),
             "false" => self.create_token(TokenType::False, Some(keyword_column_start)),
             "null" => self.create_token(TokenType::Null, Some(keyword_column_start)),
+            "NaN" | "Infinity" if self.allow_non_finite_numbers => {
+                self.create_token(TokenType::Number, Some(keyword_column_start))
+            }
+            _ if allow_unquoted_keys => {
+                self.create_token(TokenType::Identifier, Some(keyword_column_start))
+            }
             _ => Err(ScannerError::UnknownLiteral {
                 error: self.error_preview(None, Some(keyword_column_start)),
+                line: self.line,
+                column: keyword_column_start,
+                snippet: self.snippet(None),
             })?,
         };
 
@@ -312,9 +1110,8 @@ impl<'source> Scanner<'source> {
         F: Fn(&(usize, char)) -> bool,
     {
         if let Some((char_index, char)) = self.chars.next_if(predicate) {
-            self.current = char_index + char.len_utf8();
-            self.column_start += 1;
-            self.column_end += 1;
+            self.current = self.chars_offset + char_index + char.len_utf8();
+            self.advance_column(char);
 
             return Some(char);
         }
@@ -324,9 +1121,8 @@ impl<'source> Scanner<'source> {
 
     fn advance(&mut self) -> Option<char> {
         if let Some((char_index, char)) = self.chars.next() {
-            self.current = char_index + char.len_utf8();
-            self.column_start += 1;
-            self.column_end += 1;
+            self.current = self.chars_offset + char_index + char.len_utf8();
+            self.advance_column(char);
 
             return Some(char);
         }
@@ -334,6 +1130,21 @@ impl<'source> Scanner<'source> {
         None
     }
 
+    /// Move the display column past `char` where a tab moves to the next tab stop
+    fn advance_column(&mut self, char: char) {
+        self.column_start = self.column_end;
+        self.column_end += match char {
+            '\t' => self.tab_width - (self.column_end - 1) % self.tab_width,
+            _ => 1,
+        };
+    }
+
+    fn new_line(&mut self) {
+        self.line += 1;
+        self.column_start = 0;
+        self.column_end = 1;
+    }
+
     fn create_token(&mut self, token_type: TokenType, column_start: Option<usize>) -> Token {
         Token::new(
             token_type,
@@ -344,10 +1155,202 @@ impl<'source> Scanner<'source> {
     }
 }
 
+/// Iterator over the tokens of a `Scanner` created by `Scanner::tokens`
+#[derive(Debug)]
+pub struct TokenStream<'scanner, 'source> {
+    scanner: &'scanner mut Scanner<'source>,
+}
+
+impl Iterator for TokenStream<'_, '_> {
+    type Item = Result<Token, ScannerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.scanner.next_token()
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod scanner_tests {
     use super::*;
 
+    /// Apply `replacement` over `edit` of `source`, check that rescanning it incrementally matches
+    /// a full scan and return where the incremental scan stopped
+    fn assert_rescan(
+        source: &str,
+        edit: Range<usize>,
+        replacement: &str,
+        options: ParseOptions,
+    ) -> usize {
+        let tokens = Scanner::new(source).with_options(options).scan().unwrap();
+        let edited = format!(
+            "{}{}{}",
+            &source[..edit.start],
+            replacement,
+            &source[edit.end..]
+        );
+
+        let mut scanner = Scanner::new(&edited).with_options(options);
+
+        assert_eq!(
+            Scanner::new(&edited).with_options(options).scan(),
+            scanner.rescan_range(&tokens, edit.clone(), replacement.len()),
+            "{:?} -> {:?}",
+            edit,
+            replacement
+        );
+
+        scanner.current
+    }
+
+    #[test]
+    fn rescan_edited_range() {
+        let source = "{\n  \"a\": 1,\n  \"b\": [true, null],\n  \"c\": \"x\"\n}";
+
+        let edits = [
+            (8..9, "\"changed\""),
+            (8..9, "1,\n  \"z\": 2"),
+            (12..14, ""),
+            (0..0, "\u{FEFF}"),
+            (40..40, "  "),
+            (source.len()..source.len(), "\n\n"),
+            (21..21, "1, "),
+            (18..19, "\"b2\""),
+        ];
+
+        for (edit, replacement) in edits {
+            assert_rescan(source, edit, replacement, ParseOptions::default());
+        }
+    }
+
+    #[test]
+    fn rescan_stops_after_the_edit() {
+        let source = "[1, 2, 3, 4, 5, 6, 7, 8, 9]";
+
+        assert!(assert_rescan(source, 4..5, "20", ParseOptions::default()) < 12);
+    }
+
+    #[test]
+    fn rescan_edits_changing_later_tokens() {
+        let options = ParseOptions {
+            allow_comments: true,
+            preserve_comments: true,
+            ..ParseOptions::default()
+        };
+
+        // Opening a string swallows the comment and fails at the next quote
+        assert_rescan("[1, /* a */ 2, \"x\"]", 3..3, "\"", options);
+        assert_rescan("[1, /* a */ 2]\n// end", 4..6, "", options);
+        assert_rescan("[1, 2]\n// end", 6..6, ", 3", options);
+    }
+
+    #[test]
+    fn rescan_edits_leaving_only_whitespace_or_comments() {
+        let options = ParseOptions {
+            allow_comments: true,
+            ..ParseOptions::default()
+        };
+
+        // `Eof` sits at the end of the comment text, resuming there would start inside it
+        assert_rescan("//c\n", 3..3, "é", options);
+        assert_rescan("\n,", 1..2, "", ParseOptions::default());
+        assert_rescan("[1] // end\n ", 3..11, "", options);
+    }
+
+    #[test]
+    fn rescan_with_tabs() {
+        let options = ParseOptions {
+            tab_width: 4,
+            ..ParseOptions::default()
+        };
+
+        for replacement in ["10", "100", "10000", ""] {
+            assert_rescan("[1,\t2,\t3]", 1..2, replacement, options);
+        }
+    }
+
+    #[test]
+    fn track_crlf_line_endings() {
+        let tokens = Scanner::new("[\r\n  1,\r\n\r\n2\r\n]")
+            .scan()
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(
+            vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::Number, 2, (5, 6), (3, 4)),
+                Token::new(TokenType::Comma, 2, (6, 7), (4, 5)),
+                Token::new(TokenType::Number, 4, (11, 12), (1, 2)),
+                Token::new(TokenType::RightBracket, 5, (14, 15), (1, 2)),
+                Token::new(TokenType::Eof, 5, (15, 15), (2, 2)),
+            ],
+            tokens
+        );
+    }
+
+    #[test]
+    fn crlf_inside_comments() {
+        let tokens = Scanner::new_jsonc("// a\r\n/* b\r\n c */\r\ntrue")
+            .scan()
+            .unwrap();
+
+        assert_eq!(Token::new(TokenType::True, 4, (19, 23), (1, 5)), tokens[0]);
+    }
+
+    #[test]
+    fn error_on_carriage_return_in_string() {
+        let error = Scanner::new("[\r\n\"a\r\n\"]").scan().unwrap_err();
+
+        assert_eq!(
+            ScannerError::UnterminatedString {
+                error: "\n  |\n  |\n2 |\"a\n  |^---Column=1\n +|".to_string(),
+                line: 2,
+                column: 1,
+                snippet: "\"a\r".to_string(),
+            },
+            error
+        );
+    }
+
+    #[test]
+    fn skip_byte_order_mark() {
+        let source = "\u{FEFF}{}";
+
+        let tokens = Scanner::new(source).scan().unwrap();
+        let expected = Scanner::new("{}").scan().unwrap();
+
+        assert_eq!(
+            expected
+                .iter()
+                .map(|token| (&token.token_type, token.column_indices))
+                .collect::<Vec<_>>(),
+            tokens
+                .iter()
+                .map(|token| (&token.token_type, token.column_indices))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["{", "}", ""],
+            tokens
+                .iter()
+                .map(|token| &source[token.indices.0..token.indices.1])
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn error_after_byte_order_mark() {
+        assert!(matches!(
+            Scanner::new("\u{FEFF}").scan(),
+            Err(ScannerError::EmptySource { .. })
+        ));
+        assert!(matches!(
+            Scanner::new("\u{FEFF}@").scan(),
+            Err(ScannerError::UnknownCharacter { column: 1, .. })
+        ));
+    }
+
     #[test]
     fn error_preview() {
         let source = "@";
@@ -356,14 +1359,39 @@ mod scanner_tests {
         assert_eq!(true, s.scan().is_err())
     }
 
+    #[test]
+    fn expand_tabs_to_tab_stops() {
+        let column = |source, tab_width| match Scanner::new(source).with_tab_width(tab_width).scan()
+        {
+            Err(ScannerError::UnknownCharacter { column, .. }) => column,
+            _ => 0,
+        };
+
+        assert_eq!(6, column("[\t1,\t@]", 1));
+        assert_eq!(9, column("[\t1,\t@]", 4));
+        assert_eq!(17, column("[\t1,\t@]", 8));
+        assert_eq!(9, column("[\"😀\",\t@]", 4));
+        assert_eq!(5, column("\t\n\t\t@", 2));
+
+        assert_eq!(
+            Ok(Tokens::from(vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::True, 1, (2, 6), (5, 9)),
+                Token::new(TokenType::RightBracket, 1, (6, 7), (9, 10)),
+                Token::new(TokenType::Eof, 1, (7, 7), (10, 10))
+            ])),
+            Scanner::new("[\ttrue]").with_tab_width(4).scan()
+        );
+    }
+
     #[test]
     fn ignore_spacing_and_maintain_display_column() {
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
                 Token::new(TokenType::RightBracket, 1, (2, 3), (3, 4)),
                 Token::new(TokenType::Eof, 1, (3, 3), (4, 4))
-            ]),
+            ])),
             Scanner::new("[ ]").scan()
         );
     }
@@ -382,38 +1410,60 @@ mod scanner_tests {
     #[test]
     fn valid_exponents() {
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::Number, 1, (0, 5), (1, 6)),
                 Token::new(TokenType::Eof, 1, (5, 5), (6, 6)),
-            ]),
+            ])),
             Scanner::new("360e2").scan()
         );
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::Number, 1, (0, 4), (1, 5)),
                 Token::new(TokenType::Eof, 1, (4, 4), (5, 5)),
-            ]),
+            ])),
             Scanner::new("29E8").scan()
         );
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::Number, 1, (0, 7), (1, 8)),
                 Token::new(TokenType::Eof, 1, (7, 7), (8, 8)),
-            ]),
+            ])),
             Scanner::new("29e+100").scan()
         );
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::Number, 1, (0, 5), (1, 6)),
                 Token::new(TokenType::Eof, 1, (5, 5), (6, 6)),
-            ]),
+            ])),
             Scanner::new("29e-2").scan()
         );
     }
 
+    #[test]
+    fn error_on_leading_plus_sign() {
+        assert_eq!(
+            Err(ScannerError::InvalidNumberSign {
+                error: "\n  |\n  |\n1 |[+5]\n  | ^---Column=2\n  |".to_string(),
+                line: 1,
+                column: 2,
+                snippet: "+".to_string(),
+            }),
+            Scanner::new("[+5]").scan()
+        );
+
+        assert_eq!(
+            "invalid_number_sign",
+            Scanner::new("+.5").scan().unwrap_err().kind()
+        );
+        assert_eq!(
+            "unknown_character",
+            Scanner::new("[+]").scan().unwrap_err().kind()
+        );
+    }
+
     #[test]
     fn do_not_allow_leading_zeros_in_number() {
         assert_eq!(true, Scanner::new("000.23432").scan().is_err());
@@ -423,34 +1473,34 @@ mod scanner_tests {
     #[test]
     fn scan_valid_numbers() {
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::Number, 1, (0, 1), (1, 2)),
                 Token::new(TokenType::Eof, 1, (1, 1), (2, 2))
-            ]),
+            ])),
             Scanner::new("0").scan()
         );
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::Number, 1, (0, 3), (1, 4)),
                 Token::new(TokenType::Eof, 1, (3, 3), (4, 4))
-            ]),
+            ])),
             Scanner::new("360").scan()
         );
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::Number, 1, (0, 7), (1, 8)),
                 Token::new(TokenType::Eof, 1, (7, 7), (8, 8))
-            ]),
+            ])),
             Scanner::new("360.360").scan()
         );
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::Number, 1, (0, 5), (1, 6)),
                 Token::new(TokenType::Eof, 1, (5, 5), (6, 6))
-            ]),
+            ])),
             Scanner::new("-1066").scan()
         );
     }
@@ -466,18 +1516,18 @@ mod scanner_tests {
     #[test]
     fn scan_valid_string_with_graphemes() {
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::String, 1, (0, 10), (1, 5)),
                 Token::new(TokenType::Eof, 1, (10, 10), (5, 5))
-            ]),
+            ])),
             Scanner::new("\"🌎🚀\"").scan()
         );
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::String, 1, (0, 10), (1, 5)),
                 Token::new(TokenType::Eof, 1, (10, 10), (5, 5))
-            ]),
+            ])),
             Scanner::new("\"🌎🚀\"").scan()
         );
     }
@@ -485,30 +1535,53 @@ mod scanner_tests {
     #[test]
     fn valid_escape_sequence() {
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::String, 1, (0, 19), (1, 20)),
                 Token::new(TokenType::Eof, 1, (19, 19), (20, 20))
-            ]),
+            ])),
             Scanner::new(r#""hello\u0020world!""#).scan(),
         );
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::String, 1, (0, 14), (1, 15)),
                 Token::new(TokenType::Eof, 1, (14, 14), (15, 15))
-            ]),
+            ])),
             Scanner::new(r#""\uD83D\uDE00""#).scan(),
         );
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::String, 1, (0, 10), (1, 11)),
                 Token::new(TokenType::Eof, 1, (10, 10), (11, 11))
-            ]),
+            ])),
             Scanner::new(r#""\\\uaaaa""#).scan(),
         );
     }
 
+    #[test]
+    fn unescaped_control_characters() {
+        let error = |source| match Scanner::new(source).scan() {
+            Err(ScannerError::UnescapedControlCharacter {
+                code_point,
+                line,
+                column,
+                snippet,
+                ..
+            }) => (code_point, line, column, snippet),
+            result => panic!("expected unescaped control character, got {:?}", result),
+        };
+
+        assert_eq!((0x09, 1, 3, "\t".to_string()), error("\"a\tb\""));
+        assert_eq!((0x00, 1, 2, "\u{0}".to_string()), error("\"\u{0}\""));
+        assert_eq!(
+            (0x1B, 2, 5, "\u{1b}".to_string()),
+            error("[\n\"red\u{1b}[0m\"]")
+        );
+        assert!(Scanner::new(r#""a\tb\u001b""#).scan().is_ok());
+        assert!(Scanner::new("\"\u{7f}\"").scan().is_ok());
+    }
+
     #[test]
     fn invalid_escape_sequence() {
         assert_eq!(true, Scanner::new(r#""hello\\\world!""#).scan().is_err(),);
@@ -516,21 +1589,242 @@ mod scanner_tests {
         assert_eq!(true, Scanner::new(r#""\u01AG""#).scan().is_err(),);
     }
 
+    #[test]
+    fn invalid_surrogate_pairs() {
+        for source in [
+            r#""\uD83D""#,
+            r#""\uD83D a""#,
+            r#""\uD83D\n""#,
+            r#""\uD83D\uD83D""#,
+            r#""\uDE00""#,
+            r#""\uDE00\uD83D""#,
+        ] {
+            assert!(
+                matches!(
+                    Scanner::new(source).scan(),
+                    Err(ScannerError::InvalidSurrogatePair { .. })
+                ),
+                "{}",
+                source
+            );
+        }
+
+        assert!(matches!(
+            Scanner::new(r#""\uD83D\uDE0""#).scan(),
+            Err(ScannerError::InvalidUnicodeSequence { .. })
+        ));
+    }
+
+    #[test]
+    fn scan_braced_unicode_escapes() {
+        let options = ParseOptions {
+            allow_braced_unicode_escapes: true,
+            ..ParseOptions::default()
+        };
+
+        assert_eq!(
+            Ok(Tokens::from(vec![
+                Token::new(TokenType::String, 1, (0, 24), (1, 25)),
+                Token::new(TokenType::Eof, 1, (24, 24), (25, 25)),
+            ])),
+            Scanner::new(r#""\u{41}\u{10FFFF}\u0041""#)
+                .with_options(options)
+                .scan()
+        );
+
+        for source in [
+            r#""\u{}""#,
+            r#""\u{110000}""#,
+            r#""\u{0000041}""#,
+            r#""\u{D800}""#,
+            r#""\u{DFFF}""#,
+            r#""\u{1F60G}""#,
+            r#""\u{1F600""#,
+        ] {
+            match Scanner::new(source).with_options(options).scan() {
+                Err(error @ ScannerError::InvalidUnicodeSequence { .. }) => {
+                    assert_eq!(
+                        (1, 2),
+                        (error.position().0, error.position().1),
+                        "{}",
+                        source
+                    )
+                }
+                result => panic!(
+                    "{}: expected invalid unicode sequence, got {:?}",
+                    source, result
+                ),
+            }
+        }
+
+        assert!(matches!(
+            Scanner::new(r#""\u{41}""#).scan(),
+            Err(ScannerError::InvalidUnicodeSequence { .. })
+        ));
+    }
+
+    #[test]
+    fn scan_unquoted_keys() {
+        let options = ParseOptions {
+            allow_unquoted_keys: true,
+            ..ParseOptions::default()
+        };
+
+        let tokens = Scanner::new("{_id: 1, a$2: true}")
+            .with_options(options)
+            .scan()
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(
+            vec![
+                Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::Identifier, 1, (1, 4), (2, 5)),
+                Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
+                Token::new(TokenType::Number, 1, (6, 7), (7, 8)),
+                Token::new(TokenType::Comma, 1, (7, 8), (8, 9)),
+                Token::new(TokenType::Identifier, 1, (9, 12), (10, 13)),
+                Token::new(TokenType::Colon, 1, (12, 13), (13, 14)),
+                Token::new(TokenType::True, 1, (14, 18), (15, 19)),
+                Token::new(TokenType::RightBrace, 1, (18, 19), (19, 20)),
+                Token::new(TokenType::Eof, 1, (19, 19), (20, 20)),
+            ],
+            tokens
+        );
+
+        assert_eq!(
+            "unknown_literal",
+            Scanner::new("{id: 1}").scan().unwrap_err().kind()
+        );
+        assert_eq!(
+            "unknown_character",
+            Scanner::new("{_id: 1}").scan().unwrap_err().kind()
+        );
+    }
+
+    #[test]
+    fn scan_non_finite_numbers() {
+        assert_eq!(
+            Ok(Tokens::from(vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::Number, 1, (1, 4), (2, 5)),
+                Token::new(TokenType::Comma, 1, (4, 5), (5, 6)),
+                Token::new(TokenType::Number, 1, (5, 13), (6, 14)),
+                Token::new(TokenType::Comma, 1, (13, 14), (14, 15)),
+                Token::new(TokenType::Number, 1, (14, 23), (15, 24)),
+                Token::new(TokenType::Comma, 1, (23, 24), (24, 25)),
+                Token::new(TokenType::Number, 1, (24, 29), (25, 30)),
+                Token::new(TokenType::RightBracket, 1, (29, 30), (30, 31)),
+                Token::new(TokenType::Eof, 1, (30, 30), (31, 31)),
+            ])),
+            Scanner::new_lenient("[NaN,Infinity,-Infinity,1e400]").scan()
+        );
+
+        assert!(matches!(
+            Scanner::new_lenient("-Inf").scan(),
+            Err(ScannerError::InvalidNumber { .. })
+        ));
+        assert!(matches!(
+            Scanner::new_lenient("-NaN").scan(),
+            Err(ScannerError::InvalidNumber { .. })
+        ));
+    }
+
+    #[test]
+    fn reject_precision_loss() {
+        let options = ParseOptions {
+            reject_precision_loss: true,
+            ..ParseOptions::default()
+        };
+
+        assert!(Scanner::new("9007199254740993").scan().is_ok());
+        assert!(Scanner::new("[0.1, 1.0e2, -0, 9007199254740992]")
+            .with_options(options)
+            .scan()
+            .is_ok());
+
+        match Scanner::new("[1,\n 9007199254740993]")
+            .with_options(options)
+            .scan()
+        {
+            Err(error @ ScannerError::PrecisionLoss { .. }) => {
+                assert_eq!("precision_loss", error.kind());
+                assert_eq!((2, 2, "9007199254740993"), error.position());
+            }
+            other => panic!("expected precision loss, found {:?}", other),
+        }
+
+        assert!(matches!(
+            Scanner::new("0.30000000000000001")
+                .with_options(options)
+                .scan(),
+            Err(ScannerError::PrecisionLoss { .. })
+        ));
+        assert!(matches!(
+            Scanner::new("1e400").with_options(options).scan(),
+            Err(ScannerError::InvalidNumber { .. })
+        ));
+    }
+
+    #[test]
+    fn reject_non_finite_numbers_by_default() {
+        assert!(matches!(
+            Scanner::new("NaN").scan(),
+            Err(ScannerError::UnknownLiteral { .. })
+        ));
+        assert!(matches!(
+            Scanner::new("Infinity").scan(),
+            Err(ScannerError::UnknownLiteral { .. })
+        ));
+        assert!(matches!(
+            Scanner::new("-Infinity").scan(),
+            Err(ScannerError::InvalidNumber { .. })
+        ));
+        assert!(matches!(
+            Scanner::new("1e400").scan(),
+            Err(ScannerError::InvalidNumber { .. })
+        ));
+    }
+
+    #[test]
+    fn scan_single_quoted_strings() {
+        assert_eq!(
+            Ok(Tokens::from(vec![
+                Token::new(TokenType::String, 1, (0, 12), (1, 13)),
+                Token::new(TokenType::Eof, 1, (12, 12), (13, 13))
+            ])),
+            Scanner::new_lenient(r#"'it\'s "ok"'"#).scan()
+        );
+
+        assert!(matches!(
+            Scanner::new("'x'").scan(),
+            Err(ScannerError::UnknownCharacter { .. })
+        ));
+        assert!(matches!(
+            Scanner::new_lenient("'x").scan(),
+            Err(ScannerError::UnterminatedString { .. })
+        ));
+        assert!(matches!(
+            Scanner::new_lenient(r#""\'""#).scan(),
+            Err(ScannerError::InvalidEscapeSequence { .. })
+        ));
+    }
+
     #[test]
     fn scan_valid_strings() {
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::String, 1, (0, 15), (1, 16)),
                 Token::new(TokenType::Eof, 1, (15, 15), (16, 16))
-            ]),
+            ])),
             Scanner::new("\"hello, world!\"").scan()
         );
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::String, 1, (0, 2), (1, 3)),
                 Token::new(TokenType::Eof, 1, (2, 2), (3, 3))
-            ]),
+            ])),
             Scanner::new("\"\"").scan()
         );
     }
@@ -545,26 +1839,26 @@ mod scanner_tests {
     #[test]
     fn scan_valid_keywords() {
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::True, 1, (0, 4), (1, 5)),
                 Token::new(TokenType::Eof, 1, (4, 4), (5, 5))
-            ]),
+            ])),
             Scanner::new("true").scan()
         );
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::False, 1, (0, 5), (1, 6)),
                 Token::new(TokenType::Eof, 1, (5, 5), (6, 6))
-            ]),
+            ])),
             Scanner::new("false").scan()
         );
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::Null, 1, (0, 4), (1, 5)),
                 Token::new(TokenType::Eof, 1, (4, 4), (5, 5))
-            ]),
+            ])),
             Scanner::new("null").scan()
         );
     }
@@ -576,7 +1870,7 @@ mod scanner_tests {
         let res = s.scan();
 
         assert_eq!(
-            Ok(vec![
+            Ok(Tokens::from(vec![
                 Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
                 Token::new(TokenType::RightBrace, 1, (1, 2), (2, 3)),
                 Token::new(TokenType::LeftBracket, 1, (2, 3), (3, 4)),
@@ -584,7 +1878,7 @@ mod scanner_tests {
                 Token::new(TokenType::Colon, 1, (4, 5), (5, 6)),
                 Token::new(TokenType::Comma, 1, (5, 6), (6, 7)),
                 Token::new(TokenType::Eof, 1, (6, 6), (7, 7)),
-            ]),
+            ])),
             res
         );
     }
@@ -605,10 +1899,231 @@ mod scanner_tests {
         assert_eq!(2, s.current)
     }
 
+    #[test]
+    fn iterate_tokens_lazily() {
+        let mut scanner = Scanner::new("[1, @]");
+        let mut s = scanner.tokens();
+
+        assert_eq!(
+            Some(Ok(Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)))),
+            s.next()
+        );
+        assert_eq!(
+            Some(Ok(Token::new(TokenType::Number, 1, (1, 2), (2, 3)))),
+            s.next()
+        );
+        assert_eq!(
+            Some(Ok(Token::new(TokenType::Comma, 1, (2, 3), (3, 4)))),
+            s.next()
+        );
+        assert!(matches!(
+            s.next(),
+            Some(Err(ScannerError::UnknownCharacter { .. }))
+        ));
+        assert_eq!(None, s.next());
+    }
+
+    #[test]
+    fn eof_after_trailing_newlines() {
+        assert_eq!(
+            Ok(Tokens::from(vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::Number, 2, (4, 5), (3, 4)),
+                Token::new(TokenType::Eof, 2, (5, 5), (4, 4)),
+            ])),
+            Scanner::new("[\n  1\n\n").scan()
+        );
+        assert_eq!(
+            Ok(Tokens::from(vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::Number, 2, (3, 4), (1, 2)),
+                Token::new(TokenType::Eof, 2, (4, 4), (2, 2)),
+            ])),
+            Scanner::new("[\r\n1\r\n \r\n").scan()
+        );
+        assert_eq!(
+            Ok(Tokens::from(vec![
+                Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+                Token::new(TokenType::Eof, 2, (10, 10), (4, 4)),
+            ])),
+            Scanner::new_jsonc("[ /* a\n */\n").scan()
+        );
+        assert_eq!(
+            Ok(Tokens::from(vec![Token::new(
+                TokenType::Eof,
+                1,
+                (0, 0),
+                (1, 1)
+            )])),
+            Scanner::new("\n\n").scan()
+        );
+    }
+
+    #[test]
+    fn scan_from_bytes() {
+        assert_eq!(
+            Scanner::new("{\"a\": [1, \"é\"]}").scan(),
+            Scanner::from_bytes("{\"a\": [1, \"é\"]}".as_bytes())
+                .unwrap()
+                .scan()
+        );
+        assert_eq!(
+            Scanner::new("\u{FEFF}null").scan(),
+            Scanner::from_bytes(b"\xef\xbb\xbfnull").unwrap().scan()
+        );
+
+        let position = |bytes: &[u8]| match Scanner::from_bytes(bytes) {
+            Err(ScannerError::InvalidUtf8 {
+                offset,
+                line,
+                column,
+                ..
+            }) => Some((offset, line, column)),
+            _ => None,
+        };
+
+        assert_eq!(Some((0, 1, 1)), position(b"\xff"));
+        assert_eq!(Some((7, 1, 5)), position(b"[\"\xc3\xa9\xe2\x82\xac\xc3"));
+        assert_eq!(Some((8, 3, 4)), position(b"[\n1,\n  \"\x80\"]"));
+        assert_eq!(Some((4, 1, 2)), position(b"\xef\xbb\xbf[\xed\xa0\x80]"));
+    }
+
+    #[test]
+    fn iterator_ends_after_eof() {
+        let mut scanner = Scanner::new("null");
+        let mut s = scanner.tokens();
+
+        assert_eq!(
+            Some(Ok(Token::new(TokenType::Null, 1, (0, 4), (1, 5)))),
+            s.next()
+        );
+        assert_eq!(
+            Some(Ok(Token::new(TokenType::Eof, 1, (4, 4), (5, 5)))),
+            s.next()
+        );
+        assert_eq!(None, s.next());
+    }
+
+    #[test]
+    fn enforce_limits_from_options() {
+        let options = ParseOptions {
+            max_input_size: Some(8),
+            max_string_length: Some(2),
+            ..ParseOptions::default()
+        };
+
+        assert_eq!(
+            Err(ScannerError::InputTooLarge {
+                error: "9 bytes exceeds the limit of 8 bytes".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
+            }),
+            Scanner::new("[1,2,3,4]").with_options(options).scan()
+        );
+
+        assert!(Scanner::new("[\"ab\"]")
+            .with_options(options)
+            .scan()
+            .is_ok());
+
+        let error = Scanner::new("[\"abc\"]")
+            .with_options(options)
+            .scan()
+            .unwrap_err();
+
+        assert_eq!("string_too_long", error.kind());
+        assert_eq!((1, 2, "\"abc"), error.position());
+    }
+
+    #[test]
+    fn apply_lenient_options() {
+        let options = ParseOptions {
+            allow_comments: true,
+            allow_single_quotes: true,
+            tab_width: 4,
+            ..ParseOptions::default()
+        };
+
+        let tokens = Scanner::new("/* a */\t'b'")
+            .with_options(options)
+            .scan()
+            .unwrap();
+
+        assert_eq!(
+            Token::new(TokenType::String, 1, (8, 11), (9, 12)),
+            tokens[0]
+        );
+    }
+
+    #[test]
+    fn skip_jsonc_comments() {
+        assert_eq!(
+            Ok(Tokens::from(vec![
+                Token::new(TokenType::LeftBracket, 2, (9, 10), (1, 2)),
+                Token::new(TokenType::Number, 2, (19, 20), (11, 12)),
+                Token::new(TokenType::RightBracket, 4, (27, 28), (1, 2)),
+                Token::new(TokenType::Eof, 4, (28, 28), (2, 2)),
+            ])),
+            Scanner::new_jsonc("// start\n[ /* a */ 1 /*\n*/\n]").scan()
+        );
+    }
+
+    #[test]
+    fn preserve_jsonc_comments() {
+        let options = ParseOptions {
+            allow_comments: true,
+            preserve_comments: true,
+            ..ParseOptions::default()
+        };
+
+        assert_eq!(
+            Ok(Tokens::from(vec![
+                Token::new(TokenType::Comment, 1, (0, 8), (1, 9)),
+                Token::new(TokenType::LeftBracket, 2, (9, 10), (1, 2)),
+                Token::new(TokenType::Comment, 2, (11, 18), (3, 10)),
+                Token::new(TokenType::Number, 2, (19, 20), (11, 12)),
+                Token::new(TokenType::Comment, 2, (21, 26), (13, 3)),
+                Token::new(TokenType::RightBracket, 4, (27, 28), (1, 2)),
+                Token::new(TokenType::Eof, 4, (28, 28), (2, 2)),
+            ])),
+            Scanner::new("// start\n[ /* a */ 1 /*\n*/\n]")
+                .with_options(options)
+                .scan()
+        );
+    }
+
+    #[test]
+    fn comments_are_errors_by_default() {
+        assert!(Scanner::new("// comment\n[]").scan().is_err());
+        assert!(Scanner::new("/* comment */ []").scan().is_err());
+    }
+
+    #[test]
+    fn error_on_unterminated_or_invalid_comment() {
+        assert!(matches!(
+            Scanner::new_jsonc("[] /* comment").scan(),
+            Err(ScannerError::UnterminatedComment { .. })
+        ));
+
+        assert!(matches!(
+            Scanner::new_jsonc("[] / comment").scan(),
+            Err(ScannerError::UnknownCharacter { .. })
+        ));
+    }
+
     #[test]
     fn scan_gives_error_if_source_is_empty() {
         let mut s = Scanner::new("");
 
-        assert_eq!(true, s.scan().is_err())
+        assert_eq!(
+            Err(ScannerError::EmptySource {
+                error: String::new(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
+            }),
+            s.scan()
+        );
     }
 }
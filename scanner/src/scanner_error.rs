@@ -1,42 +1,310 @@
-use std::{error::Error, fmt::Display};
+use alloc::string::String;
+use core::{error::Error, fmt::Display};
 
 #[derive(Debug, PartialEq)]
 pub enum ScannerError {
-    EmptySource { error: String },
-    UnknownCharacter { error: String },
-    UnknownLiteral { error: String },
-    UnterminatedString { error: String },
-    UnterminatedFractionalNumber { error: String },
-    LeadingZeros { error: String },
-    InvalidExponent { error: String },
-    InvalidNumber { error: String },
-    InvalidEscapeSequence { error: String },
-    InvalidUnicodeSequence { error: String },
+    EmptySource {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    UnknownCharacter {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    UnknownLiteral {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    UnterminatedString {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    UnterminatedComment {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    UnterminatedFractionalNumber {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    LeadingZeros {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    InvalidExponent {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    InvalidNumber {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    InvalidEscapeSequence {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    InvalidUnicodeSequence {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    InvalidSurrogatePair {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    InvalidNumberSign {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    InputTooLarge {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    StringTooLong {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    /// Raw character below U+0020 in a string, which JSON requires to be escaped
+    UnescapedControlCharacter {
+        code_point: u32,
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    /// Finite number that an `f64` cannot hold exactly, with `ParseOptions::reject_precision_loss`
+    PrecisionLoss {
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    /// Bytes given to `Scanner::from_bytes` that are not UTF-8, `offset` is the first invalid byte
+    InvalidUtf8 {
+        offset: usize,
+        error: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
 }
 
 impl Error for ScannerError {}
 
-impl Display for ScannerError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl ScannerError {
+    /// Stable identifier for each error used by machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::EmptySource { .. } => "empty_source",
+            Self::UnknownCharacter { .. } => "unknown_character",
+            Self::UnknownLiteral { .. } => "unknown_literal",
+            Self::UnterminatedString { .. } => "unterminated_string",
+            Self::UnterminatedComment { .. } => "unterminated_comment",
+            Self::UnterminatedFractionalNumber { .. } => "unterminated_fractional_number",
+            Self::LeadingZeros { .. } => "leading_zeros",
+            Self::InvalidExponent { .. } => "invalid_exponent",
+            Self::InvalidNumber { .. } => "invalid_number",
+            Self::InvalidEscapeSequence { .. } => "invalid_escape_sequence",
+            Self::InvalidUnicodeSequence { .. } => "invalid_unicode_sequence",
+            Self::InvalidSurrogatePair { .. } => "invalid_surrogate_pair",
+            Self::InvalidNumberSign { .. } => "invalid_number_sign",
+            Self::InputTooLarge { .. } => "input_too_large",
+            Self::StringTooLong { .. } => "string_too_long",
+            Self::UnescapedControlCharacter { .. } => "unescaped_control_character",
+            Self::PrecisionLoss { .. } => "precision_loss",
+            Self::InvalidUtf8 { .. } => "invalid_utf8",
+        }
+    }
+
+    /// Line number, display column and offending slice of the source
+    pub fn position(&self) -> (usize, usize, &str) {
         match self {
-            Self::EmptySource { error } => {
-                write!(f, "Empty source {}", error)
+            Self::EmptySource {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::UnknownCharacter {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::UnknownLiteral {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::UnterminatedString {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::UnterminatedComment {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::UnterminatedFractionalNumber {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::LeadingZeros {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::InvalidExponent {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::InvalidNumber {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::InvalidEscapeSequence {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::InvalidUnicodeSequence {
+                line,
+                column,
+                snippet,
+                ..
             }
-            Self::UnknownCharacter { error } => {
+            | Self::InvalidSurrogatePair {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::InvalidNumberSign {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::InputTooLarge {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::StringTooLong {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::UnescapedControlCharacter {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::PrecisionLoss {
+                line,
+                column,
+                snippet,
+                ..
+            }
+            | Self::InvalidUtf8 {
+                line,
+                column,
+                snippet,
+                ..
+            } => (*line, *column, snippet),
+        }
+    }
+}
+
+impl Display for ScannerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::EmptySource { .. } => write!(f, "Input is empty, expected a JSON value"),
+            Self::UnknownCharacter { error, .. } => {
                 write!(f, "Unknown character {}", error)
             }
-            Self::UnknownLiteral { error } => write!(f, "Unknown literal {}", error),
-            Self::UnterminatedString { error } => write!(f, "Unterminated string {}", error),
-            Self::UnterminatedFractionalNumber { error } => {
+            Self::UnknownLiteral { error, .. } => write!(f, "Unknown literal {}", error),
+            Self::UnterminatedString { error, .. } => write!(f, "Unterminated string {}", error),
+            Self::UnterminatedComment { error, .. } => write!(f, "Unterminated comment {}", error),
+            Self::UnterminatedFractionalNumber { error, .. } => {
                 write!(f, "Unterminated fractional number {}", error)
             }
-            Self::LeadingZeros { error } => write!(f, "Leading zeros {}", error),
-            Self::InvalidExponent { error } => write!(f, "Invalid exponent {}", error),
-            Self::InvalidNumber { error } => write!(f, "Invalid number {}", error),
-            Self::InvalidEscapeSequence { error } => write!(f, "Invalid escape sequence {}", error),
-            Self::InvalidUnicodeSequence { error } => {
+            Self::LeadingZeros { error, .. } => write!(f, "Leading zeros {}", error),
+            Self::InvalidExponent { error, .. } => write!(f, "Invalid exponent {}", error),
+            Self::InvalidNumber { error, .. } => write!(f, "Invalid number {}", error),
+            Self::InvalidEscapeSequence { error, .. } => {
+                write!(f, "Invalid escape sequence {}", error)
+            }
+            Self::InvalidUnicodeSequence { error, .. } => {
                 write!(f, "Invalid unicode sequence {}", error)
             }
+            Self::InvalidSurrogatePair { error, .. } => {
+                write!(f, "Invalid surrogate pair {}", error)
+            }
+            Self::InvalidNumberSign { error, .. } => {
+                write!(f, "Invalid number sign, only - is allowed {}", error)
+            }
+            Self::InputTooLarge { error, .. } => write!(f, "Input too large, {}", error),
+            Self::StringTooLong { error, .. } => write!(f, "String too long {}", error),
+            Self::UnescapedControlCharacter {
+                code_point, error, ..
+            } => write!(
+                f,
+                "Unescaped control character U+{:04X}, escape it as \\u{:04x} {}",
+                code_point, code_point, error
+            ),
+            Self::PrecisionLoss { error, .. } => {
+                write!(
+                    f,
+                    "Number cannot be held exactly by a 64-bit float {}",
+                    error
+                )
+            }
+            Self::InvalidUtf8 { error, .. } => write!(f, "Invalid UTF-8, {}", error),
         }
     }
 }
@@ -45,12 +313,57 @@ impl Display for ScannerError {
 mod scanner_error_tests {
     use super::*;
 
+    #[test]
+    fn expose_kind_and_position() {
+        let error = ScannerError::UnterminatedString {
+            error: "preview".to_string(),
+            line: 16,
+            column: 12,
+            snippet: "\"Hello,".to_string(),
+        };
+
+        assert_eq!("unterminated_string", error.kind());
+        assert_eq!((16, 12, "\"Hello,"), error.position());
+    }
+
+    #[test]
+    fn expect_unescaped_control_character_message() {
+        assert_eq!(
+            "Unescaped control character U+001B, escape it as \\u001b preview",
+            ScannerError::UnescapedControlCharacter {
+                code_point: 0x1B,
+                error: "preview".to_string(),
+                line: 1,
+                column: 3,
+                snippet: "\u{1b}".to_string(),
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn expect_invalid_utf8_message() {
+        let error = ScannerError::InvalidUtf8 {
+            offset: 4,
+            error: "byte 0xff at offset 4".to_string(),
+            line: 2,
+            column: 3,
+            snippet: String::new(),
+        };
+
+        assert_eq!("invalid_utf8", error.kind());
+        assert_eq!("Invalid UTF-8, byte 0xff at offset 4", error.to_string());
+    }
+
     #[test]
     fn expect_invaild_unicode_sequence_message() {
         assert_eq!(
             "Invalid unicode sequence \"\\uaaaa\"",
             ScannerError::InvalidUnicodeSequence {
-                error: "\"\\uaaaa\"".to_string()
+                error: "\"\\uaaaa\"".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
             }
             .to_string()
         );
@@ -61,7 +374,10 @@ mod scanner_error_tests {
         assert_eq!(
             "Invalid escape sequence \"\\\\e\"",
             ScannerError::InvalidEscapeSequence {
-                error: "\"\\\\e\"".to_string()
+                error: "\"\\\\e\"".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
             }
             .to_string()
         );
@@ -72,7 +388,10 @@ mod scanner_error_tests {
         assert_eq!(
             "Invalid number 0.2e",
             ScannerError::InvalidNumber {
-                error: "0.2e".to_string()
+                error: "0.2e".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
             }
             .to_string()
         );
@@ -83,7 +402,24 @@ mod scanner_error_tests {
         assert_eq!(
             "Invalid exponent 20Ee",
             ScannerError::InvalidExponent {
-                error: "20Ee".to_string()
+                error: "20Ee".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn expect_invalid_number_sign_message() {
+        assert_eq!(
+            "Invalid number sign, only - is allowed +5",
+            ScannerError::InvalidNumberSign {
+                error: "+5".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
             }
             .to_string()
         );
@@ -94,7 +430,10 @@ mod scanner_error_tests {
         assert_eq!(
             "Leading zeros 00.42",
             ScannerError::LeadingZeros {
-                error: "00.42".to_string()
+                error: "00.42".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
             }
             .to_string()
         );
@@ -105,7 +444,10 @@ mod scanner_error_tests {
         assert_eq!(
             "Unterminated fractional number 100.",
             ScannerError::UnterminatedFractionalNumber {
-                error: "100.".to_string()
+                error: "100.".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
             }
             .to_string()
         );
@@ -116,7 +458,24 @@ mod scanner_error_tests {
         assert_eq!(
             "Unterminated string \"hello",
             ScannerError::UnterminatedString {
-                error: "\"hello".to_string()
+                error: "\"hello".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn expect_unterminated_comment_message() {
+        assert_eq!(
+            "Unterminated comment /* note",
+            ScannerError::UnterminatedComment {
+                error: "/* note".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
             }
             .to_string()
         );
@@ -127,7 +486,10 @@ mod scanner_error_tests {
         assert_eq!(
             "Unknown literal hello",
             ScannerError::UnknownLiteral {
-                error: "hello".to_string()
+                error: "hello".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
             }
             .to_string()
         );
@@ -138,7 +500,10 @@ mod scanner_error_tests {
         assert_eq!(
             "Unknown character @",
             ScannerError::UnknownCharacter {
-                error: "@".to_string()
+                error: "@".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
             }
             .to_string()
         );
@@ -147,9 +512,12 @@ mod scanner_error_tests {
     #[test]
     fn expect_empty_source_message() {
         assert_eq!(
-            "Empty source ",
+            "Input is empty, expected a JSON value",
             ScannerError::EmptySource {
-                error: "".to_string()
+                error: "".to_string(),
+                line: 1,
+                column: 1,
+                snippet: String::new(),
             }
             .to_string()
         );
@@ -4,6 +4,9 @@ pub use format::formatter::Formatter;
 pub use parser::parser::Parser;
 pub use scanner::scanner::Scanner;
 
+#[cfg(test)]
+mod roundtrip;
+
 #[test]
 fn scan_parse_format() {
     let source = "[]";
@@ -29,3 +32,108 @@ fn scan_parse_format() {
 
     assert_eq!("[]", json);
 }
+
+#[test]
+fn parse_with_single_call() {
+    assert_eq!(
+        "[]",
+        Formatter::default().format(&jtools::parse("[]").unwrap())
+    );
+    assert!(jtools::parse("[").is_err());
+}
+
+#[test]
+fn canonical_format_round_trip() {
+    let documents = [
+        "{\"name\":\"\\u006Atools\",\"size\":1.0e2,\"tags\":[\"\\uD83D\\uDE00\",-0.0,true]}",
+        "{ \"name\": \"jtools\", \"size\": 100, \"tags\": [\"😀\", -0E0, true] }",
+    ];
+
+    let formatted = documents.map(|source| {
+        let tokens = Scanner::new(source).scan().unwrap();
+        let parser = Parser::new(source, tokens);
+        let ast = parser.parse().unwrap();
+
+        Formatter::canonical().format(&ast)
+    });
+
+    assert_eq!(formatted[0], formatted[1]);
+
+    let tokens = Scanner::new(&formatted[0]).scan().unwrap();
+    let parser = Parser::new(&formatted[0], tokens);
+    let ast = parser.parse().unwrap();
+
+    assert_eq!(formatted[0], Formatter::canonical().format(&ast));
+}
+
+#[test]
+fn best_effort_parse_of_truncated_read() {
+    let source = "{\"logs\": [{\"id\": 1}, {\"id\": 2, \"message\": \"disk fu";
+
+    let mut scanner = Scanner::new(source);
+    let tokens: Vec<_> = scanner.tokens().map_while(Result::ok).collect();
+
+    let (ast, error) = Parser::new(source, tokens).parse_best_effort();
+
+    assert_eq!(
+        "{\"logs\":[{\"id\":1},{\"id\":2}]}",
+        format::minifier::Minifier::default().minify(&ast.unwrap())
+    );
+    assert_eq!(Some("unexpected_token"), error.map(|error| error.kind()));
+}
+
+#[test]
+fn end_of_input_error_points_at_last_line() {
+    for source in ["{\n  \"a\": [1,\n", "{\r\n  \"a\": [1,\r\n\r\n"] {
+        let tokens = Scanner::new(source).scan().unwrap();
+        let error = Parser::new(source, tokens).parse().unwrap_err();
+
+        assert_eq!((2, 11, ""), error.position());
+    }
+}
+
+#[test]
+fn edit_value_preserving_layout() {
+    let source = "// server\n{\n  \"host\": \"localhost\", /* default */\n  \"port\":   8080\n}\n";
+    let options = token::parse_options::ParseOptions {
+        allow_comments: true,
+        preserve_comments: true,
+        ..Default::default()
+    };
+
+    let tokens = Scanner::new(source).with_options(options).scan().unwrap();
+    let mut ast = Parser::new(source, tokens.clone())
+        .with_options(options)
+        .parse()
+        .unwrap();
+
+    assert_eq!(
+        source,
+        format::preserving::format_preserving(source, &tokens, &ast)
+    );
+
+    if let ast::node::Node::Object(properties) = &mut ast {
+        if let ast::node::Node::Property(_, value) = &mut properties[1] {
+            **value = ast::node::Node::Literal("9090");
+        }
+    }
+
+    assert_eq!(
+        source.replace("8080", "9090"),
+        format::preserving::format_preserving(source, &tokens, &ast)
+    );
+}
+
+#[test]
+fn parse_concatenated_documents() {
+    let source = "{\"a\":1} {\"b\":2}[3]\n\"x\"\t\n";
+    let tokens = Scanner::new(source).scan().unwrap();
+    let minifier = format::minifier::Minifier::default();
+
+    let documents: Vec<_> = Parser::new(source, tokens)
+        .parse_stream()
+        .map(|document| minifier.minify(&document.unwrap()))
+        .collect();
+
+    assert_eq!(vec!["{\"a\":1}", "{\"b\":2}", "[3]", "\"x\""], documents);
+}
@@ -0,0 +1,374 @@
+//! Round trip tests over generated JSON
+//!
+//! Each case generates a random document together with the values it is expected to decode to,
+//! then checks that scanning and parsing accept it, that every literal decodes to the generated
+//! value, that writing it back preserving its layout reproduces it and that minified, formatted,
+//! canonical and ASCII output parses back to the same values.
+//! Documents come from a seeded generator so a failing case can be reproduced from its seed.
+
+use ast::node::Node;
+use format::{
+    formatter::Formatter, literal::canonical_literal, minifier::Minifier,
+    preserving::format_preserving,
+};
+use token::escape::unescape_json_string;
+
+const CASES: u64 = 2000;
+const MAX_DEPTH: usize = 4;
+
+/// Characters written as is inside generated strings, including wide and multi-codepoint graphemes
+const RAW: [&str; 14] = [
+    "a",
+    "Z",
+    "0",
+    " ",
+    "~",
+    "/",
+    "\u{7f}",
+    "é",
+    "e\u{301}",
+    "漢",
+    "😀",
+    "👩\u{200d}👩\u{200d}👧",
+    "\u{2028}",
+    "\u{fe0f}",
+];
+
+/// Expected value of a generated document
+#[derive(Debug)]
+enum Value {
+    Keyword(&'static str),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+/// xorshift64* generator, good enough to drive test cases without an external crate
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn chance(&mut self, one_in: usize) -> bool {
+        self.below(one_in) == 0
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+}
+
+fn whitespace(rng: &mut Rng, json: &mut String) {
+    while rng.chance(3) {
+        json.push_str(rng.pick(&[" ", "\t", "\n", "\r\n"]));
+    }
+}
+
+fn value(rng: &mut Rng, depth: usize, json: &mut String) -> Value {
+    let kinds = if depth >= MAX_DEPTH { 3 } else { 5 };
+
+    match rng.below(kinds) {
+        0 => {
+            let keyword = rng.pick(&["true", "false", "null"]);
+            json.push_str(keyword);
+            Value::Keyword(keyword)
+        }
+        1 => number(rng, json),
+        2 => Value::String(string(rng, json)),
+        3 => {
+            let mut elements = vec![];
+            json.push('[');
+            whitespace(rng, json);
+
+            for index in 0..rng.below(4) {
+                if index > 0 {
+                    json.push(',');
+                    whitespace(rng, json);
+                }
+
+                elements.push(value(rng, depth + 1, json));
+                whitespace(rng, json);
+            }
+
+            json.push(']');
+            Value::Array(elements)
+        }
+        _ => {
+            let mut properties = vec![];
+            json.push('{');
+            whitespace(rng, json);
+
+            for index in 0..rng.below(4) {
+                if index > 0 {
+                    json.push(',');
+                    whitespace(rng, json);
+                }
+
+                // Duplicate keys are rejected by the parser, so a repeated key is written again
+                let start = json.len();
+                let mut key = string(rng, json);
+
+                while properties.iter().any(|(existing, _)| *existing == key) {
+                    json.truncate(start);
+                    key = string(rng, json);
+                }
+
+                whitespace(rng, json);
+                json.push(':');
+                whitespace(rng, json);
+                properties.push((key, value(rng, depth + 1, json)));
+                whitespace(rng, json);
+            }
+
+            json.push('}');
+            Value::Object(properties)
+        }
+    }
+}
+
+/// Write a number literal, regenerating numbers that overflow `f64` as the scanner rejects them
+fn number(rng: &mut Rng, json: &mut String) -> Value {
+    let start = json.len();
+
+    loop {
+        json.truncate(start);
+        number_literal(rng, json);
+
+        match json[start..].parse::<f64>() {
+            Ok(number) if number.is_finite() => return Value::Number(number),
+            _ => continue,
+        }
+    }
+}
+
+fn number_literal(rng: &mut Rng, json: &mut String) {
+    if rng.chance(2) {
+        json.push('-');
+    }
+
+    if rng.chance(3) {
+        json.push('0');
+    } else {
+        json.push(char::from(b'1' + rng.below(9) as u8));
+        digits(rng, json, 20);
+    }
+
+    if rng.chance(2) {
+        json.push('.');
+        json.push(char::from(b'0' + rng.below(10) as u8));
+        digits(rng, json, 20);
+    }
+
+    if rng.chance(2) {
+        json.push_str(rng.pick(&["e", "E", "e+", "E-", "e-"]));
+        json.push(char::from(b'0' + rng.below(10) as u8));
+        let max = if rng.chance(10) { 3 } else { 1 };
+        digits(rng, json, max);
+    }
+}
+
+fn digits(rng: &mut Rng, json: &mut String, max: usize) {
+    for _ in 0..rng.below(max + 1) {
+        json.push(char::from(b'0' + rng.below(10) as u8));
+    }
+}
+
+/// Write a string literal using every kind of escape and return the string it decodes to
+fn string(rng: &mut Rng, json: &mut String) -> String {
+    let mut value = String::new();
+    json.push('"');
+
+    for _ in 0..rng.below(8) {
+        match rng.below(6) {
+            0 => {
+                let (escape, char) = [
+                    ("\\\"", '"'),
+                    ("\\\\", '\\'),
+                    ("\\/", '/'),
+                    ("\\b", '\u{8}'),
+                    ("\\f", '\u{c}'),
+                    ("\\n", '\n'),
+                    ("\\r", '\r'),
+                    ("\\t", '\t'),
+                ][rng.below(8)];
+
+                json.push_str(escape);
+                value.push(char);
+            }
+            1 => {
+                let char = loop {
+                    let code_point = if rng.chance(2) {
+                        rng.below(0x80)
+                    } else {
+                        rng.below(0x10000)
+                    };
+
+                    if let Some(char) = char::from_u32(code_point as u32) {
+                        break char;
+                    }
+                };
+
+                json.push_str(&unicode_escape(rng, char as u32));
+                value.push(char);
+            }
+            2 => {
+                let char = char::from_u32(0x10000 + rng.below(0x100000) as u32).unwrap();
+
+                for unit in char.encode_utf16(&mut [0; 2]) {
+                    json.push_str(&unicode_escape(rng, *unit as u32));
+                }
+
+                value.push(char);
+            }
+            _ => {
+                let raw = rng.pick(&RAW);
+                json.push_str(raw);
+                value.push_str(raw);
+            }
+        }
+    }
+
+    json.push('"');
+    value
+}
+
+fn unicode_escape(rng: &mut Rng, code_unit: u32) -> String {
+    if rng.chance(2) {
+        format!("\\u{:04x}", code_unit)
+    } else {
+        format!("\\u{:04X}", code_unit)
+    }
+}
+
+/// Assert that `node` holds the generated `value`
+fn assert_value(seed: u64, value: &Value, node: &Node) {
+    match (value, node) {
+        (Value::Keyword(keyword), Node::Literal(literal)) => {
+            assert_eq!(keyword, literal, "seed {}", seed)
+        }
+        (Value::Number(number), Node::Literal(literal)) => {
+            assert_eq!(Ok(*number), literal.parse::<f64>(), "seed {}", seed)
+        }
+        (Value::String(string), Node::Literal(literal)) => {
+            assert_eq!(
+                Ok(string),
+                unescape_json_string(literal).as_ref(),
+                "seed {}",
+                seed
+            )
+        }
+        (Value::Array(elements), Node::Array(nodes)) => {
+            assert_eq!(elements.len(), nodes.len(), "seed {}", seed);
+
+            for (element, node) in elements.iter().zip(nodes) {
+                assert_value(seed, element, node);
+            }
+        }
+        (Value::Object(properties), Node::Object(nodes)) => {
+            assert_eq!(properties.len(), nodes.len(), "seed {}", seed);
+
+            for ((key, value), node) in properties.iter().zip(nodes) {
+                let Node::Property(key_node, value_node) = node else {
+                    panic!("seed {}: expected property, found {:?}", seed, node);
+                };
+
+                assert_value(seed, &Value::String(key.clone()), key_node);
+                assert_value(seed, value, value_node);
+            }
+        }
+        _ => panic!("seed {}: expected {:?}, found {:?}", seed, value, node),
+    }
+}
+
+/// Parse `json` and assert it holds `value`
+fn assert_reparse(seed: u64, value: &Value, json: &str) {
+    match jtools::parse(json) {
+        Ok(ast) => assert_value(seed, value, &ast),
+        Err(error) => panic!("seed {}: {} in {:?}", seed, error, json),
+    }
+}
+
+#[test]
+fn generated_documents_round_trip() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let mut source = String::new();
+
+        whitespace(&mut rng, &mut source);
+        let value = value(&mut rng, 0, &mut source);
+        whitespace(&mut rng, &mut source);
+
+        assert_reparse(seed, &value, &source);
+
+        let ast = jtools::parse(&source).unwrap();
+        let tokens = scanner::scanner::Scanner::new(&source).scan().unwrap();
+
+        assert_eq!(
+            source,
+            format_preserving(&source, &tokens, &ast),
+            "seed {}",
+            seed
+        );
+
+        for minifier in [
+            Minifier::default(),
+            Minifier::default().with_ensure_ascii(true),
+        ] {
+            let minified = minifier.minify(&ast);
+
+            assert_reparse(seed, &value, &minified);
+            assert_eq!(
+                minified,
+                minifier.minify(&jtools::parse(&minified).unwrap())
+            );
+        }
+
+        for formatter in [
+            Formatter::default(),
+            Formatter::default().with_ensure_ascii(true),
+            Formatter::default().with_align_values(true),
+            Formatter::canonical(),
+            Formatter::canonical().with_ensure_ascii(true),
+        ] {
+            let formatted = formatter.format(&ast);
+
+            assert_reparse(seed, &value, &formatted);
+            assert_eq!(
+                formatted,
+                formatter.format(&jtools::parse(&formatted).unwrap())
+            );
+        }
+    }
+}
+
+#[test]
+fn canonical_literals_are_stable() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let mut source = String::new();
+
+        let value = match rng.below(2) {
+            0 => Value::String(string(&mut rng, &mut source)),
+            _ => number(&mut rng, &mut source),
+        };
+
+        let canonical = canonical_literal(&source);
+
+        assert_reparse(seed, &value, &canonical);
+        assert_eq!(canonical, canonical_literal(&canonical), "seed {}", seed);
+    }
+}
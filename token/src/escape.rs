@@ -0,0 +1,203 @@
+use alloc::{format, string::String};
+use core::{error::Error, fmt::Display, str::CharIndices};
+
+/// Convert between Rust strings and JSON string literals
+///
+/// ## Description
+///
+/// `escape_json_string` wraps a string in quotes and only escapes what JSON requires: `"`, `\`,
+/// and control characters. Control characters with a short escape use it, and the others use
+/// lowercase `\u00xx`. Every other character is written as is.
+///
+/// `unescape_json_string` is the reverse. It accepts the same escape sequences as the scanner,
+/// including surrogate pairs such as `\uD83D\uDE00`, and returns an error for a literal the
+/// scanner would reject or a lone surrogate that has no `char`.
+///
+/// ## Examples
+///
+/// ```
+/// use token::escape::{escape_json_string, unescape_json_string};
+///
+/// assert_eq!("\"say \\\"hi\\\"\\n\"", escape_json_string("say \"hi\"\n"));
+/// assert_eq!(Ok("A😀".to_string()), unescape_json_string("\"\\u0041\\uD83D\\uDE00\""));
+/// ```
+pub fn escape_json_string(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('"');
+
+    for char in value.chars() {
+        match char {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\u{08}' => literal.push_str("\\b"),
+            '\u{0C}' => literal.push_str("\\f"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            char if char < '\u{20}' => literal.push_str(&format!("\\u{:04x}", char as u32)),
+            char => literal.push(char),
+        }
+    }
+
+    literal.push('"');
+    literal
+}
+
+/// Decode a quoted JSON string literal into the string it represents
+pub fn unescape_json_string(literal: &str) -> Result<String, UnescapeError> {
+    let inner = literal
+        .strip_prefix('"')
+        .and_then(|literal| literal.strip_suffix('"'))
+        .ok_or(UnescapeError::MissingQuotes)?;
+
+    let mut value = String::with_capacity(inner.len());
+    let mut chars = inner.char_indices();
+
+    while let Some((index, char)) = chars.next() {
+        if char != '\\' {
+            value.push(char);
+            continue;
+        }
+
+        // Indices are reported relative to the literal including its opening quote
+        let index = index + 1;
+
+        let escaped = match chars.next() {
+            Some((_, '"')) => '"',
+            Some((_, '\\')) => '\\',
+            Some((_, '/')) => '/',
+            Some((_, 'b')) => '\u{08}',
+            Some((_, 'f')) => '\u{0C}',
+            Some((_, 'n')) => '\n',
+            Some((_, 'r')) => '\r',
+            Some((_, 't')) => '\t',
+            Some((_, 'u')) => {
+                let high = code_unit(&mut chars, index)?;
+
+                match high {
+                    0xD800..=0xDBFF => {
+                        if !matches!(
+                            (chars.next(), chars.next()),
+                            (Some((_, '\\')), Some((_, 'u')))
+                        ) {
+                            return Err(UnescapeError::InvalidSurrogatePair { index });
+                        }
+
+                        let low = code_unit(&mut chars, index)?;
+
+                        if !(0xDC00..=0xDFFF).contains(&low) {
+                            return Err(UnescapeError::InvalidSurrogatePair { index });
+                        }
+
+                        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+                            .ok_or(UnescapeError::InvalidSurrogatePair { index })?
+                    }
+                    _ => {
+                        char::from_u32(high).ok_or(UnescapeError::InvalidSurrogatePair { index })?
+                    }
+                }
+            }
+            _ => return Err(UnescapeError::InvalidEscapeSequence { index }),
+        };
+
+        value.push(escaped);
+    }
+
+    Ok(value)
+}
+
+/// Four hex digits following `\u` as a UTF-16 code unit
+fn code_unit(chars: &mut CharIndices, index: usize) -> Result<u32, UnescapeError> {
+    (0..4).try_fold(0, |code_unit, _| {
+        chars
+            .next()
+            .and_then(|(_, char)| char.to_digit(16))
+            .map(|digit| code_unit * 16 + digit)
+            .ok_or(UnescapeError::InvalidUnicodeSequence { index })
+    })
+}
+
+/// Reason a literal could not be unescaped, with the byte index of the offending escape
+#[derive(Debug, PartialEq)]
+pub enum UnescapeError {
+    MissingQuotes,
+    InvalidEscapeSequence { index: usize },
+    InvalidUnicodeSequence { index: usize },
+    InvalidSurrogatePair { index: usize },
+}
+
+impl Error for UnescapeError {}
+
+impl Display for UnescapeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingQuotes => write!(f, "String literal must be wrapped in double quotes"),
+            Self::InvalidEscapeSequence { index } => {
+                write!(f, "Invalid escape sequence at index {}", index)
+            }
+            Self::InvalidUnicodeSequence { index } => {
+                write!(f, "Invalid unicode sequence at index {}", index)
+            }
+            Self::InvalidSurrogatePair { index } => {
+                write!(f, "Invalid surrogate pair at index {}", index)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod escape_tests {
+    use super::*;
+
+    #[test]
+    fn escape_and_unescape_round_trip() {
+        for value in [
+            "",
+            "plain",
+            "\"quoted\"",
+            "back\\slash",
+            "\u{1}\u{8}\u{c}\n\r\t",
+            "😀é/",
+        ] {
+            assert_eq!(
+                Ok(value.to_string()),
+                unescape_json_string(&escape_json_string(value))
+            );
+        }
+
+        assert_eq!("\"\\u0001\\b\"", escape_json_string("\u{1}\u{8}"));
+        assert_eq!("\"😀/\"", escape_json_string("😀/"));
+    }
+
+    #[test]
+    fn unescape_all_sequences() {
+        assert_eq!(
+            Ok("\"\\/\u{8}\u{c}\n\r\tAé😀".to_string()),
+            unescape_json_string(r#""\"\\\/\b\f\n\r\t\u0041\u00E9\uD83D\uDE00""#)
+        );
+    }
+
+    #[test]
+    fn error_on_invalid_literals() {
+        assert_eq!(
+            Err(UnescapeError::MissingQuotes),
+            unescape_json_string("abc")
+        );
+        assert_eq!(
+            Err(UnescapeError::InvalidEscapeSequence { index: 2 }),
+            unescape_json_string(r#""a\e""#)
+        );
+        assert_eq!(
+            Err(UnescapeError::InvalidUnicodeSequence { index: 1 }),
+            unescape_json_string(r#""\u12G4""#)
+        );
+        assert_eq!(
+            Err(UnescapeError::InvalidSurrogatePair { index: 1 }),
+            unescape_json_string(r#""\uD83D""#)
+        );
+        assert_eq!(
+            Err(UnescapeError::InvalidSurrogatePair { index: 1 }),
+            unescape_json_string(r#""\uDE00\uD83D""#)
+        );
+    }
+}
@@ -1,2 +1,10 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod escape;
+pub mod number;
+pub mod parse_options;
 pub mod token;
 pub mod token_type;
+pub mod tokens;
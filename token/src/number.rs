@@ -0,0 +1,96 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+/// Compare JSON number literals by the exact decimal value they are written with
+///
+/// ## Description
+///
+/// `decimal` reduces a number literal to its sign, significant digits and power of ten, so
+/// spellings of the same value such as `1.0e2`, `100` and `10E1` compare equal without going
+/// through an `f64`.
+///
+/// `round_trips` checks whether a literal survives being read as an `f64` and written back. A
+/// finite `f64` is written with the shortest digits that parse back to it, so a literal round
+/// trips when those digits have the same exact decimal value as the literal. `0.1` round trips
+/// even though its `f64` is not exactly one tenth, while `9007199254740993`, one past the largest
+/// integer an `f64` holds exactly, is read as `9007199254740992` and does not.
+///
+/// ## Examples
+///
+/// ```
+/// use token::number::{decimal, round_trips};
+///
+/// assert_eq!(decimal("1.0e2"), decimal("100"));
+/// assert!(round_trips("0.1"));
+/// assert!(!round_trips("9007199254740993"));
+/// ```
+pub fn decimal(number: &str) -> Option<(bool, String, i64)> {
+    let (negative, number) = match number.strip_prefix('-') {
+        Some(number) => (true, number),
+        None => (false, number),
+    };
+
+    let (mantissa, exponent) = match number.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().ok()?),
+        None => (number, 0),
+    };
+
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let digits = format!("{}{}", integer, fraction);
+    let digits = digits.trim_start_matches('0');
+    let significant = digits.trim_end_matches('0');
+
+    if significant.is_empty() {
+        return Some((negative, String::new(), 0));
+    }
+
+    let exponent = exponent - fraction.len() as i64 + (digits.len() - significant.len()) as i64;
+
+    Some((negative, significant.to_string(), exponent))
+}
+
+/// Whether a number literal keeps its exact value when read as a finite `f64` and written back
+pub fn round_trips(literal: &str) -> bool {
+    let Some(number) = literal
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+    else {
+        return false;
+    };
+
+    match (decimal(literal), decimal(&format!("{:e}", number))) {
+        (Some(literal), Some(written)) => literal == written,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod number_tests {
+    use super::*;
+
+    #[test]
+    fn decimal_value() {
+        assert_eq!(Some((false, "1".to_string(), 2)), decimal("1.0e2"));
+        assert_eq!(Some((true, "25".to_string(), -2)), decimal("-0.25"));
+        assert_eq!(Some((true, String::new(), 0)), decimal("-0.0"));
+        assert_eq!(decimal("100"), decimal("10E1"));
+    }
+
+    #[test]
+    fn detect_precision_loss() {
+        assert!(round_trips("9007199254740992"));
+        assert!(round_trips("0.1"));
+        assert!(round_trips("-0"));
+        assert!(round_trips("1.0e2"));
+        assert!(round_trips("1.7976931348623157e308"));
+
+        assert!(!round_trips("9007199254740993"));
+        assert!(!round_trips("0.30000000000000001"));
+        assert!(!round_trips("12345678901234567890"));
+        assert!(!round_trips("1e400"));
+    }
+}
@@ -0,0 +1,101 @@
+/// Scanner and parser settings collected in one place
+///
+/// ## Description
+///
+/// `ParseOptions::default()` is strict JSON as described by RFC 8259 with a nesting limit of 128,
+/// no comments, only double quoted strings and an error for duplicate keys. Input size and string
+/// length are unlimited by default and should be capped when parsing untrusted input.
+///
+/// The same options are passed to `Scanner::with_options` and `Parser::with_options`, each of
+/// which reads only the settings it is responsible for.
+///
+/// ## Examples
+///
+/// ```
+/// use token::parse_options::{DuplicateKeys, ParseOptions};
+///
+/// let options = ParseOptions {
+///     max_depth: 32,
+///     max_input_size: Some(1024 * 1024),
+///     max_string_length: Some(4096),
+///     duplicate_keys: DuplicateKeys::Error,
+///     ..ParseOptions::default()
+/// };
+///
+/// assert!(!options.allow_comments);
+/// assert_eq!(1, options.tab_width);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParseOptions {
+    /// Skip `//` and `/* */` comments as in JSONC
+    pub allow_comments: bool,
+    /// Emit allowed comments as `TokenType::Comment` tokens instead of skipping them
+    pub preserve_comments: bool,
+    /// Accept strings wrapped in single quotes
+    pub allow_single_quotes: bool,
+    /// Accept JSON5 style identifier keys such as `{name: "x"}`
+    pub allow_unquoted_keys: bool,
+    /// Accept escapes of a whole code point such as `\u{1F600}` in strings
+    pub allow_braced_unicode_escapes: bool,
+    /// Accept `NaN`, `Infinity`, `-Infinity` and numbers too large for an `f64` as numbers
+    pub allow_non_finite_numbers: bool,
+    /// Reject finite numbers whose exact value an `f64` cannot hold, such as `9007199254740993`
+    pub reject_precision_loss: bool,
+    /// Columns a tab advances to when reporting error positions
+    pub tab_width: usize,
+    /// Maximum size of the source in bytes
+    pub max_input_size: Option<usize>,
+    /// Maximum length in bytes of a string between its quotes, escape sequences as written
+    pub max_string_length: Option<usize>,
+    /// Maximum nesting of objects and arrays
+    pub max_depth: usize,
+    pub duplicate_keys: DuplicateKeys,
+    pub case_insensitive_keys: bool,
+    /// Type the top-level value must have
+    pub require_root: RootKind,
+    /// Underline the span in error previews with ANSI escape codes, for errors shown on a
+    /// terminal as text
+    pub colored_errors: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            allow_comments: false,
+            preserve_comments: false,
+            allow_single_quotes: false,
+            allow_unquoted_keys: false,
+            allow_braced_unicode_escapes: false,
+            allow_non_finite_numbers: false,
+            reject_precision_loss: false,
+            tab_width: 1,
+            max_input_size: None,
+            max_string_length: None,
+            max_depth: 128,
+            duplicate_keys: DuplicateKeys::default(),
+            case_insensitive_keys: false,
+            require_root: RootKind::default(),
+            colored_errors: false,
+        }
+    }
+}
+
+/// How the parser handles an object key that appears more than once
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum DuplicateKeys {
+    #[default]
+    Error,
+    LastWins,
+    KeepAll,
+    /// Keep the last value as with `LastWins` and report each duplicate as a warning
+    Warn,
+}
+
+/// Type of top-level value accepted by the parser
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum RootKind {
+    #[default]
+    Any,
+    Object,
+    Array,
+}
@@ -18,7 +18,7 @@ use crate::token_type::TokenType;
 /// let token_left_bracket = Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2));
 /// let token_true = Token::new(TokenType::True, 1, (2, 6), (3, 7));
 /// ```
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Token {
     pub token_type: TokenType,
     pub line_number: usize,
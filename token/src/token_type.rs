@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use core::fmt::Display;
 
 /// A type given to a token during scanning.
 ///
@@ -21,7 +21,7 @@ use std::fmt::Display;
 /// let left_bracket = TokenType::LeftBrace;
 /// let end_of_file = TokenType::Eof;
 /// ```
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
     LeftBrace,
     RightBrace,
@@ -34,11 +34,46 @@ pub enum TokenType {
     True,
     False,
     Null,
+    /// Bare object key such as `name` in `{name: "x"}`, only scanned when unquoted keys are allowed
+    Identifier,
+    /// `//` or `/* */` comment, only scanned when comments are allowed and preserved
+    Comment,
     Eof,
 }
 
+impl TokenType {
+    /// Token type for a symbol or keyword, the reverse of `Display`
+    ///
+    /// ```
+    /// use token::token_type::TokenType;
+    ///
+    /// assert_eq!(Some(TokenType::LeftBrace), TokenType::from_symbol("{"));
+    /// assert_eq!(Some(TokenType::Null), TokenType::from_symbol("null"));
+    /// assert_eq!(None, TokenType::from_symbol("nil"));
+    /// ```
+    pub fn from_symbol(symbol: &str) -> Option<TokenType> {
+        match symbol {
+            "{" => Some(TokenType::LeftBrace),
+            "}" => Some(TokenType::RightBrace),
+            "[" => Some(TokenType::LeftBracket),
+            "]" => Some(TokenType::RightBracket),
+            ":" => Some(TokenType::Colon),
+            "," => Some(TokenType::Comma),
+            "string" => Some(TokenType::String),
+            "number" => Some(TokenType::Number),
+            "true" => Some(TokenType::True),
+            "false" => Some(TokenType::False),
+            "null" => Some(TokenType::Null),
+            "identifier" => Some(TokenType::Identifier),
+            "comment" => Some(TokenType::Comment),
+            "eof" => Some(TokenType::Eof),
+            _ => None,
+        }
+    }
+}
+
 impl Display for TokenType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             TokenType::LeftBrace => write!(f, "{{"),
             TokenType::RightBrace => write!(f, "}}"),
@@ -51,7 +86,41 @@ impl Display for TokenType {
             TokenType::True => write!(f, "true"),
             TokenType::False => write!(f, "false"),
             TokenType::Null => write!(f, "null"),
+            TokenType::Identifier => write!(f, "identifier"),
+            TokenType::Comment => write!(f, "comment"),
             TokenType::Eof => write!(f, "eof"),
         }
     }
 }
+
+#[cfg(test)]
+mod token_type_tests {
+    use super::*;
+
+    #[test]
+    fn reverse_display() {
+        for token_type in [
+            TokenType::LeftBrace,
+            TokenType::RightBrace,
+            TokenType::LeftBracket,
+            TokenType::RightBracket,
+            TokenType::Colon,
+            TokenType::Comma,
+            TokenType::String,
+            TokenType::Number,
+            TokenType::True,
+            TokenType::False,
+            TokenType::Null,
+            TokenType::Identifier,
+            TokenType::Comment,
+            TokenType::Eof,
+        ] {
+            assert_eq!(
+                Some(&token_type),
+                TokenType::from_symbol(&token_type.to_string()).as_ref()
+            );
+        }
+
+        assert_eq!(None, TokenType::from_symbol("True"));
+    }
+}
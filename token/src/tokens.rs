@@ -0,0 +1,156 @@
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::{token::Token, token_type::TokenType};
+
+/// Tokens of a single source in the order they were scanned
+///
+/// ## Description
+///
+/// A thin wrapper around `Vec<Token>` returned by `Scanner::scan` and accepted by `Parser::new`.
+/// It dereferences to a slice so it can be iterated and indexed like the vector.
+///
+/// Scanned tokens are sorted by their indices and end with an `Eof` token. Tokens built by hand,
+/// as in tests, can be converted from a `Vec<Token>` without these checks so `ends_with_eof` and
+/// `is_monotonic` are provided to verify them.
+///
+/// ## Examples
+///
+/// ```
+/// use token::{token::Token, token_type::TokenType, tokens::Tokens};
+///
+/// let source = "[true]";
+///
+/// let tokens = Tokens::from(vec![
+///     Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+///     Token::new(TokenType::True, 1, (1, 5), (2, 6)),
+///     Token::new(TokenType::RightBracket, 1, (5, 6), (6, 7)),
+///     Token::new(TokenType::Eof, 1, (6, 6), (7, 7)),
+/// ]);
+///
+/// assert_eq!(Some(&TokenType::True), tokens.token_at(3).map(|token| &token.token_type));
+/// assert_eq!(4, tokens.len());
+/// assert!(tokens.ends_with_eof() && tokens.is_monotonic());
+/// assert_eq!(Some((1, 7)), tokens.last_position());
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Tokens(Vec<Token>);
+
+impl Tokens {
+    /// Whether the last token is `Eof`, as it always is for scanned tokens
+    pub fn ends_with_eof(&self) -> bool {
+        matches!(self.0.last(), Some(token) if token.token_type == TokenType::Eof)
+    }
+
+    /// Whether each token starts at or after the end of the one before it
+    pub fn is_monotonic(&self) -> bool {
+        self.0
+            .windows(2)
+            .all(|pair| pair[0].indices.1 <= pair[1].indices.0)
+    }
+
+    /// Line and display column where the last token ends, the end of input for scanned tokens
+    pub fn last_position(&self) -> Option<(usize, usize)> {
+        self.0
+            .last()
+            .map(|token| (token.line_number, token.column_indices.1))
+    }
+
+    /// Token whose byte range contains `offset`
+    ///
+    /// Ranges are half open, so an offset between two adjacent tokens belongs to the second.
+    /// Whitespace, comments and the zero width `Eof` token are not matched. Tokens are assumed to
+    /// be sorted by their indices, as produced by the scanner, so the lookup is a binary search.
+    pub fn token_at(&self, offset: usize) -> Option<&Token> {
+        let index = self.0.partition_point(|token| token.indices.1 <= offset);
+
+        self.0
+            .get(index)
+            .filter(|token| token.indices.0 <= offset && offset < token.indices.1)
+    }
+
+    pub fn into_inner(self) -> Vec<Token> {
+        self.0
+    }
+}
+
+impl From<Vec<Token>> for Tokens {
+    fn from(tokens: Vec<Token>) -> Self {
+        Self(tokens)
+    }
+}
+
+impl FromIterator<Token> for Tokens {
+    fn from_iter<I: IntoIterator<Item = Token>>(tokens: I) -> Self {
+        Self(tokens.into_iter().collect())
+    }
+}
+
+impl IntoIterator for Tokens {
+    type Item = Token;
+    type IntoIter = alloc::vec::IntoIter<Token>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl Deref for Tokens {
+    type Target = [Token];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tokens_tests {
+    use super::*;
+
+    #[test]
+    fn check_invariants() {
+        let tokens = Tokens::from(vec![
+            Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+            Token::new(TokenType::RightBracket, 2, (2, 3), (1, 2)),
+            Token::new(TokenType::Eof, 2, (3, 3), (2, 2)),
+        ]);
+
+        assert!(tokens.ends_with_eof());
+        assert!(tokens.is_monotonic());
+        assert_eq!(Some((2, 2)), tokens.last_position());
+
+        let hand_built = Tokens::from(vec![
+            Token::new(TokenType::True, 1, (1, 5), (2, 6)),
+            Token::new(TokenType::LeftBracket, 1, (0, 1), (1, 2)),
+        ]);
+
+        assert!(!hand_built.ends_with_eof());
+        assert!(!hand_built.is_monotonic());
+        assert!(Tokens::default().is_monotonic());
+        assert_eq!(None, Tokens::default().last_position());
+    }
+
+    #[test]
+    fn find_token_at_offset() {
+        // { "a": 1 }
+        let tokens = Tokens::from(vec![
+            Token::new(TokenType::LeftBrace, 1, (0, 1), (1, 2)),
+            Token::new(TokenType::String, 1, (2, 5), (3, 6)),
+            Token::new(TokenType::Colon, 1, (5, 6), (6, 7)),
+            Token::new(TokenType::Number, 1, (7, 8), (8, 9)),
+            Token::new(TokenType::RightBrace, 1, (9, 10), (10, 11)),
+            Token::new(TokenType::Eof, 1, (10, 10), (11, 11)),
+        ]);
+
+        let token_type_at = |offset| tokens.token_at(offset).map(|token| &token.token_type);
+
+        assert_eq!(Some(&TokenType::LeftBrace), token_type_at(0));
+        assert_eq!(None, token_type_at(1));
+        assert_eq!(Some(&TokenType::String), token_type_at(2));
+        assert_eq!(Some(&TokenType::String), token_type_at(4));
+        assert_eq!(Some(&TokenType::Colon), token_type_at(5));
+        assert_eq!(Some(&TokenType::RightBrace), token_type_at(9));
+        assert_eq!(None, token_type_at(10));
+        assert_eq!(None, Tokens::default().token_at(0));
+    }
+}